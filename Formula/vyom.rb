@@ -12,7 +12,6 @@ class Vyom < Formula
   end
 
   test do
-    # Simple test to verify version
-    assert_match "vyom 0.1.0", shell_output("#{bin}/vyom --version")
+    assert_match "vyom #{version}", shell_output("#{bin}/vyom --version")
   end
 end
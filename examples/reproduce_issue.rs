@@ -20,8 +20,8 @@ pub struct Theme {
     pub cyan: Color,
 }
 
-impl Theme {
-    pub fn default() -> Self {
+impl Default for Theme {
+    fn default() -> Self {
         Self {
             base: Color::Rgb(30, 30, 46), // Catppuccin Base
             // ... truncated for brevity, we know default is Catppuccin
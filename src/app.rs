@@ -1,49 +1,263 @@
-use crate::player::{TrackInfo, PlayerTrait};
+use crate::player::{PlayerError, PlayerState, PlayerSummary, PlayerTrait, Repeat, TrackInfo};
 use crate::lyrics::{LyricLine};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
 use image::DynamicImage;
 use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
 
-use crate::theme::Theme;
+use crate::artwork::{Rendered, Rgb};
+use crate::banned::Banned;
+use crate::keys::Keymap;
+use crate::loved::Loved;
+use crate::output::Device;
+use crate::bookmarks::Bookmarks;
+use crate::clock::PlaybackClock;
+use crate::config::Config;
+use crate::history::{self, Listening, Summary};
+use crate::language;
+use crate::podcast::{self, Chapter};
+use crate::pomodoro::Pomodoro;
+use crate::power;
+use crate::stats;
+use crate::spotify_api::{PlaybackContext, Section};
+use crate::theme::{self, Theme};
+use crate::typing::TypingPractice;
 
+const VISUALIZER_BARS: usize = 48;
 
 
-#[derive(Debug, Clone, PartialEq)]
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LyricsState {
     Idle,
     Loading,
     Loaded(Vec<LyricLine>),
+    /// Plain lyrics without timings, plus the provider name
+    Unsynced(Vec<String>, String),
+    /// Podcast episode description, shown in place of lyrics
+    ShowNotes(Vec<String>),
     Instrumental,
     Failed(String),
     NotFound,
 }
 
+/// `O`: the output devices to choose from
+#[derive(Debug, Clone, Default)]
+pub struct OutputPicker {
+    /// None until they've been listed
+    pub devices: Option<Vec<Device>>,
+    pub selected: usize,
+}
+
+/// Pane the arrow keys and Enter act on. Tab moves along, so everything the
+/// mouse does has a key.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Focus {
+    /// ←/→ seek, ↑/↓ volume, Enter plays/pauses
+    #[default]
+    Card,
+    /// ↑/↓ move through the lines, Enter jumps to the one in the middle
+    Lyrics,
+    /// ↑/↓ pick a track, Enter skips ahead to it
+    Queue,
+    /// The `/` query: typing edits it, ↑/↓ step through the matches
+    Search,
+}
+
+/// `Q`: the tracks up next, in a pane under the lyrics
+#[derive(Debug, Clone, Default)]
+pub struct QueuePane {
+    /// None while they're being asked for; Err says why there are none
+    pub tracks: Option<Result<Vec<TrackInfo>, String>>,
+    pub selected: usize,
+}
+
+/// `/` search over the lyrics
+#[derive(Debug, Clone, Default)]
+pub struct LyricsSearch {
+    pub query: String,
+    /// Still typing the query (keys go to the query, not the bindings)
+    pub editing: bool,
+    /// Indices of matching lines, in order
+    pub matches: Vec<usize>,
+    /// Position in `matches` the view is on
+    pub current: usize,
+}
+
 pub enum ArtworkState {
     Idle,
     Loading,
-    Loaded(DynamicImage),
+    Loaded(Arc<DynamicImage>),
     Failed,
 }
 
+/// A skip or seek the user made, kept so `u` can take it back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Jump {
+    Seek,
+    Next,
+    Prev,
+}
+
+/// Where playback was right before the last jump
+#[derive(Debug, Clone)]
+pub struct UndoPoint {
+    pub track: TrackInfo,
+    pub position_ms: u64,
+    pub jump: Jump,
+}
+
+/// What a click on a footer hint does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FooterAction {
+    Quit,
+    Next,
+    Prev,
+    PlayPause,
+    VolumeUp,
+    VolumeDown,
+}
+
 pub struct App {
     pub theme: Theme,
+    pub config: Config,
 
     pub is_running: bool,
+    // "Quit Vyom?" prompt is up (only with `[quit] confirm`)
+    pub confirm_quit: bool,
     pub track: Option<TrackInfo>,
+    // Every running player (selector strip shows when there's more than one)
+    pub players: Vec<PlayerSummary>,
+    // Set when the last poll failed for a running player (cleared by the next good poll)
+    pub player_error: Option<PlayerError>,
+    // Automation onboarding dialog (shown on PermissionDenied until dismissed)
+    pub permission_dialog_dismissed: bool,
+    pub permission_btn: Rect,
+    // Position/duration of `track`, running on between polls
+    pub clock: PlaybackClock,
+    // Spotify playback context from the Web API (Music reports its own in TrackInfo)
+    pub spotify_context: Option<PlaybackContext>,
+    // Spotify reports a private session (plugins aren't told about tracks)
+    pub private_session: bool,
+    // Chapters of the episode playing, from the timestamps in its show notes
+    pub chapters: Vec<Chapter>,
+    // A Spotify track's private session lookup hasn't answered yet
+    pub private_session_pending: bool,
+    // Shuffle and repeat as the player last reported them (None: it doesn't say)
+    pub shuffle: Option<bool>,
+    pub repeat: Option<Repeat>,
+    // (track URI, in Liked Songs) from the Web API login
+    pub spotify_saved: Option<(String, bool)>,
+    // Spotify audio analysis sections, to tell chorus lyrics from verses
+    pub sections: Vec<Section>,
+    // Spotify's loudness for the track (dB), and the client's normalization
+    // being off, so tracks from elsewhere can be judged against it
+    pub loudness_db: Option<f32>,
+    pub normalization_off: bool,
+    // Lyrics/artwork fetches are failing to connect (retried once it's back)
+    pub network_down: bool,
+    // Tempo of the current track, if known (drives the beat-synced visualizer)
+    pub tempo_bpm: Option<f32>,
     pub lyrics: LyricsState,       // changed from Option<Vec<LyricLine>>
     pub artwork: ArtworkState,
+    // When the current artwork was first drawn (drives the fade-in)
+    pub artwork_loaded_at: Option<Instant>,
+    // Cell size the artwork pane wants this frame, and the half blocks or
+    // image escape rendered for it in the background (with the size they were made for)
+    pub artwork_wanted: Option<(u16, u16)>,
+    pub artwork_rendered: Option<((u16, u16), Rendered)>,
+    // Where this frame wants the graphics-protocol image (None: nowhere, or
+    // covered by a popup); main writes it there after the frame
+    pub artwork_placement: Option<Rect>,
+    // The cover blown up over the whole terminal (`z`, any key closes it)
+    pub lightbox: bool,
+    // Ticks since start, for the loading spinners and shimmer
+    pub ticks: u64,
+    // Gradient ends taken from the artwork (progress bar and visualizer)
+    pub album_colors: Option<(Rgb, Rgb)>,
+    // When a new track came in (drives the info block's slide-in)
+    pub track_changed_at: Option<Instant>,
+    // Visualizer bar heights (0.0..=1.0) and the PRNG state feeding them
+    pub visualizer_bars: Vec<f32>,
+    // Line from Lua `custom_widget` hooks, refreshed every tick
+    pub plugin_widget: Option<String>,
+    visualizer_seed: u32,
     // Manual Scroll State (None = Auto-sync)
     pub lyrics_offset: Option<usize>,
-    pub lyrics_cache: HashMap<String, Vec<LyricLine>>,
+    pub lyrics_search: Option<LyricsSearch>,
+    // Pane the arrows act on; it's only outlined once Tab has been used
+    pub focus: Focus,
+    pub show_focus: bool,
+    // The lyrics pane made it on screen last frame (Tab skips it otherwise)
+    pub lyrics_pane: bool,
+    // Up next, while `Q` has it open
+    pub queue: Option<QueuePane>,
+    // The queue pane made it on screen last frame
+    pub queue_pane: bool,
+    // "Loop this line": (start_ms, end_ms) of the lyric line being repeated
+    pub loop_line: Option<(u64, u64)>,
+    // Intro/outro auto-skip already decided for this track
+    pub intro_checked: bool,
+    pub outro_checked: bool,
+    // Short message on the card border ("skipped intro"), and when it was shown
+    pub toast: Option<(String, Instant)>,
+    // Last skip/seek, undone with `u`
+    pub undo: Option<UndoPoint>,
+    // Party view (`P`): every running player side by side, and their cards' hitboxes
+    pub party_view: bool,
+    pub party_cards: Vec<(Rect, String)>,
+    // Debug overlay (F12) and its latest once-a-second sample
+    pub debug_overlay: bool,
+    pub debug_sample: Option<stats::Sample>,
+    // Where long tracks were left off, and the position offered by "Resume?"
+    pub bookmarks: Bookmarks,
+    pub resume_prompt: Option<u64>,
+    // Listening history: the track being timed, and today's recap card (`R`)
+    // with the day it last opened by itself (local midnight, unix seconds)
+    pub now_listening: Option<Listening>,
+    pub recap: Option<Summary>,
+    pub recap_shown_on: u64,
+    // Tracks banned with `X`
+    pub banned: Banned,
+    // Hearts (`f`), synced with Last.fm / ListenBrainz
+    pub loved: Loved,
+    // `[keys]` rebindings, applied before the event loop matches a key
+    pub keymap: Keymap,
+    // Device the system plays through, when it can be told
+    pub output_device: Option<String>,
+    pub output_picker: Option<OutputPicker>,
+    // Focus timer (`w`), pausing and resuming playback between intervals
+    pub pomodoro: Option<Pomodoro>,
+    // Typing practice mini-game (takes over the lyrics pane and the keyboard)
+    pub typing: Option<TypingPractice>,
+    // Lines visible in the lyrics pane at the last draw (PgUp/PgDn step)
+    pub lyrics_page: usize,
+    pub lyrics_cache: HashMap<String, LyricsState>,
+    // What the lyrics are in (badge on the lyrics card), and what the user reads
+    pub lyrics_language: Option<&'static str>,
+    pub user_language: String,
+    // Translation lines switched off with `t`
+    pub translations_hidden: bool,
     pub last_scroll_time: Option<Instant>,
+    // Reading ahead (`L`): a manual scroll stays put until released
+    pub scroll_lock: bool,
     
     // Button Hit Areas
     pub prev_btn: Rect,
     pub play_btn: Rect,
     pub next_btn: Rect,
     pub progress_rect: Rect,
+    // The visualizer seeks too (mapped onto the gauge's columns)
+    pub visualizer_rect: Rect,
+    // Last mouse position, for the seek preview tooltip
+    pub hover: Option<(u16, u16)>,
+    // Footer hints double as buttons
+    pub footer_btns: Vec<(Rect, FooterAction)>,
+    // The footer's volume hint, where the wheel changes the volume
+    pub volume_rect: Rect,
     // (Rect, Timestamp in ms)
     pub lyrics_hitboxes: Vec<(Rect, u64)>,
     
@@ -55,45 +269,677 @@ pub struct App {
 
 
 impl App {
-    pub fn new(app_show_lyrics: bool, is_tmux: bool) -> Self {
+    pub fn new(app_show_lyrics: bool, is_tmux: bool, config: Config) -> Self {
         let theme = crate::theme::load_current_theme();
+        let pomodoro = config.pomodoro.enabled.then(|| Pomodoro::start(&config.pomodoro));
+        // What translations go into doubles as "the user's language"
+        let user_language = config.translation.target.clone().unwrap_or_else(language::user_language);
         
         Self {
             theme,
+            config,
             is_running: true,
+            confirm_quit: false,
             track: None,
+            players: Vec::new(),
+            player_error: None,
+            permission_dialog_dismissed: false,
+            permission_btn: Rect::default(),
+            clock: PlaybackClock::default(),
+            spotify_context: None,
+            spotify_saved: None,
+            shuffle: None,
+            repeat: None,
+            private_session: false,
+            chapters: Vec::new(),
+            private_session_pending: false,
+            sections: Vec::new(),
+            loudness_db: None,
+            normalization_off: false,
+            tempo_bpm: None,
+            network_down: false,
             lyrics: LyricsState::Idle, // changed
             artwork: ArtworkState::Idle,
+            artwork_loaded_at: None,
+            artwork_wanted: None,
+            artwork_rendered: None,
+            lightbox: false,
+            ticks: 0,
+            artwork_placement: None,
+            track_changed_at: None,
+            album_colors: None,
+            visualizer_bars: vec![0.0; VISUALIZER_BARS],
+            plugin_widget: None,
+            visualizer_seed: 0x9E37_79B9,
             prev_btn: Rect::default(),
             play_btn: Rect::default(),
             next_btn: Rect::default(),
             progress_rect: Rect::default(),
+            visualizer_rect: Rect::default(),
+            volume_rect: Rect::default(),
+            hover: None,
+            footer_btns: Vec::new(),
             lyrics_hitboxes: Vec::new(),
             lyrics_offset: None,
+            lyrics_page: 1,
+            lyrics_search: None,
+            focus: Focus::Card,
+            show_focus: false,
+            lyrics_pane: false,
+            queue: None,
+            queue_pane: false,
+            loop_line: None,
+            undo: None,
+            party_view: false,
+            debug_overlay: false,
+            debug_sample: None,
+            party_cards: Vec::new(),
+            bookmarks: Bookmarks::load(),
+            banned: Banned::load(),
+            loved: Loved::load(),
+            keymap: Keymap::default(),
+            output_device: None,
+            output_picker: None,
+            now_listening: None,
+            recap: None,
+            recap_shown_on: 0,
+            pomodoro,
+            resume_prompt: None,
+            typing: None,
             lyrics_cache: HashMap::new(),
+            lyrics_language: None,
+            user_language,
+            translations_hidden: false,
             last_scroll_time: None,
+            scroll_lock: false,
+            intro_checked: false,
+            outro_checked: false,
+            toast: None,
             app_show_lyrics,
             is_tmux,
         }
     }
 
+    /// Advance the visualizer one frame. While playing, bars ease toward fresh
+    /// random targets; otherwise they ease down to a flat line. With a known
+    /// tempo the targets are scaled by a pulse that peaks on every beat.
+    pub fn tick_visualizer(&mut self) {
+        let is_playing = self.track.as_ref().map(|t| t.state == PlayerState::Playing).unwrap_or(false);
+        let pulse = match self.tempo_bpm {
+            Some(bpm) if bpm > 0.0 => {
+                let beat_ms = 60_000.0 / bpm;
+                let phase = (self.clock.position_ms() as f32 % beat_ms) / beat_ms;
+                // Sharp attack on the beat, quadratic decay until the next one
+                0.35 + 0.65 * (1.0 - phase).powi(2)
+            }
+            _ => 1.0,
+        };
+        // Captured audio when there is some, else bars made up on the beat
+        let live = crate::audio::bands();
+        for i in 0..self.visualizer_bars.len() {
+            let target = if let Some(bands) = &live {
+                bands.get(i).copied().unwrap_or(0.0)
+            } else if is_playing {
+                // xorshift32, plenty for fake audio
+                self.visualizer_seed ^= self.visualizer_seed << 13;
+                self.visualizer_seed ^= self.visualizer_seed >> 17;
+                self.visualizer_seed ^= self.visualizer_seed << 5;
+                (0.15 + (self.visualizer_seed % 1000) as f32 / 1000.0 * 0.85) * pulse
+            } else {
+                0.0
+            };
+            let bar = &mut self.visualizer_bars[i];
+            *bar += (target - *bar) * 0.35;
+        }
+    }
+
+    /// Name of the playlist/album/radio the track plays from, if known
+    pub fn context_name(&self) -> Option<&str> {
+        let track = self.track.as_ref()?;
+        track.context.as_deref()
+            .or(self.spotify_context.as_ref().map(|c| c.name.as_str()))
+    }
+
+    /// Skip decorative animations: asked for in the config, or saving battery
+    pub fn reduced_motion(&self) -> bool {
+        self.config.ui.reduced_motion || power::current().reduced_motion()
+    }
+
+    /// Draw without colors: asked for in the config, or `NO_COLOR` is set
+    pub fn monochrome(&self) -> bool {
+        self.config.ui.monochrome || theme::no_color()
+    }
+
+    /// Whether `ms` falls in a section the audio analysis flags as a chorus
+    pub fn in_chorus(&self, ms: u64) -> bool {
+        self.sections.iter().any(|s| s.chorus && s.start_ms <= ms && ms < s.end_ms)
+    }
+
+    /// Chapter containing the current position
+    pub fn current_chapter(&self) -> Option<&Chapter> {
+        let pos = self.clock.position_ms();
+        self.chapters.iter().rev().find(|c| c.start_ms <= pos)
+    }
+
+    /// Seek target for chapter skip. Backwards restarts the current chapter
+    /// unless we're within its first 3 seconds, like a CD player.
+    pub fn chapter_seek_target(&self, forward: bool) -> Option<u64> {
+        let pos = self.clock.position_ms();
+        if forward {
+            self.chapters.iter().find(|c| c.start_ms > pos).map(|c| c.start_ms)
+        } else {
+            let idx = self.chapters.iter().rposition(|c| c.start_ms <= pos)?;
+            let current = &self.chapters[idx];
+            if pos.saturating_sub(current.start_ms) > 3000 || idx == 0 {
+                Some(current.start_ms)
+            } else {
+                Some(self.chapters[idx - 1].start_ms)
+            }
+        }
+    }
+
+    /// Index of the line being sung right now. Unsynced lyrics have no
+    /// timings, so the position is estimated from track progress.
+    pub fn current_lyric_idx(&self) -> Option<usize> {
+        self.track.as_ref()?;
+        match &self.lyrics {
+            LyricsState::Loaded(lyrics) => Some(current_line_idx(lyrics, self.clock.position_ms())),
+            LyricsState::Unsynced(lines, _) => {
+                let ratio = self.clock.progress();
+                Some(((lines.len() as f64 * ratio) as usize).min(lines.len().saturating_sub(1)))
+            }
+            // Read from the top, scrolling is up to the reader
+            LyricsState::ShowNotes(_) => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Text of the line being sung (synced or estimated), if there are lyrics
+    pub fn current_lyric_text(&self) -> Option<&str> {
+        let idx = self.current_lyric_idx()?;
+        match &self.lyrics {
+            LyricsState::Loaded(lyrics) => lyrics.get(idx).map(|l| l.text.as_str()),
+            LyricsState::Unsynced(lines, _) => lines.get(idx).map(String::as_str),
+            _ => None,
+        }
+    }
+
+    pub fn lyrics_len(&self) -> usize {
+        match &self.lyrics {
+            LyricsState::Loaded(lyrics) => lyrics.len(),
+            LyricsState::Unsynced(lines, _) | LyricsState::ShowNotes(lines) => lines.len(),
+            _ => 0,
+        }
+    }
+
+    /// Manually scroll the lyrics by `delta` lines from wherever the view is now
+    pub fn scroll_lyrics(&mut self, delta: isize) {
+        if let Some(current_idx) = self.current_lyric_idx() {
+            let from = self.lyrics_offset.unwrap_or(current_idx);
+            self.scroll_lyrics_to(from.saturating_add_signed(delta));
+        }
+    }
+
+    /// Manually center the lyrics view on line `idx` (clamped to the lyrics)
+    pub fn scroll_lyrics_to(&mut self, idx: usize) {
+        if self.current_lyric_idx().is_some() {
+            let max_idx = self.lyrics_len().saturating_sub(1);
+            self.lyrics_offset = Some(idx.min(max_idx));
+            self.last_scroll_time = Some(Instant::now());
+        }
+    }
+
+    /// Lock the lyrics where they're scrolled to (the current line if not
+    /// scrolled), or release the lock and let them glide back
+    pub fn toggle_scroll_lock(&mut self) {
+        if self.scroll_lock {
+            self.scroll_lock = false;
+            self.last_scroll_time = None;
+        } else if let Some(current_idx) = self.current_lyric_idx() {
+            self.scroll_lyrics_to(self.lyrics_offset.unwrap_or(current_idx));
+            self.scroll_lock = true;
+        }
+    }
+
+    /// Start or stop repeating the current synced line (it ends where the next one starts)
+    pub fn toggle_loop_line(&mut self) {
+        if self.loop_line.take().is_some() {
+            return;
+        }
+        let (Some(_), LyricsState::Loaded(lyrics)) = (&self.track, &self.lyrics) else { return };
+        let idx = current_line_idx(lyrics, self.clock.position_ms());
+        let Some(line) = lyrics.get(idx) else { return };
+        let end = lyrics.get(idx + 1).map(|l| l.timestamp_ms).unwrap_or(self.clock.duration_ms());
+        if end > line.timestamp_ms {
+            self.loop_line = Some((line.timestamp_ms, end));
+        }
+    }
+
+    /// Text of the synced line being typed in practice mode
+    pub fn typing_target(&self) -> Option<&str> {
+        let (Some(typing), LyricsState::Loaded(lyrics)) = (&self.typing, &self.lyrics) else { return None };
+        lyrics.get(typing.line_idx).map(|l| l.text.as_str())
+    }
+
+    /// Keep the typing target on the sung line, scoring the line it leaves
+    pub fn sync_typing(&mut self) {
+        let Some(current) = self.current_lyric_idx() else { return };
+        let old_target = self.typing_target().unwrap_or_default().to_string();
+        if let Some(typing) = &mut self.typing {
+            if typing.line_idx != current {
+                typing.advance(current, &old_target);
+            }
+        }
+    }
+
+    /// Where to seek to skip the intro, once per track. Waits for the lyrics
+    /// unless the config has an intro end for this track.
+    pub fn intro_skip_target(&mut self) -> Option<u64> {
+        let skip = &self.config.skip;
+        if self.intro_checked || !skip.intro {
+            return None;
+        }
+        let track = self.track.as_ref()?;
+        let configured = skip.tracks.get(&format!("{} - {}", track.artist, track.name)).map(|secs| secs * 1000);
+        let target = match (configured, &self.lyrics) {
+            (Some(ms), _) => Some(ms),
+            (None, LyricsState::Idle | LyricsState::Loading) => return None,
+            (None, LyricsState::Loaded(lyrics)) => lyrics.first()
+                .map(|line| line.timestamp_ms.saturating_sub(skip.lead_in_secs * 1000))
+                .filter(|&ms| ms >= skip.min_secs * 1000),
+            (None, _) => None,
+        };
+        self.intro_checked = true;
+        // Already past it (started late, or seeked) means nothing to skip
+        target.filter(|&ms| self.clock.position_ms() + 1000 < ms)
+    }
+
+    /// Whether only a long outro is left after the last lyric line, once per track
+    pub fn outro_reached(&mut self) -> bool {
+        let skip = &self.config.skip;
+        let LyricsState::Loaded(lyrics) = &self.lyrics else { return false };
+        if self.outro_checked || !skip.outro || self.loop_line.is_some() {
+            return false;
+        }
+        let Some(last) = lyrics.last() else { return false };
+        // Give the last line time to be sung
+        let lyrics_end = last.timestamp_ms + skip.lead_in_secs.max(5) * 1000;
+        let position = self.clock.position_ms();
+        let remaining = self.clock.duration_ms().saturating_sub(lyrics_end);
+        if position < lyrics_end || remaining < skip.min_secs * 1000 {
+            return false;
+        }
+        self.outro_checked = true;
+        true
+    }
+
+    /// Feed a poll into the listening history; the previous track is logged when it changes
+    pub fn log_listening(&mut self, track: &TrackInfo) {
+        if !self.config.history.enabled {
+            return;
+        }
+        match &mut self.now_listening {
+            Some(listening) if listening.is_track(track) => listening.update(track),
+            _ => {
+                if let Some(previous) = self.now_listening.replace(Listening::new(track)) {
+                    previous.finish();
+                }
+            }
+        }
+    }
+
+    /// Summarize today's listens into the recap card
+    pub fn open_recap(&mut self) {
+        self.recap = Some(Summary::of(&history::load_since(history::local_midnight(0))));
+    }
+
+    /// Open the recap by itself once a day when `recap_at` comes around
+    pub fn recap_due(&mut self) -> bool {
+        let Some(at) = self.config.history.recap_at.as_deref().and_then(history::parse_clock) else { return false };
+        let today = history::local_midnight(0);
+        if self.recap_shown_on == today || history::local_minute_of_day() < at {
+            return false;
+        }
+        self.recap_shown_on = today;
+        true
+    }
+
+    /// Heart next to the title: loved here, or in the player (Music's love,
+    /// Spotify's Liked Songs)
+    pub fn is_loved(&self, track: &TrackInfo) -> bool {
+        self.loved.contains(track)
+            || track.favorite == Some(true)
+            || self.spotify_saved.as_ref().is_some_and(|(uri, saved)| *saved && track.id.as_ref() == Some(uri))
+    }
+
+    /// New lyrics (or a new state), and the language they're in
+    pub fn set_lyrics(&mut self, state: LyricsState) {
+        self.lyrics_language = match &state {
+            LyricsState::Loaded(lyrics) => language::detect(lyrics.iter().map(|l| l.text.as_str())),
+            LyricsState::Unsynced(lines, _) => language::detect(lines.iter().map(String::as_str)),
+            _ => None,
+        };
+        self.chapters = match &state {
+            LyricsState::ShowNotes(notes) => podcast::chapters(notes),
+            _ => Vec::new(),
+        };
+        self.lyrics = state;
+    }
+
+    /// Lyrics known to be in the user's language: no translation lines needed
+    pub fn lyrics_in_user_language(&self) -> bool {
+        self.lyrics_language == Some(self.user_language.as_str())
+    }
+
+    /// Hand translations (one per line) to the track's lyrics, shown and cached
+    pub fn attach_translations(&mut self, id: &str, is_current: bool, translations: &[String]) {
+        self.attach_secondary(id, is_current, translations, |line| &mut line.translation);
+        if is_current {
+            self.translations_hidden = false;
+        }
+    }
+
+    /// Same for romanized lines, which don't unhide the layer `t` hid
+    pub fn attach_romanizations(&mut self, id: &str, is_current: bool, romanizations: &[String]) {
+        self.attach_secondary(id, is_current, romanizations, |line| &mut line.romanization);
+    }
+
+    fn attach_secondary(&mut self, id: &str, is_current: bool, texts: &[String], field: fn(&mut LyricLine) -> &mut Option<String>) {
+        let fill = |state: &mut LyricsState| {
+            if let LyricsState::Loaded(lines) = state {
+                if lines.len() == texts.len() {
+                    for (line, text) in lines.iter_mut().zip(texts) {
+                        *field(line) = (!text.is_empty()).then(|| text.clone());
+                    }
+                }
+            }
+        };
+        if let Some(cached) = self.lyrics_cache.get_mut(id) {
+            fill(cached);
+        }
+        if is_current {
+            fill(&mut self.lyrics);
+        }
+    }
+
+    pub fn show_toast(&mut self, message: impl Into<String>) {
+        self.toast = Some((message.into(), Instant::now()));
+    }
+
+    /// Where to seek to keep the loop going, if playback left the looped line
+    pub fn loop_seek_target(&self) -> Option<u64> {
+        let (start, end) = self.loop_line?;
+        let pos = self.clock.position_ms();
+        // A little slack before the start so a seek that lands early doesn't bounce
+        (pos >= end || pos + 1000 < start).then_some(start)
+    }
+
+    /// Case-insensitive match of the search query against every lyric line
+    pub fn update_search_matches(&mut self) {
+        let Some(search) = &mut self.lyrics_search else { return };
+        let query = search.query.to_lowercase();
+        let texts: Vec<&str> = match &self.lyrics {
+            LyricsState::Loaded(lyrics) => lyrics.iter().map(|l| l.text.as_str()).collect(),
+            LyricsState::Unsynced(lines, _) | LyricsState::ShowNotes(lines) => lines.iter().map(String::as_str).collect(),
+            _ => Vec::new(),
+        };
+        search.matches = if query.is_empty() {
+            Vec::new()
+        } else {
+            texts.iter().enumerate()
+                .filter(|(_, t)| t.to_lowercase().contains(&query))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        search.current = 0;
+        if let Some(&first) = search.matches.first() {
+            self.scroll_lyrics_to(first);
+        }
+    }
+
+    /// Move to the next (`1`) or previous (`-1`) match, wrapping around
+    pub fn step_search(&mut self, delta: isize) {
+        let Some(search) = &mut self.lyrics_search else { return };
+        if search.matches.is_empty() {
+            return;
+        }
+        let len = search.matches.len() as isize;
+        search.current = (search.current as isize + delta).rem_euclid(len) as usize;
+        let idx = search.matches[search.current];
+        self.scroll_lyrics_to(idx);
+    }
+
+    /// Timestamp of the match the view is on (synced lyrics only)
+    pub fn search_match_timestamp(&self) -> Option<u64> {
+        let search = self.lyrics_search.as_ref()?;
+        let idx = *search.matches.get(search.current)?;
+        match &self.lyrics {
+            LyricsState::Loaded(lyrics) => lyrics.get(idx).map(|l| l.timestamp_ms),
+            _ => None,
+        }
+    }
+
+    /// The focused pane, falling back to the card when the lyrics pane (or
+    /// the search, or the queue) has gone away since
+    pub fn focused(&self) -> Focus {
+        match self.focus {
+            Focus::Search if self.lyrics_search.is_some() => Focus::Search,
+            Focus::Queue if self.queue_pane => Focus::Queue,
+            Focus::Search | Focus::Lyrics | Focus::Queue if self.lyrics_pane => Focus::Lyrics,
+            _ => Focus::Card,
+        }
+    }
+
+    /// Tab (BackTab with `back`): card → lyrics → queue → search, when each is there
+    pub fn cycle_focus(&mut self, back: bool) {
+        let mut panes = vec![Focus::Card];
+        if self.lyrics_pane {
+            panes.push(Focus::Lyrics);
+        }
+        if self.queue_pane {
+            panes.push(Focus::Queue);
+        }
+        if self.lyrics_pane && self.lyrics_search.is_some() {
+            panes.push(Focus::Search);
+        }
+        let at = panes.iter().position(|p| *p == self.focused()).unwrap_or(0);
+        let len = panes.len();
+        self.focus = panes[if back { (at + len - 1) % len } else { (at + 1) % len }];
+        self.show_focus = true;
+        // The search pane is its query: focusing it means typing
+        if let Some(search) = &mut self.lyrics_search {
+            search.editing = self.focus == Focus::Search;
+        }
+    }
+
+    /// ↑/↓ in the queue pane
+    pub fn step_queue(&mut self, delta: isize) {
+        let Some(queue) = &mut self.queue else { return };
+        let len = match &queue.tracks {
+            Some(Ok(tracks)) => tracks.len(),
+            _ => 0,
+        };
+        queue.selected = queue.selected.saturating_add_signed(delta).min(len.saturating_sub(1));
+    }
+
+    /// Timestamp of the line the lyrics view is centered on (synced lyrics only)
+    pub fn centered_line_timestamp(&self) -> Option<u64> {
+        let idx = self.lyrics_offset.or_else(|| self.current_lyric_idx())?;
+        match &self.lyrics {
+            LyricsState::Loaded(lyrics) => lyrics.get(idx).map(|l| l.timestamp_ms),
+            _ => None,
+        }
+    }
+
+    /// Volume up or down a `volume_step`, saying where it landed
+    pub fn step_volume(&mut self, up: bool, player: &dyn PlayerTrait) {
+        let step = self.config.ui.volume_step;
+        let stepped = if up { player.volume_up(step) } else { player.volume_down(step) };
+        match stepped {
+            Ok(Some(volume)) => {
+                self.show_toast(format!("Volume {}%", volume));
+                if let Some(track) = &mut self.track {
+                    track.volume = Some(volume);
+                }
+            },
+            Ok(None) => self.show_toast(if up { "Volume up" } else { "Volume down" }),
+            Err(e) => self.show_toast(e.to_string()),
+        }
+    }
+
+    /// Player the onboarding dialog is asking permission for, if it's showing
+    pub fn permission_dialog_player(&self) -> Option<String> {
+        match &self.player_error {
+            Some(PlayerError::PermissionDenied(player)) if !self.permission_dialog_dismissed => Some(player.clone()),
+            _ => None,
+        }
+    }
+
+    /// Every way out (q, Ctrl+C, SIGINT, the footer) goes through here
+    pub fn request_quit(&mut self) {
+        if self.config.quit.confirm && !self.confirm_quit {
+            self.confirm_quit = true;
+        } else {
+            self.is_running = false;
+        }
+    }
+
+    /// Long enough to keep a bookmark for (audiobooks, podcasts, mixes)
+    pub fn is_long_track(&self, track: &TrackInfo) -> bool {
+        let min_mins = self.config.bookmarks.min_duration_mins;
+        min_mins > 0 && track.duration_ms >= min_mins * 60_000
+    }
+
+    /// Answer "Resume?": jump to the bookmark, or start over
+    pub fn answer_resume(&mut self, resume: bool, player: &dyn PlayerTrait) {
+        let Some(position_ms) = self.resume_prompt.take() else { return };
+        if resume {
+            self.remember_jump(Jump::Seek);
+            if player.seek(position_ms as f64 / 1000.0).is_ok() {
+                self.clock.seek(position_ms);
+            }
+        }
+    }
+
     pub fn handle_click(&mut self, x: u16, y: u16, player: &dyn PlayerTrait) {
-        if self.prev_btn.contains((x, y).into()) {
+        // Clicking away dismisses the quit and resume prompts
+        if self.confirm_quit {
+            self.confirm_quit = false;
+            return;
+        }
+        if self.resume_prompt.take().is_some() {
+            return;
+        }
+
+        // The onboarding dialog is modal
+        if let Some(name) = self.permission_dialog_player() {
+            if self.permission_btn.contains((x, y).into()) {
+                tokio::task::spawn_blocking(move || crate::player::request_automation_permission(&name));
+            }
+            return;
+        }
+
+        if let Some((_, action)) = self.footer_btns.iter().find(|(r, _)| r.contains((x, y).into())).copied() {
+            match action {
+                FooterAction::Quit => self.request_quit(),
+                FooterAction::Next => { self.remember_jump(Jump::Next); let _ = player.next(); },
+                FooterAction::Prev => { self.remember_jump(Jump::Prev); let _ = player.prev(); },
+                FooterAction::PlayPause => { let _ = player.play_pause(); },
+                FooterAction::VolumeUp => { let _ = player.volume_up(self.config.ui.volume_step); },
+                FooterAction::VolumeDown => { let _ = player.volume_down(self.config.ui.volume_step); },
+            }
+        } else if self.party_view {
+            // The party view covers the card: a click on a player switches to it
+            if let Some((_, name)) = self.party_cards.iter().find(|(r, _)| r.contains((x, y).into())) {
+                crate::player::set_preferred_player(Some(name.clone()));
+                self.party_view = false;
+            }
+        } else if self.prev_btn.contains((x, y).into()) {
+            self.remember_jump(Jump::Prev);
             let _ = player.prev();
         } else if self.play_btn.contains((x, y).into()) {
             let _ = player.play_pause();
         } else if self.next_btn.contains((x, y).into()) {
-             let _ = player.next();
-        } else if self.progress_rect.contains((x, y).into()) {
-            if let Some(track) = &self.track {
-                if track.duration_ms > 0 {
-                     let relative_x = x.saturating_sub(self.progress_rect.x);
-                     let width = self.progress_rect.width.max(1);
-                     let percent = relative_x as f64 / width as f64;
-                     let target_sec = (track.duration_ms as f64 / 1000.0) * percent;
-                     let _ = player.seek(target_sec);
-                }
+            self.remember_jump(Jump::Next);
+            let _ = player.next();
+        } else if let Some(target_ms) = self.seek_target_at(x, y) {
+            self.remember_jump(Jump::Seek);
+            if player.seek(target_ms as f64 / 1000.0).is_ok() {
+                self.clock.seek(target_ms);
             }
         }
     }
+
+    /// Call right before a user skip/seek so `u` can return here
+    pub fn remember_jump(&mut self, jump: Jump) {
+        if let Some(track) = &self.track {
+            self.undo = Some(UndoPoint { track: track.clone(), position_ms: self.clock.position_ms(), jump });
+        }
+    }
+
+    /// Go back to where the last jump started: re-select the track if it
+    /// changed (by id, or by skipping back the way we came), then seek.
+    pub fn undo_jump(&mut self, player: &dyn PlayerTrait) {
+        let Some(point) = self.undo.take() else { return };
+        let same_track = self.track.as_ref()
+            .is_some_and(|t| t.name == point.track.name && t.artist == point.track.artist);
+
+        if !same_track {
+            let reselected = point.track.id.as_deref().is_some_and(|id| player.play_track(id).is_ok());
+            if !reselected {
+                let _ = match point.jump {
+                    Jump::Next => player.prev(),
+                    Jump::Prev => player.next(),
+                    Jump::Seek => Ok(()),
+                };
+            }
+        }
+
+        if player.seek(point.position_ms as f64 / 1000.0).is_ok() && same_track {
+            self.clock.seek(point.position_ms);
+        }
+    }
+
+    /// Where a click at (x, y) on the gauge or visualizer would seek to
+    pub fn seek_target_at(&self, x: u16, y: u16) -> Option<u64> {
+        let on_bar = self.progress_rect.contains((x, y).into())
+            || self.visualizer_rect.contains((x, y).into());
+        let duration_ms = self.clock.duration_ms();
+        if !on_bar || duration_ms == 0 || self.progress_rect.is_empty() {
+            return None;
+        }
+        // The visualizer is wider than the gauge: past either end means start/end
+        let relative_x = x.saturating_sub(self.progress_rect.x).min(self.progress_rect.width);
+        let percent = relative_x as f64 / self.progress_rect.width as f64;
+        Some((duration_ms as f64 * percent) as u64)
+    }
+
+    /// Where a wheel notch at (x, y) seeks to, when it's over the gauge or visualizer
+    pub fn wheel_seek_target(&self, x: u16, y: u16, forward: bool) -> Option<u64> {
+        let over_bar = self.progress_rect.contains((x, y).into())
+            || self.visualizer_rect.contains((x, y).into());
+        if !over_bar {
+            return None;
+        }
+        self.step_seek_target(forward, self.config.ui.wheel_seek_secs)
+    }
+
+    /// `secs` on (`forward`) or back from now, for the wheel and ←/→
+    pub fn step_seek_target(&self, forward: bool, secs: u64) -> Option<u64> {
+        let duration_ms = self.clock.duration_ms();
+        let step_ms = secs * 1000;
+        if duration_ms == 0 || step_ms == 0 {
+            return None;
+        }
+        let position_ms = self.clock.position_ms();
+        Some(if forward { (position_ms + step_ms).min(duration_ms) } else { position_ms.saturating_sub(step_ms) })
+    }
+}
+
+/// Last line whose timestamp has been reached (first line before the song starts).
+pub fn current_line_idx(lyrics: &[LyricLine], position_ms: u64) -> usize {
+    lyrics.iter()
+        .position(|l| l.timestamp_ms > position_ms)
+        .map(|i| i.saturating_sub(1))
+        .unwrap_or(lyrics.len().saturating_sub(1))
 }
@@ -1,13 +1,43 @@
+use image::imageops::FilterType;
 use image::DynamicImage;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use futures::future::{select_ok, BoxFuture, FutureExt};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
 use reqwest::Client;
 use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::base64;
+use crate::config::{ArtworkConfig, ArtworkProtocol};
+use crate::power;
 
 #[derive(Debug, Deserialize)]
 struct ItunesResponse {
     results: Vec<ItunesResult>,
 }
 
+#[derive(Debug, Deserialize)]
+struct MusicBrainzResponse {
+    #[serde(default)]
+    releases: Vec<MusicBrainzRelease>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MusicBrainzRelease {
+    id: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct ItunesResult {
     #[serde(rename = "artworkUrl100")]
@@ -18,6 +48,339 @@ struct ItunesResult {
     artist_name: Option<String>,
 }
 
+/// Cap on the downloaded covers in ~/.cache/vyom/artwork, in bytes (0 = none)
+static CACHE_LIMIT: AtomicU64 = AtomicU64::new(200 * 1024 * 1024);
+
+/// How long a cached iTunes match is trusted before searching again (the
+/// store's artwork changes, and a fuzzy match may have been the wrong album)
+const ITUNES_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// An album iTunes didn't have is looked for again after this
+const ITUNES_MISS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// `[artwork] cache_mb`, at startup and on every config reload
+pub fn set_cache_limit(mb: u64) {
+    CACHE_LIMIT.store(mb * 1024 * 1024, Ordering::Relaxed);
+}
+
+/// Raw downloaded bytes alongside the decoded image
+type FetchedImage = (Vec<u8>, DynamicImage);
+
+pub type Rgb = (u8, u8, u8);
+
+/// Two colors from the artwork for the progress gradient: the most vivid one,
+/// and the vivid one furthest from it. None for grey or very dark covers.
+pub fn accent_colors(img: &DynamicImage) -> Option<(Rgb, Rgb)> {
+    let thumb = img.thumbnail(16, 16).to_rgb8();
+    // Score by saturation × brightness, skipping washed-out and near-black pixels
+    let candidates: Vec<(f32, Rgb)> = thumb.pixels().filter_map(|p| {
+        let (r, g, b) = (p[0], p[1], p[2]);
+        let max = r.max(g).max(b) as f32 / 255.0;
+        let min = r.min(g).min(b) as f32 / 255.0;
+        let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (max >= 0.3 && saturation >= 0.2).then_some((saturation * max, (r, g, b)))
+    }).collect();
+
+    let &(best_score, best) = candidates.iter().max_by(|a, b| a.0.total_cmp(&b.0))?;
+    let distance = |c: Rgb| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(c.0, best.0) + d(c.1, best.1) + d(c.2, best.2)
+    };
+    let other = candidates.iter()
+        .filter(|(score, _)| *score >= best_score * 0.5)
+        .map(|(_, c)| *c)
+        .max_by_key(|c| distance(*c))
+        .unwrap_or(best);
+    Some((other, best))
+}
+
+/// Half-block (`▀`) lines for a `cols`×`rows` cell area: top pixel as the
+/// foreground, bottom pixel as the background, centered vertically. Resizing a
+/// big cover takes a while, so this runs on a blocking thread, not in `draw`.
+pub fn render_lines(img: &DynamicImage, cols: u16, rows: u16) -> Vec<Line<'static>> {
+    // Resize preserving aspect ratio (Triangle for quality)
+    let resized = img.resize(cols as u32, rows as u32 * 2, FilterType::Triangle).to_rgb8();
+    let (width, height) = resized.dimensions();
+    let padding_top = (rows as u32).saturating_sub(height.div_ceil(2)) / 2;
+
+    let mut lines = vec![Line::default(); padding_top as usize];
+    for y in (0..height).step_by(2) {
+        let spans = (0..width).map(|x| {
+            let top = resized.get_pixel(x, y);
+            let bottom = if y + 1 < height { resized.get_pixel(x, y + 1) } else { top };
+            Span::styled("▀", Style::default()
+                .fg(Color::Rgb(top[0], top[1], top[2]))
+                .bg(Color::Rgb(bottom[0], bottom[1], bottom[2])))
+        });
+        lines.push(Line::from(spans.collect::<Vec<_>>()));
+    }
+    lines
+}
+
+// Graphics protocols 🖼️
+// Terminals that can show real images get the cover at full resolution:
+// Kitty's graphics protocol (kitty, Ghostty), iTerm2 inline images (iTerm2,
+// WezTerm) or Sixel (foot, Windows Terminal, ...). The escape is built off-thread
+// with the half blocks' other work and written after the frame (see `main`),
+// over cells ratatui is told to leave alone.
+
+/// Kitty image id; a new cover replaces the old one under the same id
+const KITTY_IMAGE_ID: u32 = 1;
+/// Kitty wants the payload in chunks of at most 4096 bytes
+const KITTY_CHUNK: usize = 4096;
+
+/// What the terminal seems to support, for `auto`. Inside tmux escapes need
+/// passthrough (and tmux support), so it takes an explicit setting there.
+pub fn detect_protocol(configured: ArtworkProtocol, is_tmux: bool) -> ArtworkProtocol {
+    if configured != ArtworkProtocol::Auto {
+        return configured;
+    }
+    if is_tmux {
+        return ArtworkProtocol::Halfblocks;
+    }
+    let env = |name: &str| std::env::var(name).unwrap_or_default();
+    let (term, program) = (env("TERM"), env("TERM_PROGRAM"));
+    if std::env::var("KITTY_WINDOW_ID").is_ok() || term == "xterm-kitty" || term == "xterm-ghostty" || program == "ghostty" {
+        ArtworkProtocol::Kitty
+    } else if program == "iTerm.app" || program == "WezTerm" {
+        ArtworkProtocol::Iterm2
+    } else if term.starts_with("foot") || term.starts_with("mlterm") || std::env::var("WT_SESSION").is_ok() {
+        ArtworkProtocol::Sixel
+    } else {
+        ArtworkProtocol::Halfblocks
+    }
+}
+
+/// Pixels per cell, from the terminal when it says; 8×16 is a common guess
+pub fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.columns > 0 && size.height > 0 && size.rows > 0 => {
+            (size.width as u32 / size.columns as u32, size.height as u32 / size.rows as u32)
+        }
+        _ => (8, 16),
+    }
+}
+
+/// The artwork, ready to draw into a pane
+#[derive(Debug, Clone)]
+pub enum Rendered {
+    /// Half blocks, drawn like any other text
+    Lines(Vec<Line<'static>>),
+    /// An image escape sequence, written straight to the terminal
+    Graphic(Graphic),
+}
+
+#[derive(Debug, Clone)]
+pub struct Graphic {
+    /// Cells the image covers
+    pub cols: u16,
+    pub rows: u16,
+    escape: String,
+}
+
+impl Graphic {
+    /// Where it goes in a pane: centered
+    pub fn area_in(&self, pane: Rect) -> Rect {
+        let (cols, rows) = (self.cols.min(pane.width), self.rows.min(pane.height));
+        Rect::new(pane.x + (pane.width - cols) / 2, pane.y + (pane.height - rows) / 2, cols, rows)
+    }
+
+    /// Draw it with its top-left at `at`, leaving the cursor where it was
+    pub fn escape_at(&self, at: Rect) -> String {
+        format!("\x1b7\x1b[{};{}H{}\x1b8", at.y + 1, at.x + 1, self.escape)
+    }
+}
+
+/// Escape that takes the image down again, where the protocol has one. The
+/// others are part of the text and go when the screen is redrawn.
+pub fn remove_escape(protocol: ArtworkProtocol, is_tmux: bool) -> Option<String> {
+    (protocol == ArtworkProtocol::Kitty)
+        .then(|| passthrough(&format!("\x1b_Ga=d,d=I,i={},q=2\x1b\\", KITTY_IMAGE_ID), is_tmux))
+}
+
+/// Wrap an escape so tmux hands it to the outer terminal (`allow-passthrough on`)
+pub fn passthrough(escape: &str, is_tmux: bool) -> String {
+    if is_tmux {
+        format!("\x1bPtmux;{}\x1b\\", escape.replace('\x1b', "\x1b\x1b"))
+    } else {
+        escape.to_string()
+    }
+}
+
+/// Render for a `cols`×`rows` cell pane with `protocol` (never `auto`).
+/// Runs on a blocking thread like [`render_lines`].
+pub fn render(img: &DynamicImage, cols: u16, rows: u16, protocol: ArtworkProtocol, is_tmux: bool) -> Rendered {
+    if matches!(protocol, ArtworkProtocol::Auto | ArtworkProtocol::Halfblocks) {
+        return Rendered::Lines(render_lines(img, cols, rows));
+    }
+    // Fit the cover into the pane's pixels, then cover whole cells with it
+    let (cell_w, cell_h) = cell_size();
+    let resized = img.resize(cols as u32 * cell_w, rows as u32 * cell_h, FilterType::Triangle).to_rgb8();
+    let (width, height) = resized.dimensions();
+    let covered = (width.div_ceil(cell_w) as u16, height.div_ceil(cell_h) as u16);
+
+    let escape = match protocol {
+        ArtworkProtocol::Sixel => sixel(&resized),
+        _ => {
+            let mut png = Vec::new();
+            if DynamicImage::ImageRgb8(resized).write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png).is_err() {
+                return Rendered::Lines(render_lines(img, cols, rows));
+            }
+            let payload = base64::encode(&png);
+            if protocol == ArtworkProtocol::Kitty {
+                kitty(&payload, covered, is_tmux)
+            } else {
+                let escape = format!(
+                    "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1;doNotMoveCursor=1:{}\x07",
+                    png.len(), covered.0, covered.1, payload,
+                );
+                passthrough(&escape, is_tmux)
+            }
+        }
+    };
+    Rendered::Graphic(Graphic { cols: covered.0, rows: covered.1, escape })
+}
+
+/// Renders kept around, most recent last: toggling panes or the layout, or
+/// going back a track, lands on a size/cover that was already done
+const RENDER_CACHE_SIZE: usize = 8;
+
+/// (cover fingerprint, pane size, render)
+type CachedRender = (u64, (u16, u16), Rendered);
+
+static RENDER_CACHE: Mutex<VecDeque<CachedRender>> = Mutex::new(VecDeque::new());
+
+/// Cheap fingerprint of a cover: its size and a sparse sample of its pixels
+fn image_hash(img: &DynamicImage) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let bytes = img.as_bytes();
+    (img.width(), img.height(), bytes.len()).hash(&mut hasher);
+    bytes.iter().step_by((bytes.len() / 4096).max(1)).for_each(|b| b.hash(&mut hasher));
+    hasher.finish()
+}
+
+/// [`render`], reusing an earlier result for the same cover at the same size.
+/// The protocol and tmux don't change while running, so they aren't part of the key.
+pub fn render_cached(img: &DynamicImage, cols: u16, rows: u16, protocol: ArtworkProtocol, is_tmux: bool) -> Rendered {
+    let key = (image_hash(img), (cols, rows));
+    if let Ok(mut cache) = RENDER_CACHE.lock() {
+        if let Some(entry) = cache.iter().position(|(hash, size, _)| (*hash, *size) == key).and_then(|i| cache.remove(i)) {
+            let rendered = entry.2.clone();
+            cache.push_back(entry);
+            return rendered;
+        }
+    }
+    let rendered = render(img, cols, rows, protocol, is_tmux);
+    if let Ok(mut cache) = RENDER_CACHE.lock() {
+        if cache.len() >= RENDER_CACHE_SIZE {
+            cache.pop_front();
+        }
+        cache.push_back((key.0, key.1, rendered.clone()));
+    }
+    rendered
+}
+
+/// Transmit and place a PNG in one go, stretched over exactly `cols`×`rows` cells
+fn kitty(payload: &str, (cols, rows): (u16, u16), is_tmux: bool) -> String {
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        let escape = if i == 0 {
+            format!("\x1b_Ga=T,f=100,t=d,i={},c={},r={},C=1,q=2,m={};{}\x1b\\", KITTY_IMAGE_ID, cols, rows, more, chunk)
+        } else {
+            format!("\x1b_Gm={};{}\x1b\\", more, chunk)
+        };
+        out.push_str(&passthrough(&escape, is_tmux));
+    }
+    out
+}
+
+/// Sixel with a 6×6×6 color cube: one band of six pixel rows at a time, each
+/// color in the band as its own run-length encoded pass
+fn sixel(img: &image::RgbImage) -> String {
+    let (width, height) = img.dimensions();
+    let level = |c: u8| (c as usize * 5 + 127) / 255;
+    let colors: Vec<u8> = img.pixels().map(|p| (level(p[0]) * 36 + level(p[1]) * 6 + level(p[2])) as u8).collect();
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    for i in 0..216 {
+        let _ = write!(out, "#{};2;{};{};{}", i, i / 36 * 20, i / 6 % 6 * 20, i % 6 * 20);
+    }
+    for top in (0..height).step_by(6) {
+        let band = top..(top + 6).min(height);
+        let mut used = [false; 216];
+        for y in band.clone() {
+            for x in 0..width {
+                used[colors[(y * width + x) as usize] as usize] = true;
+            }
+        }
+        for color in (0..216).filter(|&c| used[c]) {
+            let _ = write!(out, "#{}", color);
+            let sixels = (0..width).map(|x| {
+                let bits = band.clone().fold(0u8, |bits, y| {
+                    bits | (u8::from(colors[(y * width + x) as usize] as usize == color) << (y - top))
+                });
+                (63 + bits) as char
+            });
+            // Runs of the same sixel as "!<count><sixel>"
+            let mut run: Option<(char, usize)> = None;
+            for sixel in sixels.chain(std::iter::once('\0')) {
+                match run {
+                    Some((c, n)) if c == sixel => run = Some((c, n + 1)),
+                    _ => {
+                        match run {
+                            Some((c, n)) if n > 3 => { let _ = write!(out, "!{}{}", n, c); }
+                            Some((c, n)) => out.extend(std::iter::repeat_n(c, n)),
+                            None => {}
+                        }
+                        run = Some((sixel, 1));
+                    }
+                }
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Mark a cached cover as just used: the cache is pruned by modification time
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+/// Delete the least recently used covers until the rest fit under the cap
+fn prune_image_cache(dir: &Path) {
+    let limit = CACHE_LIMIT.load(Ordering::Relaxed);
+    if limit == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let meta = entry.metadata().ok()?;
+            let path = entry.path();
+            path.extension().is_some_and(|ext| ext == "img").then_some(())?;
+            Some((meta.modified().ok()?, meta.len(), path))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort_by_key(|(modified, _, _)| *modified);
+    for (_, len, path) in files {
+        if total <= limit {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total -= len;
+        }
+    }
+}
+
 pub struct ArtworkRenderer {
     client: Client,
 }
@@ -27,12 +390,127 @@ impl ArtworkRenderer {
         Self { client }
     }
 
-    pub async fn fetch_image(&self, url: &str) -> Result<DynamicImage> {
+    /// Download and decode, keeping the original bytes for the disk cache
+    async fn fetch_image(&self, url: &str) -> Result<FetchedImage> {
         let bytes = self.client.get(url).send().await?.bytes().await?;
         let img = image::load_from_memory(&bytes)?;
+        Ok((bytes.to_vec(), img))
+    }
+
+    /// The player's own cover when it has one, else a race between the search
+    /// sources that keeps the first image that downloads and decodes (the losers
+    /// are dropped as soon as one wins). A search can match the wrong album, so
+    /// it never beats an exact URL that works.
+    /// `size` is the requested edge in pixels, see [`pick_resolution`].
+    /// Covers already on disk (see [`Self::prefetch`]) skip the network entirely.
+    pub async fn fetch_racing(&self, player_url: Option<&str>, artist: &str, album: &str, size: u32) -> Result<DynamicImage> {
+        let cache_path = Self::get_image_cache_path(artist, album, size);
+        if let Some(path) = &cache_path {
+            if let Ok(img) = fs::read(path).map_err(anyhow::Error::from).and_then(|b| Ok(image::load_from_memory(&b)?)) {
+                touch(path);
+                return Ok(img);
+            }
+        }
+
+        let exact = match player_url {
+            Some(url) => self.fetch_image(&sized_spotify_url(url, size)).await.ok(),
+            None => None,
+        };
+        let (bytes, img) = match exact {
+            Some(fetched) => fetched,
+            None => self.race_searches(artist, album, size).await?,
+        };
+
+        if let Some(path) = &cache_path {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if fs::write(path, &bytes).is_ok() {
+                if let Some(dir) = path.parent() {
+                    prune_image_cache(dir);
+                }
+            }
+        }
+        Ok(img)
+    }
+
+    /// iTunes against the Cover Art Archive, first image wins
+    async fn race_searches(&self, artist: &str, album: &str, size: u32) -> Result<FetchedImage> {
+        let sources: Vec<BoxFuture<'_, Result<FetchedImage>>> = vec![
+            async move {
+                let url = self.fetch_itunes_artwork(artist, album).await?;
+                self.fetch_image(&sized_itunes_url(&url, size)).await
+            }.boxed(),
+            async move {
+                let url = self.fetch_cover_art_archive(artist, album, size).await?;
+                self.fetch_image(&url).await
+            }.boxed(),
+        ];
+        let (fetched, _losers) = select_ok(sources).await?;
+        Ok(fetched)
+    }
+
+    /// The cover from the disk cache only, for offline mode: the requested size
+    /// if it's there, else the biggest one downloaded for that album
+    pub fn fetch_cached(artist: &str, album: &str, size: u32) -> Result<DynamicImage> {
+        let exact = Self::get_image_cache_path(artist, album, size).context("No home directory")?;
+        let dir = exact.parent().context("No cache directory")?;
+        let prefix = format!("{}_{}_", artist.replace('/', "_"), album.replace('/', "_"));
+        let path = if exact.exists() {
+            exact
+        } else {
+            fs::read_dir(dir)?
+                .filter_map(|entry| {
+                    let name = entry.ok()?.file_name().into_string().ok()?;
+                    let size: u32 = name.strip_prefix(&prefix)?.strip_suffix(".img")?.parse().ok()?;
+                    Some((size, dir.join(name)))
+                })
+                .max_by_key(|(size, _)| *size)
+                .map(|(_, path)| path)
+                .context("No cached artwork")?
+        };
+        let img = image::load_from_memory(&fs::read(&path)?)?;
+        touch(&path);
         Ok(img)
     }
 
+    /// Warm the disk cache for an upcoming track so its cover swaps in instantly
+    pub async fn prefetch(&self, player_url: Option<&str>, artist: &str, album: &str, size: u32) -> Result<()> {
+        let cached = Self::get_image_cache_path(artist, album, size).filter(|p| p.exists());
+        if let Some(path) = &cached {
+            touch(path);
+        } else {
+            self.fetch_racing(player_url, artist, album, size).await?;
+        }
+        Ok(())
+    }
+
+    /// Cover Art Archive front cover, via a MusicBrainz release lookup
+    async fn fetch_cover_art_archive(&self, artist: &str, album: &str, size: u32) -> Result<String> {
+        let query = format!(
+            "release:\"{}\" AND artist:\"{}\"",
+            Self::clean_string(album).replace('"', ""),
+            Self::clean_string(artist).replace('"', ""),
+        );
+        let data: MusicBrainzResponse = self.client.get("https://musicbrainz.org/ws/2/release/")
+            .query(&[("query", query.as_str()), ("fmt", "json"), ("limit", "1")])
+            .send().await?
+            .json().await?;
+
+        match data.releases.first() {
+            Some(release) => {
+                // CAA only serves fixed thumbnail sizes
+                let thumb = match size {
+                    0..=300 => 250,
+                    301..=600 => 500,
+                    _ => 1200,
+                };
+                Ok(format!("https://coverartarchive.org/release/{}/front-{}", release.id, thumb))
+            }
+            None => anyhow::bail!("No MusicBrainz release found"),
+        }
+    }
+
     fn clean_string(s: &str) -> String {
         // Remove content in (), [], and "feat."
         let s = s.to_lowercase();
@@ -42,7 +520,54 @@ impl ArtworkRenderer {
         s.trim().to_string()
     }
 
+    fn get_cache_path(artist: &str, album: &str) -> Option<PathBuf> {
+        let home = std::env::var("HOME").ok()?;
+        let safe_artist = artist.replace('/', "_");
+        let safe_album = album.replace('/', "_");
+        let filename = format!("{}_{}.txt", safe_artist, safe_album);
+
+        let path = Path::new(&home).join(".cache").join("vyom").join("itunes").join(filename);
+        Some(path)
+    }
+
+    /// Downloaded cover bytes, one file per (artist, album, size)
+    fn get_image_cache_path(artist: &str, album: &str, size: u32) -> Option<PathBuf> {
+        let home = std::env::var("HOME").ok()?;
+        let filename = format!("{}_{}_{}.img", artist.replace('/', "_"), album.replace('/', "_"), size);
+        Some(Path::new(&home).join(".cache").join("vyom").join("artwork").join(filename))
+    }
+
+    /// Resolved artwork URL for (artist, album), cached on disk so replaying an
+    /// album doesn't hit the iTunes Search API again. Matches are kept for
+    /// [`ITUNES_TTL`]; an empty file records a search that found nothing, for
+    /// [`ITUNES_MISS_TTL`].
     pub async fn fetch_itunes_artwork(&self, artist: &str, album: &str) -> Result<String> {
+        let cache_path = Self::get_cache_path(artist, album);
+        if let Some(path) = &cache_path {
+            let age = fs::metadata(path).and_then(|meta| meta.modified()).ok()
+                .and_then(|modified| modified.elapsed().ok());
+            if let (Some(age), Ok(url)) = (age, fs::read_to_string(path)) {
+                match url.trim() {
+                    "" if age < ITUNES_MISS_TTL => anyhow::bail!("No results found on iTunes"),
+                    url if !url.is_empty() && age < ITUNES_TTL => return Ok(url.to_string()),
+                    _ => {}
+                }
+            }
+        }
+
+        let url = self.search_itunes_artwork(artist, album).await?;
+
+        if let Some(path) = &cache_path {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let _ = fs::write(path, url.as_deref().unwrap_or_default());
+        }
+        url.context("No results found on iTunes")
+    }
+
+    /// None when iTunes answered but had nothing that matches
+    async fn search_itunes_artwork(&self, artist: &str, album: &str) -> Result<Option<String>> {
         let clean_artist = Self::clean_string(artist);
         let clean_album = Self::clean_string(album);
         let term = format!("{} {}", clean_artist, clean_album);
@@ -50,6 +575,7 @@ impl ArtworkRenderer {
         // Try US first (global default), then IN (for regional content)
         // We can add more regions if needed, or make it configurable later.
         let countries = ["US", "IN"];
+        let mut answered = false;
         
         for country in countries {
             let params = [
@@ -59,6 +585,7 @@ impl ArtworkRenderer {
                 ("country", country),
             ];
             
+            // .query() percent-encodes the term (spaces, &, #, accents...)
             let resp_result = self.client.get("https://itunes.apple.com/search")
                 .query(&params)
                 .send().await;
@@ -66,6 +593,7 @@ impl ArtworkRenderer {
             // If request failed entirely (network), probably fails for all. But let's proceed.
             if let Ok(resp) = resp_result {
                 if let Ok(data) = resp.json::<ItunesResponse>().await {
+                    answered = true;
                     // 2. Filter Candidates (Strict Artist Check)
                     let candidates: Vec<&ItunesResult> = data.results.iter().filter(|r| {
                         if let Some(r_artist) = &r.artist_name {
@@ -90,16 +618,62 @@ impl ArtworkRenderer {
                         }
                     });
                     
-                    let result = best_match.or(candidates.first()).map(|&r| r);
+                    let result = best_match.or(candidates.first()).copied();
                     
                     if let Some(result) = result {
-                        let high_res = result.artwork_url.replace("100x100bb", "600x600bb");
-                        return Ok(high_res);
+                        // Size is applied by the caller (sized_itunes_url)
+                        return Ok(Some(result.artwork_url.clone()));
                     }
                 }
             }
         }
         
-        anyhow::bail!("No results found on iTunes")
+        if !answered {
+            anyhow::bail!("iTunes didn't answer");
+        }
+        Ok(None)
+    }
+}
+
+/// Artwork edge in pixels to request. Half-block rendering needs one pixel per
+/// column and two per row, so a small pane never needs more than 300px; graphics
+/// protocols need the pane's real pixels.
+/// `low_bandwidth` and the battery profile cap it at 300px.
+pub fn pick_resolution(config: &ArtworkConfig, protocol: ArtworkProtocol, cols: u16, rows: u16) -> u32 {
+    let (cell_w, cell_h) = match protocol {
+        ArtworkProtocol::Auto | ArtworkProtocol::Halfblocks => (1, 2),
+        _ => cell_size(),
+    };
+    let size = match config.resolution {
+        Some(px) => px,
+        None => match (cols as u32 * cell_w).max(rows as u32 * cell_h) {
+            0..=300 => 300,
+            301..=600 => 600,
+            _ => 1000,
+        },
+    };
+    if config.low_bandwidth || power::current().small_artwork() { size.min(300) } else { size }
+}
+
+/// iTunes artwork URLs end in `<w>x<h>bb.jpg` and can be resized freely
+fn sized_itunes_url(url: &str, size: u32) -> String {
+    let Some((base, file)) = url.rsplit_once('/') else { return url.to_string() };
+    match file.find("bb") {
+        Some(idx) => format!("{}/{}x{}{}", base, size, size, &file[idx..]),
+        None => url.to_string(),
+    }
+}
+
+/// Spotify CDN URLs encode the size in the image id prefix (300, 640 or ~1000px)
+fn sized_spotify_url(url: &str, size: u32) -> String {
+    const SIZES: [&str; 3] = ["ab67616d00001e02", "ab67616d0000b273", "ab67616d000082c1"];
+    let wanted = match size {
+        0..=300 => SIZES[0],
+        301..=640 => SIZES[1],
+        _ => SIZES[2],
+    };
+    match SIZES.iter().find(|prefix| url.contains(*prefix)) {
+        Some(current) => url.replace(current, wanted),
+        None => url.to_string(),
     }
 }
@@ -0,0 +1,135 @@
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::VisualizerConfig;
+
+// Audio capture 🎧
+// Real frequency bars for the visualizer. A capture tool (PulseAudio/PipeWire's
+// `parec` on the desktop monitor, `ffmpeg` on a loopback device such as
+// BlackHole on macOS) streams raw PCM into a thread that FFTs it into bands.
+// Like the players, it's a subprocess: no audio crates needed.
+
+const SAMPLE_RATE: f32 = 44_100.0;
+/// FFT window (~46ms); a new spectrum every half window
+const WINDOW: usize = 2048;
+const HOP: usize = WINDOW / 2;
+/// Band edges, log-spaced in between
+const LOW_HZ: f32 = 40.0;
+const HIGH_HZ: f32 = 16_000.0;
+/// Loudness mapped onto an empty..full bar
+const FLOOR_DB: f32 = -70.0;
+const CEILING_DB: f32 = -10.0;
+/// Older spectra mean capture stopped: the visualizer goes back to its own bars
+const STALE_AFTER: Duration = Duration::from_millis(500);
+
+/// Latest bands (0.0-1.0) and when they came in
+static SPECTRUM: Mutex<Option<(Instant, Vec<f32>)>> = Mutex::new(None);
+
+/// Capture command for this OS when none is configured. It has to write
+/// signed 16-bit little-endian mono PCM at 44.1 kHz to stdout.
+fn default_command() -> Option<&'static str> {
+    match std::env::consts::OS {
+        "macos" => Some("ffmpeg -loglevel quiet -f avfoundation -i ':BlackHole 2ch' -f s16le -ac 1 -ar 44100 -"),
+        "linux" => Some("parec --device=@DEFAULT_MONITOR@ --format=s16le --rate=44100 --channels=1 --raw"),
+        _ => None,
+    }
+}
+
+/// Start capturing into `bands` bands, when `[visualizer] capture` is on
+pub fn start(config: &VisualizerConfig, bands: usize) {
+    if !config.capture {
+        return;
+    }
+    let Some(command) = config.capture_command.clone().or(default_command().map(str::to_string)) else { return };
+    std::thread::spawn(move || {
+        let (shell, flag) = if cfg!(target_os = "windows") { ("cmd", "/C") } else { ("sh", "-c") };
+        let Ok(mut child) = Command::new(shell)
+            .args([flag, &command])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return;
+        };
+        let Some(mut stdout) = child.stdout.take() else { return };
+        let mut samples = vec![0.0f32; WINDOW];
+        let mut bytes = vec![0u8; HOP * 2];
+        while stdout.read_exact(&mut bytes).is_ok() {
+            // Slide the window along by a hop
+            samples.copy_within(HOP.., 0);
+            for (i, pair) in bytes.chunks_exact(2).enumerate() {
+                samples[WINDOW - HOP + i] = i16::from_le_bytes([pair[0], pair[1]]) as f32 / i16::MAX as f32;
+            }
+            let spectrum = spectrum(&samples, bands);
+            if let Ok(mut latest) = SPECTRUM.lock() {
+                *latest = Some((Instant::now(), spectrum));
+            }
+        }
+        let _ = child.kill();
+    });
+}
+
+/// The live bands, unless capture is off or has stalled
+pub fn bands() -> Option<Vec<f32>> {
+    let latest = SPECTRUM.lock().ok()?;
+    let (at, bands) = latest.as_ref()?;
+    (at.elapsed() < STALE_AFTER).then(|| bands.clone())
+}
+
+/// Hann-windowed FFT of `samples`, the loudest bin of each log-spaced band
+fn spectrum(samples: &[f32], bands: usize) -> Vec<f32> {
+    let n = samples.len();
+    let mut re: Vec<f32> = samples.iter().enumerate()
+        .map(|(i, s)| s * (0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / n as f32).cos()))
+        .collect();
+    let mut im = vec![0.0f32; n];
+    fft(&mut re, &mut im);
+
+    let hz_per_bin = SAMPLE_RATE / n as f32;
+    let edge = |band: usize| LOW_HZ * (HIGH_HZ / LOW_HZ).powf(band as f32 / bands as f32);
+    (0..bands).map(|band| {
+        let from = ((edge(band) / hz_per_bin) as usize).max(1);
+        let to = ((edge(band + 1) / hz_per_bin) as usize).clamp(from + 1, n / 2);
+        let peak = (from..to).map(|k| (re[k] * re[k] + im[k] * im[k]).sqrt()).fold(0.0f32, f32::max);
+        // Normalized so a full-scale sine sits at 0 dB
+        let db = 20.0 * (peak * 4.0 / n as f32).max(1e-9).log10();
+        ((db - FLOOR_DB) / (CEILING_DB - FLOOR_DB)).clamp(0.0, 1.0)
+    }).collect()
+}
+
+/// In-place iterative radix-2 FFT; `re.len()` must be a power of two
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let (tr, ti) = (re[b] * cos - im[b] * sin, re[b] * sin + im[b] * cos);
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
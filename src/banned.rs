@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::player::TrackInfo;
+
+// Skip-and-ban 🚫
+// A dislike button for players that don't have one: `X` skips the track and
+// puts it on a local list, and listed tracks are skipped whenever they come on.
+
+/// Track key ([`TrackInfo::key`]) → "Title · Artist" for people reading the
+/// file, kept in ~/.cache/vyom/banned.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Banned(BTreeMap<String, String>);
+
+fn banned_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache").join("vyom").join("banned.json"))
+}
+
+impl Banned {
+    pub fn load() -> Self {
+        banned_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Some(path) = banned_path() else { return };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    pub fn contains(&self, track: &TrackInfo) -> bool {
+        self.0.contains_key(&track.key())
+    }
+
+    /// Ban `track`, or lift the ban if it's already on the list. Returns
+    /// whether it's banned now.
+    pub fn toggle(&mut self, track: &TrackInfo) -> bool {
+        let key = track.key();
+        if self.0.remove(&key).is_some() {
+            return false;
+        }
+        self.0.insert(key, format!("{} · {}", track.name, track.artist));
+        true
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::player::TrackInfo;
+
+// Bookmarks for long tracks 🔖
+// Audiobooks, podcasts and DJ mixes remember where they were left off. When
+// one starts again from the top, Vyom offers to jump back there.
+
+/// Positions before this aren't worth a bookmark
+const MIN_POSITION_MS: u64 = 60_000;
+/// A track "starts from zero" while it's still in its first seconds
+const START_WINDOW_MS: u64 = 30_000;
+/// This close to the end counts as finished, and the bookmark is dropped
+const FINISHED_WITHIN_MS: u64 = 60_000;
+
+/// Track key ([`TrackInfo::key`]) → position in ms, kept in ~/.cache/vyom/bookmarks.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bookmarks(HashMap<String, u64>);
+
+fn bookmarks_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache").join("vyom").join("bookmarks.json"))
+}
+
+impl Bookmarks {
+    pub fn load() -> Self {
+        bookmarks_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Some(path) = bookmarks_path() else { return };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    /// Remember where `track` is now; finished tracks are forgotten
+    pub fn record(&mut self, track: &TrackInfo) {
+        if track.position_ms + FINISHED_WITHIN_MS >= track.duration_ms {
+            self.0.remove(&track.key());
+        } else if track.position_ms >= MIN_POSITION_MS {
+            self.0.insert(track.key(), track.position_ms);
+        }
+    }
+
+    /// Where to offer resuming `track` from, if it just started over
+    pub fn resume_point(&self, track: &TrackInfo) -> Option<u64> {
+        if track.position_ms >= START_WINDOW_MS {
+            return None;
+        }
+        self.0.get(&track.key()).copied()
+    }
+}
@@ -0,0 +1,173 @@
+use std::time::Instant;
+
+use crate::player::{PlayerState, TrackInfo};
+
+// Playback clock ⏱️
+// Where the player is between polls. The player is only asked a few times a
+// second, so while it plays the clock runs on by itself and the progress bar,
+// lyrics and animations move smoothly. A seek Vyom asked for is trusted until
+// the player reports it, so a poll answered just before the seek landed
+// doesn't yank everything back to the old spot.
+
+/// A reported position this close to a pending seek means it landed
+const SEEK_TOLERANCE_MS: u64 = 1500;
+/// Stop waiting for a seek the player never reported
+const SEEK_TIMEOUT_MS: u128 = 2000;
+
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackClock {
+    position_ms: u64,
+    duration_ms: u64,
+    playing: bool,
+    /// When `position_ms` was true
+    synced_at: Option<Instant>,
+    /// Seek target and when it was asked for, until a poll confirms it
+    seek_intent: Option<(u64, Instant)>,
+}
+
+impl PlaybackClock {
+    /// Take in a fresh poll of the player
+    pub fn sync(&mut self, track: Option<&TrackInfo>) {
+        let Some(track) = track else {
+            *self = Self::default();
+            return;
+        };
+        self.duration_ms = track.duration_ms;
+        self.playing = track.state == PlayerState::Playing;
+
+        if let Some((target, at)) = self.seek_intent {
+            let expected = target + if self.playing { at.elapsed().as_millis() as u64 } else { 0 };
+            let landed = track.position_ms.abs_diff(expected) <= SEEK_TOLERANCE_MS;
+            if !landed && at.elapsed().as_millis() < SEEK_TIMEOUT_MS {
+                return;
+            }
+            self.seek_intent = None;
+        }
+        self.position_ms = track.position_ms;
+        self.synced_at = Some(Instant::now());
+    }
+
+    /// We just asked the player to seek: jump there now, before it confirms
+    pub fn seek(&mut self, position_ms: u64) {
+        let now = Instant::now();
+        self.position_ms = position_ms;
+        self.synced_at = Some(now);
+        self.seek_intent = Some((position_ms, now));
+    }
+
+    /// Position extrapolated from the last poll while playing
+    pub fn position_ms(&self) -> u64 {
+        let elapsed = match (self.playing, self.synced_at) {
+            (true, Some(t)) => t.elapsed().as_millis() as u64,
+            _ => 0,
+        };
+        (self.position_ms + elapsed).min(self.duration_ms.max(self.position_ms))
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.duration_ms
+    }
+
+    /// How far through the track, 0.0..=1.0 (0 when the duration is unknown)
+    pub fn progress(&self) -> f64 {
+        if self.duration_ms == 0 {
+            return 0.0;
+        }
+        (self.position_ms() as f64 / self.duration_ms as f64).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn track(position_ms: u64, state: PlayerState) -> TrackInfo {
+        TrackInfo {
+            name: "Song".into(),
+            artist: "Artist".into(),
+            album: "Album".into(),
+            artwork_url: None,
+            duration_ms: 200_000,
+            position_ms,
+            state,
+            source: "Spotify".into(),
+            id: None,
+            bpm: None,
+            volume: None,
+            context: None,
+            rating: None,
+            play_count: None,
+            last_played: None,
+            favorite: None,
+        }
+    }
+
+    fn ago(ms: u64) -> Instant {
+        Instant::now() - Duration::from_millis(ms)
+    }
+
+    /// Within a little of `expected`, for the time the test itself takes
+    fn near(actual: u64, expected: u64) -> bool {
+        actual.abs_diff(expected) < 100
+    }
+
+    #[test]
+    fn runs_on_between_polls_while_playing() {
+        let mut clock = PlaybackClock::default();
+        clock.sync(Some(&track(10_000, PlayerState::Playing)));
+        clock.synced_at = Some(ago(1_000));
+        assert!(near(clock.position_ms(), 11_000));
+    }
+
+    #[test]
+    fn stands_still_while_paused() {
+        let mut clock = PlaybackClock::default();
+        clock.sync(Some(&track(10_000, PlayerState::Paused)));
+        clock.synced_at = Some(ago(1_000));
+        assert_eq!(clock.position_ms(), 10_000);
+    }
+
+    #[test]
+    fn stops_at_the_end() {
+        let mut clock = PlaybackClock::default();
+        clock.sync(Some(&track(199_500, PlayerState::Playing)));
+        clock.synced_at = Some(ago(5_000));
+        assert_eq!(clock.position_ms(), 200_000);
+        assert_eq!(clock.progress(), 1.0);
+    }
+
+    #[test]
+    fn seek_outlasts_a_stale_poll() {
+        let mut clock = PlaybackClock::default();
+        clock.sync(Some(&track(10_000, PlayerState::Playing)));
+        clock.seek(120_000);
+        clock.sync(Some(&track(10_300, PlayerState::Playing)));
+        assert!(near(clock.position_ms(), 120_000));
+        // The player catches up
+        clock.sync(Some(&track(120_200, PlayerState::Playing)));
+        assert!(clock.seek_intent.is_none());
+        assert!(near(clock.position_ms(), 120_200));
+    }
+
+    #[test]
+    fn seek_the_player_never_reports_times_out() {
+        let mut clock = PlaybackClock::default();
+        clock.sync(Some(&track(10_000, PlayerState::Paused)));
+        clock.seek(120_000);
+        clock.seek_intent = Some((120_000, ago(SEEK_TIMEOUT_MS as u64 + 500)));
+        clock.sync(Some(&track(10_000, PlayerState::Paused)));
+        assert!(clock.seek_intent.is_none());
+        assert_eq!(clock.position_ms(), 10_000);
+    }
+
+    #[test]
+    fn resets_without_a_track() {
+        let mut clock = PlaybackClock::default();
+        clock.sync(Some(&track(10_000, PlayerState::Playing)));
+        clock.sync(None);
+        assert_eq!(clock.position_ms(), 0);
+        assert_eq!(clock.duration_ms(), 0);
+        assert_eq!(clock.progress(), 0.0);
+    }
+}
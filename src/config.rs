@@ -0,0 +1,581 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::keychain;
+use crate::lyrics::Provider;
+
+/// User configuration loaded from `~/.config/vyom/config.toml`.
+/// Every section is optional, missing keys fall back to defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Never touch the network, rely on caches and local files (`--offline`)
+    pub offline: bool,
+    /// How hard Vyom works: tick and poll rates, artwork size, animations
+    pub power_profile: PowerProfile,
+    pub network: NetworkConfig,
+    /// Overrides for the power profile's tick and poll rates
+    pub polling: PollingConfig,
+    pub layout: LayoutConfig,
+    pub ui: UiConfig,
+    /// Action -> key, e.g. `next = "j"` (see `keys.rs` for the action names)
+    pub keys: HashMap<String, String>,
+    pub quit: QuitConfig,
+    pub plugins: PluginsConfig,
+    pub notifications: NotificationsConfig,
+    pub output: OutputConfig,
+    pub bookmarks: BookmarksConfig,
+    pub banned: BannedConfig,
+    pub skip: SkipConfig,
+    pub snapshot: SnapshotConfig,
+    pub history: HistoryConfig,
+    pub lastfm: LastfmConfig,
+    pub listenbrainz: ListenBrainzConfig,
+    pub spotify: SpotifyConfig,
+    pub pomodoro: PomodoroConfig,
+    pub artwork: ArtworkConfig,
+    pub visualizer: VisualizerConfig,
+    pub lyrics: LyricsConfig,
+    pub translation: TranslationConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Skip decorative animations (artwork fade-in, ...)
+    pub reduced_motion: bool,
+    /// Footer template replacing the keybinding hints, e.g.
+    /// `"[blue]{title}[/] {artist} {position}/{duration} vol {volume}"`
+    pub footer: Option<String>,
+    /// Breadcrumb bar above the card: player, playlist, connection status
+    pub header: bool,
+    /// Seconds after a manual lyrics scroll before it glides back to the current line
+    pub scroll_timeout_secs: u64,
+    /// Seconds one mouse wheel notch over the progress bar or visualizer seeks (0: the wheel scrolls the lyrics there too)
+    pub wheel_seek_secs: u64,
+    /// Volume change (percent) per `+`/`-` press or wheel notch over the footer's volume hint
+    pub volume_step: u8,
+    /// Card heights (inner rows) below which the smaller layouts kick in
+    pub cramped_below_rows: u16,
+    pub compact_below_rows: u16,
+    /// Artwork above the info (stacked) or beside it (side)
+    pub card_layout: CardLayout,
+    /// Start with the lyrics pane, as if `--lyrics` was passed
+    pub show_lyrics: bool,
+    /// Parts of the UI that can be switched off (also toggled at runtime)
+    pub show_artwork: bool,
+    pub show_visualizer: bool,
+    pub show_controls: bool,
+    pub show_footer: bool,
+    /// 🎵/🎤/💿 in front of title, artist and album
+    pub info_icons: bool,
+    /// Icon style: emoji, Nerd Font, or plain ASCII
+    pub glyphs: GlyphSet,
+    /// Color the progress bar and visualizer from the artwork instead of the theme
+    pub album_colors: bool,
+    /// Underline under the current lyric line that fills up until the next one
+    pub line_progress: bool,
+    /// No colors, only bold, dim and reverse (also on when `NO_COLOR` is set)
+    pub monochrome: bool,
+    /// Track progress in the terminal's tab/taskbar or badge, where it shows one
+    pub terminal_progress: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GlyphSet {
+    #[default]
+    Emoji,
+    Nerd,
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerProfile {
+    /// Balanced on AC power, battery when unplugged
+    #[default]
+    Auto,
+    Performance,
+    Balanced,
+    Battery,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardLayout {
+    /// Side-by-side when the card is much wider than tall, stacked otherwise
+    #[default]
+    Auto,
+    Stacked,
+    Side,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            reduced_motion: false,
+            footer: None,
+            header: false,
+            scroll_timeout_secs: 3,
+            wheel_seek_secs: 5,
+            volume_step: 10,
+            cramped_below_rows: 30,
+            compact_below_rows: 20,
+            card_layout: CardLayout::Auto,
+            show_lyrics: false,
+            show_artwork: true,
+            show_visualizer: true,
+            show_controls: true,
+            show_footer: true,
+            info_icons: true,
+            glyphs: GlyphSet::Emoji,
+            album_colors: true,
+            line_progress: true,
+            monochrome: false,
+            terminal_progress: true,
+        }
+    }
+}
+
+/// Unset = whatever the power profile says
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PollingConfig {
+    /// Redraw/animation tick
+    pub tick_ms: Option<u64>,
+    /// Asking the player what's playing
+    pub player_ms: Option<u64>,
+    /// Refreshing the list of running players
+    pub players_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    /// Split the tmux window and dock Vyom in the new pane (like `--standalone` when off)
+    pub tmux_split: bool,
+    /// Width of that pane, in percent of the window
+    pub tmux_split_percent: u16,
+    /// Terminal width from which the lyrics go beside the card instead of below
+    pub side_by_side_min_width: u16,
+    /// Card's share of the width when they're side by side
+    pub side_by_side_card_percent: u16,
+    /// Below this terminal height there's no room for lyrics under the card
+    pub stacked_min_height: u16,
+    /// Card height when the lyrics are below it
+    pub stacked_card_rows: u16,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            tmux_split: true,
+            tmux_split_percent: 22,
+            side_by_side_min_width: 90,
+            side_by_side_card_percent: 65,
+            stacked_min_height: 40,
+            stacked_card_rows: 36,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct VisualizerConfig {
+    /// GetSongBPM API key. When a tempo is known the bars pulse on the beat.
+    pub getsongbpm_key: Option<String>,
+    /// Drive the bars from the audio actually playing (needs a capture tool,
+    /// and a loopback device such as BlackHole on macOS)
+    pub capture: bool,
+    /// Shell command printing s16le mono 44.1 kHz PCM to stdout. Unset =
+    /// `parec` on the default monitor (Linux), `ffmpeg` on BlackHole (macOS).
+    pub capture_command: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct QuitConfig {
+    /// Ask before quitting (`q`, Ctrl+C, the footer button)
+    pub confirm: bool,
+    /// Pause the player on the way out
+    pub pause_playback: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct BookmarksConfig {
+    /// Tracks at least this long remember their position and offer to
+    /// resume when they start over (0 turns it off)
+    pub min_duration_mins: u64,
+}
+
+impl Default for BookmarksConfig {
+    fn default() -> Self {
+        Self { min_duration_mins: 20 }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct BannedConfig {
+    /// Skip banned tracks (`X`) whenever they start playing
+    pub auto_skip: bool,
+}
+
+impl Default for BannedConfig {
+    fn default() -> Self {
+        Self { auto_skip: true }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Log listens to ~/.cache/vyom/history.jsonl (recap and `vyom report` need it)
+    pub enabled: bool,
+    /// Show today's recap once the clock passes this ("22:00"); `R` opens it anytime
+    pub recap_at: Option<String>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self { enabled: true, recap_at: None }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    /// Markdown file `e` appends now-playing notes to (`~/` allowed).
+    /// Unset = copy them to the clipboard.
+    pub file: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SkipConfig {
+    /// Seek past long instrumental intros to just before the first lyric line
+    pub intro: bool,
+    /// Go to the next track once the lyrics are over and a long outro is left
+    pub outro: bool,
+    /// Intros and outros shorter than this are left alone
+    pub min_secs: u64,
+    /// Land this long before the first line
+    pub lead_in_secs: u64,
+    /// Where the intro ends, in seconds, for tracks keyed "Artist - Title".
+    /// Used instead of the lyrics when set, and works without any.
+    pub tracks: HashMap<String, u64>,
+}
+
+impl Default for SkipConfig {
+    fn default() -> Self {
+        Self {
+            intro: false,
+            outro: false,
+            min_secs: 15,
+            lead_in_secs: 3,
+            tracks: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct PomodoroConfig {
+    /// Start the focus timer with Vyom (`w` starts/stops it either way)
+    pub enabled: bool,
+    pub work_mins: u64,
+    pub break_mins: u64,
+    pub long_break_mins: u64,
+    /// Every this many work intervals the break is a long one
+    pub long_break_every: u32,
+    /// Which intervals have music: the other kind pauses the player
+    pub music_during: MusicDuring,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MusicDuring {
+    #[default]
+    Work,
+    Break,
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            work_mins: 25,
+            break_mins: 5,
+            long_break_mins: 15,
+            long_break_every: 4,
+            music_during: MusicDuring::Work,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PluginsConfig {
+    /// Call `on_track_change` even in a Spotify private session
+    /// (off so scrobblers and presence plugins stay quiet)
+    pub notify_private: bool,
+    /// Plugins (by file name, e.g. "notify" for notify.lua) whose
+    /// `on_track_change` is skipped while Focus / Do Not Disturb is on
+    pub quiet_during_focus: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Desktop notification with Play/Pause and Next buttons on every new
+    /// track (Linux, D-Bus). Quiet during Focus / Do Not Disturb.
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Pause when the output device changes mid-song (headphones unplugged
+    /// or out of range, so it doesn't carry on through the speakers)
+    pub pause_on_change: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ArtworkConfig {
+    /// Requested edge in pixels (e.g. 300, 600, 1000). Unset = pick from pane size.
+    pub resolution: Option<u32>,
+    /// Never download more than 300px
+    pub low_bandwidth: bool,
+    /// How the cover is drawn: a terminal graphics protocol or half blocks
+    pub protocol: ArtworkProtocol,
+    /// Downloaded covers kept on disk, in MB; the least recently shown go
+    /// first (0 keeps everything)
+    pub cache_mb: u64,
+}
+
+impl Default for ArtworkConfig {
+    fn default() -> Self {
+        Self { resolution: None, low_bandwidth: false, protocol: ArtworkProtocol::Auto, cache_mb: 200 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtworkProtocol {
+    /// Whatever the terminal looks like it supports (half blocks inside tmux)
+    #[default]
+    Auto,
+    Kitty,
+    Iterm2,
+    Sixel,
+    Halfblocks,
+}
+
+/// A Last.fm API account (https://www.last.fm/api/account/create) plus the
+/// session `vyom auth lastfm` stores. Loved tracks sync once all three are set.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct LastfmConfig {
+    pub api_key: Option<String>,
+    pub api_secret: Option<String>,
+    pub session_key: Option<String>,
+    /// Scrobble plays to the account (once it's set up)
+    pub scrobble: bool,
+}
+
+impl Default for LastfmConfig {
+    fn default() -> Self {
+        Self { api_key: None, api_secret: None, session_key: None, scrobble: true }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ListenBrainzConfig {
+    /// User token from https://listenbrainz.org/settings/. Loved tracks sync
+    /// as recording feedback when set.
+    pub token: Option<String>,
+    /// Submit listens with the token
+    pub scrobble: bool,
+}
+
+impl Default for ListenBrainzConfig {
+    fn default() -> Self {
+        Self { token: None, scrobble: true }
+    }
+}
+
+/// An app of your own at https://developer.spotify.com/dashboard, with
+/// `http://127.0.0.1:8898/callback` as a redirect URI, for the Web API login
+/// (`vyom auth spotify`): library saves, shuffle, repeat, the queue and
+/// Spotify Connect devices.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SpotifyConfig {
+    pub client_id: Option<String>,
+    /// Port of the redirect URI registered with the app
+    pub redirect_port: u16,
+    /// Stored by `vyom auth spotify`
+    pub refresh_token: Option<String>,
+}
+
+impl Default for SpotifyConfig {
+    fn default() -> Self {
+        Self { client_id: None, redirect_port: 8898, refresh_token: None }
+    }
+}
+
+/// Applied to the shared HTTP client, so it covers every outbound request.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// `http://`, `https://` or `socks5://` proxy URL. Standard proxy env vars
+    /// are still honored when unset.
+    pub proxy: Option<String>,
+    pub connect_timeout_secs: u64,
+    /// Whole-request timeout (connect + read)
+    pub timeout_secs: u64,
+    /// Extra PEM root certificate, for TLS-intercepting corporate proxies
+    pub ca_cert: Option<String>,
+    /// Skip certificate validation entirely. Last resort.
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            connect_timeout_secs: 5,
+            timeout_secs: 15,
+            ca_cert: None,
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct LyricsConfig {
+    /// Provider order per player source, e.g. `Music = ["local", "lrclib"]`.
+    /// Sources without an entry use the automatic order.
+    pub providers: HashMap<String, Vec<Provider>>,
+    /// Folder searched by the `local` provider for `.lrc` files
+    pub local_dir: Option<String>,
+    /// Musixmatch user token. The provider is skipped when unset.
+    pub musixmatch_token: Option<String>,
+    /// Genius API token. Optional, the public search is used without one.
+    pub genius_token: Option<String>,
+    /// `sp_dc` cookie from open.spotify.com. Opt-in, enables Spotify's own lyrics.
+    pub spotify_sp_dc: Option<String>,
+}
+
+impl Default for LyricsConfig {
+    fn default() -> Self {
+        Self {
+            providers: HashMap::new(),
+            local_dir: Some("~/Music/Lyrics".to_string()),
+            musixmatch_token: None,
+            genius_token: None,
+            spotify_sp_dc: None,
+        }
+    }
+}
+
+/// Machine translation of the lyrics, fetched when `t` asks for it
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TranslationConfig {
+    pub service: TranslationService,
+    /// Endpoint. Unset: DeepL's own (Free or Pro, going by the key);
+    /// LibreTranslate needs one (your instance, or https://libretranslate.com)
+    pub url: Option<String>,
+    /// Needed by DeepL and most public LibreTranslate instances
+    pub api_key: Option<String>,
+    /// Language to translate into (e.g. "de"). Unset = the locale's.
+    pub target: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslationService {
+    #[default]
+    Deepl,
+    Libretranslate,
+}
+
+/// Expand a leading `~/` to `$HOME`.
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".to_string())).join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+impl Config {
+    /// Credentials missing from the file are looked up in the OS keychain (`vyom auth set`),
+    /// once per process: reloads reuse the first answer.
+    fn fill_from_keychain(&mut self) {
+        let slots = [
+            (&mut self.lyrics.musixmatch_token, "musixmatch_token"),
+            (&mut self.lyrics.genius_token, "genius_token"),
+            (&mut self.lyrics.spotify_sp_dc, "spotify_sp_dc"),
+            (&mut self.visualizer.getsongbpm_key, "getsongbpm_key"),
+            (&mut self.lastfm.api_key, "lastfm_api_key"),
+            (&mut self.lastfm.api_secret, "lastfm_api_secret"),
+            (&mut self.lastfm.session_key, "lastfm_session_key"),
+            (&mut self.listenbrainz.token, "listenbrainz_token"),
+            (&mut self.spotify.refresh_token, "spotify_refresh_token"),
+            (&mut self.translation.api_key, "translation_api_key"),
+        ];
+        for (slot, account) in slots {
+            if slot.is_none() {
+                *slot = keychain::get_cached(account);
+            }
+        }
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(&home).join(".config/vyom/config.toml")
+}
+
+pub fn load_config() -> Config {
+    let mut config = load_settings();
+    config.fill_from_keychain();
+    config
+}
+
+/// The file alone, without the keychain lookups (each one runs a program), for
+/// commands that never use the credentials
+pub fn load_settings() -> Config {
+    let path = config_path();
+    match fs::read_to_string(&path) {
+        Ok(content) => toml::from_str::<Config>(&content).unwrap_or_else(|e| {
+            eprintln!("Invalid config at {:?}: {}", path, e);
+            Config::default()
+        }),
+        Err(_) => Config::default(),
+    }
+}
+
+/// The config again after an edit. Unlike at startup, a broken file is an
+/// error (the running config stays) instead of the defaults.
+pub fn reload_config() -> Result<Config, String> {
+    let mut config = parse_config()?;
+    config.fill_from_keychain();
+    Ok(config)
+}
+
+/// The file alone, failing when it doesn't parse
+pub fn parse_config() -> Result<Config, String> {
+    match fs::read_to_string(config_path()) {
+        Ok(content) => toml::from_str::<Config>(&content).map_err(|e| e.message().to_string()),
+        Err(_) => Ok(Config::default()),
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::Result;
+use reqwest::Client;
+use std::process::{Command, Output, Stdio};
+use std::time::Instant;
+
+use crate::artwork;
+use crate::config::{self, ArtworkProtocol, Config};
+
+// Doctor 🩺
+// `vyom doctor` goes through what Vyom needs from the machine: a config that
+// parses, a way to talk to the player (osascript and the Automation permission
+// on macOS, PowerShell on Windows), tmux, the terminal's image support and
+// the lyrics/artwork servers. Each problem comes with what to do about it,
+// and the exit status says whether there was one.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    Ok,
+    /// Works, but not as well as it could
+    Warn,
+    Fail,
+    /// Couldn't be checked right now (e.g. the player isn't running)
+    Skip,
+}
+
+struct Check {
+    status: Status,
+    name: &'static str,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl Check {
+    fn new(status: Status, name: &'static str, detail: impl Into<String>) -> Self {
+        Self { status, name, detail: detail.into(), fix: None }
+    }
+
+    fn fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Option<Output> {
+    Command::new(program).args(args).stdin(Stdio::null()).output().ok()
+}
+
+pub async fn run_cli(client: &Client) -> Result<()> {
+    println!("vyom {} ({}-{})", env!("CARGO_PKG_VERSION"), std::env::consts::ARCH, std::env::consts::OS);
+    let config = config::parse_config();
+    let is_tmux = std::env::var("TMUX").is_ok();
+
+    let mut checks = vec![config_check(&config)];
+    checks.extend(player_checks());
+    checks.extend(tmux_checks(is_tmux, config.as_ref().ok()));
+    checks.push(image_check(is_tmux, config.as_ref().ok()));
+    for (name, url) in [("lrclib", "https://lrclib.net"), ("iTunes", "https://itunes.apple.com/search?term=vyom&limit=1")] {
+        checks.push(reachable(client, name, url).await);
+    }
+
+    for check in &checks {
+        let mark = match check.status {
+            Status::Ok => "✔",
+            Status::Warn => "⚠",
+            Status::Fail => "✘",
+            Status::Skip => "-",
+        };
+        println!("{} {:<11} {}", mark, check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            println!("  {:<11} → {}", "", fix);
+        }
+    }
+    let failed = checks.iter().filter(|check| check.status == Status::Fail).count();
+    if failed > 0 {
+        anyhow::bail!("{} problem{} found", failed, if failed == 1 { "" } else { "s" });
+    }
+    println!("No problems found");
+    Ok(())
+}
+
+fn config_check(config: &Result<Config, String>) -> Check {
+    let path = config::config_path();
+    match config {
+        _ if !path.exists() => Check::new(Status::Ok, "Config", format!("none at {}, using the defaults", path.display())),
+        Ok(_) => Check::new(Status::Ok, "Config", path.display().to_string()),
+        Err(e) => Check::new(Status::Fail, "Config", format!("{}: {}", path.display(), e))
+            .fix("fix that entry; until then Vyom starts with the defaults"),
+    }
+}
+
+/// osascript, and whether this terminal may control each player that's running
+#[cfg(target_os = "macos")]
+fn player_checks() -> Vec<Check> {
+    let osascript = |script: &str| run("osascript", &["-e", script]);
+    if !osascript("return 1").is_some_and(|o| o.status.success()) {
+        return vec![Check::new(Status::Fail, "osascript", "can't run osascript")
+            .fix("it ships with macOS in /usr/bin; make sure that is on your PATH")];
+    }
+    let mut checks = vec![Check::new(Status::Ok, "osascript", "works")];
+    for app in ["Spotify", "Music"] {
+        let running = osascript(&format!("return (application \"{}\" is running) as text", app))
+            .is_some_and(|o| String::from_utf8_lossy(&o.stdout).trim() == "true");
+        if !running {
+            checks.push(Check::new(Status::Skip, "Automation", format!("{} isn't running, start it to check", app)));
+            continue;
+        }
+        // Any Apple event tells: -1743 is "not authorized to send Apple events"
+        let asked = osascript(&format!("tell application \"{}\" to get player state", app));
+        let denied = asked.is_none_or(|o| String::from_utf8_lossy(&o.stderr).contains("-1743"));
+        checks.push(if denied {
+            Check::new(Status::Fail, "Automation", format!("not allowed to control {}", app))
+                .fix(format!("System Settings › Privacy & Security › Automation: let your terminal control {}", app))
+        } else {
+            Check::new(Status::Ok, "Automation", format!("allowed to control {}", app))
+        });
+    }
+    checks
+}
+
+/// The Windows player talks to WinRT through PowerShell
+#[cfg(target_os = "windows")]
+fn player_checks() -> Vec<Check> {
+    match run("powershell", &["-NoProfile", "-Command", "$PSVersionTable.PSVersion.ToString()"]) {
+        Some(o) if o.status.success() => {
+            vec![Check::new(Status::Ok, "PowerShell", String::from_utf8_lossy(&o.stdout).trim().to_string())]
+        }
+        _ => vec![Check::new(Status::Fail, "PowerShell", "can't run powershell")
+            .fix("Vyom reads the player through Windows PowerShell; make sure powershell.exe is on your PATH")],
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn player_checks() -> Vec<Check> {
+    vec![Check::new(Status::Warn, "Player", format!("no player support on {} yet", std::env::consts::OS))
+        .fix("Vyom will show nothing playing; lyrics, status and the rest need a player on macOS or Windows")]
+}
+
+/// "tmux 3.3a" / "tmux next-3.4" -> (3, 3) / (3, 4)
+fn tmux_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.split_whitespace().nth(1)?;
+    let version = version.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().unwrap_or("0").trim_end_matches(|c: char| !c.is_ascii_digit()).parse().ok()?;
+    Some((major, minor))
+}
+
+/// tmux itself, and the passthrough a graphics protocol needs inside it
+fn tmux_checks(is_tmux: bool, config: Option<&Config>) -> Vec<Check> {
+    let Some(output) = run("tmux", &["-V"]).filter(|o| o.status.success()) else {
+        return vec![Check::new(Status::Skip, "tmux", "not installed (only needed for the split layout)")];
+    };
+    let output = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let Some(version) = tmux_version(&output) else {
+        return vec![Check::new(Status::Warn, "tmux", format!("couldn't read the version from {:?}", output))];
+    };
+    let graphics = config.is_some_and(|c| !matches!(c.artwork.protocol, ArtworkProtocol::Auto | ArtworkProtocol::Halfblocks));
+    if version < (3, 3) {
+        let check = Check::new(Status::Warn, "tmux", format!("{} (inside tmux covers are half blocks)", output));
+        return vec![if graphics { check.fix("tmux 3.3 or newer passes images through to the terminal") } else { check }];
+    }
+    let mut checks = vec![Check::new(Status::Ok, "tmux", output)];
+    if is_tmux && graphics {
+        let passthrough = run("tmux", &["show", "-gv", "allow-passthrough"])
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .unwrap_or_default();
+        checks.push(if passthrough == "on" || passthrough == "all" {
+            Check::new(Status::Ok, "Passthrough", "allow-passthrough is on")
+        } else {
+            Check::new(Status::Fail, "Passthrough", "allow-passthrough is off, covers won't show")
+                .fix("add `set -g allow-passthrough on` to ~/.tmux.conf")
+        });
+    }
+    checks
+}
+
+/// The protocol covers would be drawn with here
+fn image_check(is_tmux: bool, config: Option<&Config>) -> Check {
+    let configured = config.map(|c| c.artwork.protocol).unwrap_or_default();
+    let protocol = artwork::detect_protocol(configured, is_tmux);
+    let name = format!("{:?}", protocol).to_lowercase();
+    let env = |name: &str| std::env::var(name).unwrap_or_default();
+    let terminal = match env("TERM_PROGRAM") {
+        program if program.is_empty() => env("TERM"),
+        program => program,
+    };
+    if protocol != ArtworkProtocol::Halfblocks {
+        return Check::new(Status::Ok, "Images", format!("{} ({})", name, terminal));
+    }
+    let check = Check::new(Status::Warn, "Images", format!("half blocks ({})", terminal));
+    if is_tmux && configured == ArtworkProtocol::Auto {
+        check.fix("if the terminal outside tmux shows images, set `[artwork] protocol` to it (kitty, iterm2, sixel)")
+    } else if configured == ArtworkProtocol::Auto {
+        check.fix("kitty, Ghostty, iTerm2, WezTerm and foot show full covers; or set `[artwork] protocol` if yours has one")
+    } else {
+        check
+    }
+}
+
+/// One request through the configured client (proxy, certificates), timed
+async fn reachable(client: &Client, name: &'static str, url: &str) -> Check {
+    let started = Instant::now();
+    match client.get(url).send().await {
+        Ok(resp) if resp.status().is_success() => {
+            Check::new(Status::Ok, name, format!("reachable ({} ms)", started.elapsed().as_millis()))
+        }
+        Ok(resp) => Check::new(Status::Warn, name, format!("answered {}", resp.status()))
+            .fix("the service may be down for now; cached lyrics and artwork still work"),
+        Err(e) => {
+            let cause = std::iter::successors(Some(&e as &dyn std::error::Error), |e| e.source())
+                .last()
+                .map(|e| e.to_string())
+                .unwrap_or_default();
+            Check::new(Status::Fail, name, format!("unreachable: {}", cause))
+                .fix("check the connection or `[network] proxy`; `vyom --offline` works from the cache")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_tmux_versions() {
+        assert_eq!(tmux_version("tmux 3.3a"), Some((3, 3)));
+        assert_eq!(tmux_version("tmux next-3.4"), Some((3, 4)));
+        assert_eq!(tmux_version("tmux 2.9"), Some((2, 9)));
+        assert_eq!(tmux_version("tmux 3"), Some((3, 0)));
+    }
+
+    #[test]
+    fn unreadable_tmux_versions() {
+        assert_eq!(tmux_version("tmux master"), None);
+        assert_eq!(tmux_version("tmux"), None);
+        assert_eq!(tmux_version(""), None);
+    }
+}
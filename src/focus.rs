@@ -0,0 +1,35 @@
+#[cfg(not(target_os = "macos"))]
+use std::process::{Command, Stdio};
+
+// Focus / Do Not Disturb 🌙
+// Checked when a track changes, so plugins that notify or post a status
+// (listed in `[plugins] quiet_during_focus`) stay quiet while it's on.
+
+// --- macOS: Focus assertions written by the Do Not Disturb daemon 🍎 ---
+
+/// Any Focus mode (Do Not Disturb, Work, Sleep...) is on. The file needs
+/// Full Disk Access for the terminal on recent macOS; without it this says no.
+#[cfg(target_os = "macos")]
+pub fn is_active() -> bool {
+    let Ok(home) = std::env::var("HOME") else { return false };
+    let path = std::path::Path::new(&home).join("Library/DoNotDisturb/DB/Assertions.json");
+    let Ok(data) = std::fs::read_to_string(path) else { return false };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&data) else { return false };
+    json.pointer("/data/0/storeAssertionRecords")
+        .and_then(|records| records.as_array())
+        .is_some_and(|records| !records.is_empty())
+}
+
+// --- Linux: GNOME's "Do Not Disturb" switch via `gsettings` 🐧 ---
+
+#[cfg(not(target_os = "macos"))]
+pub fn is_active() -> bool {
+    let Ok(output) = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .stderr(Stdio::null())
+        .output()
+    else {
+        return false;
+    };
+    output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "false"
+}
@@ -0,0 +1,146 @@
+use crate::config::GlyphSet;
+use crate::player::PlayerState;
+
+/// Icons drawn in the card, header and lyrics labels.
+/// Emoji render at different widths across terminals, so there are
+/// Nerd Font and plain ASCII alternatives.
+pub struct Glyphs {
+    pub title: &'static str,
+    pub artist: &'static str,
+    pub album: &'static str,
+    pub context: &'static str,
+    pub prev: &'static str,
+    pub play: &'static str,
+    pub pause: &'static str,
+    pub next: &'static str,
+    pub stop: &'static str,
+    pub spotify: &'static str,
+    pub music: &'static str,
+    pub looping: &'static str,
+    pub typing: &'static str,
+    pub warning: &'static str,
+    pub private: &'static str,
+    pub battery: &'static str,
+    pub scrobble: &'static str,
+    pub lock: &'static str,
+    pub pomodoro: &'static str,
+    pub loved: &'static str,
+    pub output: &'static str,
+    /// Rating stars, given and not
+    pub star: &'static str,
+    pub star_empty: &'static str,
+    /// Shuffle and repeat (all, one) beside the transport buttons
+    pub shuffle: &'static str,
+    pub repeat: &'static str,
+    pub repeat_one: &'static str,
+    /// Frames of the loading spinner
+    pub spinner: &'static [&'static str],
+}
+
+const EMOJI: Glyphs = Glyphs {
+    title: "🎵",
+    artist: "🎤",
+    album: "💿",
+    context: "📜",
+    prev: "⏮",
+    play: "▶",
+    pause: "⏸",
+    next: "⏭",
+    stop: "■",
+    spotify: "🟢",
+    music: "🍎",
+    looping: "🔁",
+    typing: "⌨",
+    warning: "⚠",
+    private: "🕶",
+    battery: "🔋",
+    scrobble: "📡",
+    lock: "🔒",
+    pomodoro: "🍅",
+    loved: "♥",
+    output: "🎧",
+    star: "★",
+    star_empty: "☆",
+    shuffle: "🔀",
+    repeat: "🔁",
+    repeat_one: "🔂",
+    spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+};
+
+const NERD: Glyphs = Glyphs {
+    title: "\u{f001}",     // nf-fa-music
+    artist: "\u{f130}",    // nf-fa-microphone
+    album: "\u{f0025}",    // nf-md-album
+    context: "\u{f0cb9}",  // nf-md-playlist_music
+    prev: "\u{f048}",      // nf-fa-step_backward
+    play: "\u{f04b}",      // nf-fa-play
+    pause: "\u{f04c}",     // nf-fa-pause
+    next: "\u{f051}",      // nf-fa-step_forward
+    stop: "\u{f04d}",      // nf-fa-stop
+    spotify: "\u{f1bc}",   // nf-fa-spotify
+    music: "\u{f179}",     // nf-fa-apple
+    looping: "\u{f01e}",   // nf-fa-repeat
+    typing: "\u{f11c}",    // nf-fa-keyboard_o
+    warning: "\u{f071}",   // nf-fa-warning
+    private: "\u{f21b}",   // nf-fa-user_secret
+    battery: "\u{f243}",   // nf-fa-battery_quarter
+    scrobble: "\u{f202}",  // nf-fa-lastfm
+    lock: "\u{f023}",      // nf-fa-lock
+    pomodoro: "\u{f051b}", // nf-md-timer_outline
+    loved: "\u{f004}",    // nf-fa-heart
+    output: "\u{f025}",   // nf-fa-headphones
+    star: "\u{f005}",     // nf-fa-star
+    star_empty: "\u{f006}", // nf-fa-star_o
+    shuffle: "\u{f074}",   // nf-fa-random
+    repeat: "\u{f0456}",   // nf-md-repeat
+    repeat_one: "\u{f0458}", // nf-md-repeat_once
+    spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+};
+
+const ASCII: Glyphs = Glyphs {
+    title: ">",
+    artist: "by",
+    album: "on",
+    context: "from",
+    prev: "|<",
+    play: ">",
+    pause: "||",
+    next: ">|",
+    stop: "[]",
+    spotify: "S",
+    music: "M",
+    looping: "(loop)",
+    typing: "[kb]",
+    warning: "!",
+    private: "(p)",
+    battery: "[bat]",
+    scrobble: "[fm]",
+    lock: "[lock]",
+    pomodoro: "(t)",
+    loved: "<3",
+    output: "out:",
+    star: "*",
+    star_empty: ".",
+    shuffle: "shuf",
+    repeat: "rep",
+    repeat_one: "rep1",
+    spinner: &["|", "/", "-", "\\"],
+};
+
+impl Glyphs {
+    pub fn state(&self, state: &PlayerState) -> &'static str {
+        match state {
+            PlayerState::Playing => self.play,
+            PlayerState::Paused => self.pause,
+            PlayerState::Stopped => self.stop,
+        }
+    }
+}
+
+pub fn get(set: GlyphSet) -> &'static Glyphs {
+    match set {
+        GlyphSet::Emoji => &EMOJI,
+        GlyphSet::Nerd => &NERD,
+        GlyphSet::Ascii => &ASCII,
+    }
+}
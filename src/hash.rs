@@ -0,0 +1,22 @@
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+// Hashes 🔏
+// SHA-256 for the Spotify login's PKCE challenge and for checking downloads
+// from `vyom update`, MD5 for signing Last.fm calls. Computed in-process so
+// nothing depends on which system tools happen to be installed.
+
+/// SHA-256 of `data`, as bytes
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
+
+/// MD5 of `data`, as bytes
+pub fn md5(data: &[u8]) -> Vec<u8> {
+    Md5::digest(data).to_vec()
+}
+
+/// Lowercase hex, the way `sha256sum` and friends print hashes
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
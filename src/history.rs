@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::player::{PlayerState, TrackInfo};
+
+// Listening history 📊
+// Every track played for a while is logged, one JSON object per line, to
+// ~/.cache/vyom/history.jsonl. The recap card (`R`) and `vyom report` read it.
+
+/// Shorter plays (skips, previews) aren't listens
+const MIN_LISTEN_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Listen {
+    /// Unix seconds when the track started
+    pub played_at: u64,
+    pub name: String,
+    pub artist: String,
+    pub album: String,
+    pub source: String,
+    /// Time actually spent playing (pauses don't count)
+    pub listened_ms: u64,
+}
+
+/// The track on right now and how long it has really played
+#[derive(Debug)]
+pub struct Listening {
+    track: TrackInfo,
+    started_at: u64,
+    listened_ms: u64,
+    last_seen: Instant,
+}
+
+impl Listening {
+    pub fn new(track: &TrackInfo) -> Self {
+        Self { track: track.clone(), started_at: unix_now(), listened_ms: 0, last_seen: Instant::now() }
+    }
+
+    pub fn is_track(&self, track: &TrackInfo) -> bool {
+        self.track.name == track.name && self.track.artist == track.artist
+    }
+
+    /// Count the time since the last poll if the player was playing through it
+    pub fn update(&mut self, track: &TrackInfo) {
+        if track.state == PlayerState::Playing {
+            self.listened_ms += self.last_seen.elapsed().as_millis() as u64;
+        }
+        self.last_seen = Instant::now();
+    }
+
+    /// Write it to the history if it was played long enough
+    pub fn finish(self) {
+        if self.listened_ms < MIN_LISTEN_MS {
+            return;
+        }
+        append(&Listen {
+            played_at: self.started_at,
+            name: self.track.name,
+            artist: self.track.artist,
+            album: self.track.album,
+            source: self.track.source,
+            listened_ms: self.listened_ms,
+        });
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache").join("vyom").join("history.jsonl"))
+}
+
+fn append(listen: &Listen) {
+    let Some(path) = history_path() else { return };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let Ok(line) = serde_json::to_string(listen) else { return };
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+/// Listens that started at or after `since` (unix seconds). Unreadable lines are skipped.
+pub fn load_since(since: u64) -> Vec<Listen> {
+    let Some(content) = history_path().and_then(|path| fs::read_to_string(path).ok()) else { return Vec::new() };
+    content.lines()
+        .filter_map(|line| serde_json::from_str::<Listen>(line).ok())
+        .filter(|listen| listen.played_at >= since)
+        .collect()
+}
+
+// --- Local time without a date crate ---
+
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Local offset from UTC in seconds, read once from `date +%z` ("+0530")
+fn utc_offset_secs() -> i64 {
+    static OFFSET: OnceLock<i64> = OnceLock::new();
+    *OFFSET.get_or_init(|| {
+        let Ok(output) = Command::new("date").arg("+%z").output() else { return 0 };
+        let text = String::from_utf8_lossy(&output.stdout);
+        let text = text.trim();
+        let sign = if text.starts_with('-') { -1 } else { 1 };
+        let digits = text.trim_start_matches(['+', '-']);
+        let (Ok(hours), Ok(mins)) = (digits.get(..2).unwrap_or("0").parse::<i64>(), digits.get(2..4).unwrap_or("0").parse::<i64>()) else { return 0 };
+        sign * (hours * 3600 + mins * 60)
+    })
+}
+
+/// Unix seconds of the most recent local midnight, `days_back` days ago
+pub fn local_midnight(days_back: u64) -> u64 {
+    let local = unix_now() as i64 + utc_offset_secs();
+    let midnight = local - local.rem_euclid(86_400) - days_back as i64 * 86_400;
+    (midnight - utc_offset_secs()).max(0) as u64
+}
+
+/// Local calendar date of a unix timestamp as (year, month, day)
+pub fn local_date(unix: u64) -> (i64, u32, u32) {
+    // Days-to-civil from Howard Hinnant's date algorithms
+    let days = (unix as i64 + utc_offset_secs()).div_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// "2026-10-16"
+pub fn fmt_date(unix: u64) -> String {
+    let (year, month, day) = local_date(unix);
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+/// "today", "yesterday", "3 days ago", then the date
+pub fn fmt_ago(unix: u64) -> String {
+    let days = (0..7).find(|&days| unix >= local_midnight(days));
+    match days {
+        Some(0) => "today".to_string(),
+        Some(1) => "yesterday".to_string(),
+        Some(days) => format!("{} days ago", days),
+        None => fmt_date(unix),
+    }
+}
+
+/// Minutes since local midnight, for "show the recap at 22:00"
+pub fn local_minute_of_day() -> u64 {
+    ((unix_now() as i64 + utc_offset_secs()).rem_euclid(86_400) / 60) as u64
+}
+
+/// "22:00" → minutes since midnight
+pub fn parse_clock(text: &str) -> Option<u64> {
+    let (hours, mins) = text.trim().split_once(':')?;
+    let (hours, mins): (u64, u64) = (hours.parse().ok()?, mins.parse().ok()?);
+    (hours < 24 && mins < 60).then_some(hours * 60 + mins)
+}
+
+// --- Summaries ---
+
+/// Totals and favorites over a set of listens
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub listened_ms: u64,
+    pub listens: usize,
+    pub distinct_tracks: usize,
+    /// (artist, listens), most first
+    pub top_artists: Vec<(String, usize)>,
+    /// (title, artist, plays), most first
+    pub top_tracks: Vec<(String, String, usize)>,
+}
+
+impl Summary {
+    pub fn of(listens: &[Listen]) -> Self {
+        let mut artists: HashMap<&str, usize> = HashMap::new();
+        let mut tracks: HashMap<(&str, &str), usize> = HashMap::new();
+        for listen in listens {
+            *artists.entry(&listen.artist).or_default() += 1;
+            *tracks.entry((&listen.name, &listen.artist)).or_default() += 1;
+        }
+        let mut top_artists: Vec<(String, usize)> = artists.into_iter().map(|(a, n)| (a.to_string(), n)).collect();
+        // Ties broken by name so the order is stable
+        top_artists.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let distinct_tracks = tracks.len();
+        let mut top_tracks: Vec<(String, String, usize)> = tracks.into_iter()
+            .map(|((name, artist), n)| (name.to_string(), artist.to_string(), n))
+            .collect();
+        top_tracks.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            listened_ms: listens.iter().map(|l| l.listened_ms).sum(),
+            listens: listens.len(),
+            distinct_tracks,
+            top_artists,
+            top_tracks,
+        }
+    }
+}
+
+/// "3h 12m" / "42m"
+pub fn fmt_duration(ms: u64) -> String {
+    let mins = ms / 60_000;
+    if mins >= 60 { format!("{}h {}m", mins / 60, mins % 60) } else { format!("{}m", mins) }
+}
@@ -0,0 +1,193 @@
+#[cfg(not(unix))]
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::app::{self, App};
+use crate::player::PlayerTrait;
+use crate::status;
+
+// Control socket 🎛️
+// While the TUI runs it answers commands on ~/.cache/vyom/ctl.sock, one line
+// in and one line out, so tmux binds and window-manager keys can drive it:
+// `vyom ctl next`, `vyom ctl seek 42`, `vyom ctl state` (JSON). The first
+// instance owns the socket; later ones leave it alone.
+
+fn socket_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache").join("vyom").join("ctl.sock"))
+}
+
+const USAGE: &str = "Commands: play-pause, next, prev, seek <secs|+secs|-secs>, volume-up, volume-down, \
+                     show-lyrics, hide-lyrics, toggle-lyrics, state, quit";
+
+/// Run one command against the app. The reply is "ok", the state as JSON, or "error: ...".
+pub fn handle(app: &mut App, player: &dyn PlayerTrait, line: &str) -> String {
+    let mut words = line.split_whitespace();
+    let command = words.next().unwrap_or_default();
+    let arg = words.next();
+    let done = |result: anyhow::Result<()>| match result {
+        Ok(()) => "ok".to_string(),
+        Err(e) => format!("error: {}", e),
+    };
+    match command {
+        "play-pause" => done(player.play_pause()),
+        "next" => {
+            app.remember_jump(app::Jump::Next);
+            done(player.next())
+        },
+        "prev" => {
+            app.remember_jump(app::Jump::Prev);
+            done(player.prev())
+        },
+        "seek" => {
+            let Some(secs) = arg.and_then(|a| a.parse::<f64>().ok()) else {
+                return "error: seek takes seconds, e.g. `seek 42` or `seek +10`".to_string();
+            };
+            // "+10" / "-10" are from where it is now
+            let relative = arg.is_some_and(|a| a.starts_with(['+', '-']));
+            let position_ms = app.clock.position_ms() as f64;
+            let target_ms = if relative { position_ms + secs * 1000.0 } else { secs * 1000.0 };
+            let target_ms = (target_ms.max(0.0) as u64).min(app.clock.duration_ms());
+            app.remember_jump(app::Jump::Seek);
+            let result = player.seek(target_ms as f64 / 1000.0);
+            if result.is_ok() {
+                app.clock.seek(target_ms);
+            }
+            done(result)
+        },
+        "volume-up" | "volume-down" => {
+            app.step_volume(command == "volume-up", player);
+            "ok".to_string()
+        },
+        "show-lyrics" => {
+            app.app_show_lyrics = true;
+            "ok".to_string()
+        },
+        "hide-lyrics" => {
+            app.app_show_lyrics = false;
+            "ok".to_string()
+        },
+        "toggle-lyrics" => {
+            app.app_show_lyrics = !app.app_show_lyrics;
+            "ok".to_string()
+        },
+        "state" => state(app).to_string(),
+        "quit" => {
+            app.is_running = false;
+            "ok".to_string()
+        },
+        "" | "help" => USAGE.to_string(),
+        other => format!("error: unknown command `{}`. {}", other, USAGE),
+    }
+}
+
+/// `vyom status --json`'s object with the position from the clock, plus what the TUI knows
+fn state(app: &App) -> serde_json::Value {
+    let track = app.track.clone().map(|mut track| {
+        track.position_ms = app.clock.position_ms();
+        track
+    });
+    let text = status::render(status::DEFAULT_FORMAT, track.as_ref(), &app.config);
+    let mut state = status::to_json(text, track.as_ref());
+    state["lyric"] = app.current_lyric_text().into();
+    state["show_lyrics"] = app.app_show_lyrics.into();
+    state["shuffle"] = app.shuffle.into();
+    state["repeat"] = app.repeat.map(|repeat| repeat.label()).into();
+    state
+}
+
+#[cfg(unix)]
+mod unix {
+    use anyhow::{Context, Result};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::Shutdown;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::sync::{mpsc, oneshot};
+
+    use super::socket_path;
+    use crate::AppEvent;
+
+    /// This instance bound the socket (and removes it on the way out)
+    static OWNED: AtomicBool = AtomicBool::new(false);
+
+    /// Answer `vyom ctl` in the background, unless another instance already does
+    pub fn listen(tx: mpsc::Sender<AppEvent>) -> Result<()> {
+        let path = socket_path().context("HOME is not set")?;
+        if UnixStream::connect(&path).is_ok() {
+            return Ok(());
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Left over from an instance that didn't exit cleanly
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        OWNED.store(true, Ordering::Relaxed);
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                std::thread::spawn(move || serve(stream, tx));
+            }
+        });
+        Ok(())
+    }
+
+    /// One command per connection; the app's answer goes back before it closes
+    fn serve(stream: UnixStream, tx: mpsc::Sender<AppEvent>) {
+        let Ok(reader) = stream.try_clone() else { return };
+        let mut line = String::new();
+        if BufReader::new(reader).read_line(&mut line).is_err() {
+            return;
+        }
+        let (reply_tx, reply_rx) = oneshot::channel();
+        if tx.blocking_send(AppEvent::Command(line.trim().to_string(), reply_tx)).is_err() {
+            return;
+        }
+        let reply = reply_rx.blocking_recv().unwrap_or_else(|_| "error: Vyom is shutting down".to_string());
+        let mut stream = stream;
+        let _ = writeln!(stream, "{}", reply);
+    }
+
+    /// `vyom ctl <command>`: send it to the running instance and print the answer
+    pub fn send(args: &[String]) -> Result<()> {
+        let path = socket_path().context("HOME is not set")?;
+        let mut stream = UnixStream::connect(&path).context("No running Vyom to control")?;
+        writeln!(stream, "{}", args.join(" "))?;
+        stream.shutdown(Shutdown::Write)?;
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply)?;
+        let reply = reply.trim_end();
+        if let Some(error) = reply.strip_prefix("error: ") {
+            anyhow::bail!("{}", error);
+        }
+        println!("{}", reply);
+        Ok(())
+    }
+
+    pub fn cleanup() {
+        if OWNED.load(Ordering::Relaxed) {
+            if let Some(path) = socket_path() {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+pub use unix::{cleanup, listen, send};
+
+#[cfg(not(unix))]
+pub fn listen(_tx: tokio::sync::mpsc::Sender<crate::AppEvent>) -> Result<()> {
+    anyhow::bail!("The control socket needs Unix sockets")
+}
+
+#[cfg(not(unix))]
+pub fn send(_args: &[String]) -> Result<()> {
+    anyhow::bail!("The control socket needs Unix sockets")
+}
+
+#[cfg(not(unix))]
+pub fn cleanup() {}
@@ -0,0 +1,227 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+#[cfg(unix)]
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+/// Keychain service name every credential is stored under
+#[cfg(unix)]
+const SERVICE: &str = "vyom";
+
+/// Credentials `vyom auth` knows about: (CLI name, keychain account, description)
+pub const CREDENTIALS: &[(&str, &str, &str)] = &[
+    ("musixmatch", "musixmatch_token", "Musixmatch user token"),
+    ("genius", "genius_token", "Genius API token"),
+    ("spotify-sp-dc", "spotify_sp_dc", "Spotify sp_dc cookie"),
+    ("getsongbpm", "getsongbpm_key", "GetSongBPM API key"),
+    ("lastfm-key", "lastfm_api_key", "Last.fm API key"),
+    ("lastfm-secret", "lastfm_api_secret", "Last.fm API shared secret"),
+    ("lastfm-session", "lastfm_session_key", "Last.fm session (`vyom auth lastfm`)"),
+    ("listenbrainz", "listenbrainz_token", "ListenBrainz user token"),
+    ("spotify-session", "spotify_refresh_token", "Spotify Web API login (`vyom auth spotify`)"),
+    ("translation", "translation_api_key", "DeepL / LibreTranslate API key"),
+];
+
+/// Answers `get_cached` already has, per account
+static LOOKUPS: Mutex<Vec<(&str, Option<String>)>> = Mutex::new(Vec::new());
+
+/// `get`, asked once per account and process. The config is loaded again on
+/// every edit, and each lookup runs a program.
+pub fn get_cached(account: &'static str) -> Option<String> {
+    let mut lookups = LOOKUPS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, secret)) = lookups.iter().find(|(a, _)| *a == account) {
+        return secret.clone();
+    }
+    let secret = get(account);
+    lookups.push((account, secret.clone()));
+    secret
+}
+
+// --- macOS: Keychain via `security` 🍎 ---
+
+#[cfg(target_os = "macos")]
+pub fn get(account: &str) -> Option<String> {
+    let output = Command::new("security")
+        .args(["find-generic-password", "-s", SERVICE, "-a", account, "-w"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+pub fn set(account: &str, secret: &str) -> Result<()> {
+    if secret.contains(['\n', '\r']) {
+        anyhow::bail!("{} can't span several lines", account);
+    }
+    // `security -i` reads the command from stdin, so the secret never shows up
+    // in `ps` the way `-w <secret>` as an argument would. -U updates an
+    // existing entry instead of failing.
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let command = format!("add-generic-password -U -s {} -a {} -w {}\n", quote(SERVICE), quote(account), quote(secret));
+    let mut child = Command::new("security")
+        .arg("-i")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .context("Failed to run `security`")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(command.as_bytes())?;
+    }
+    child.wait()?;
+    // Interactive mode exits 0 whatever the command did: read it back instead
+    if get(account).as_deref() != Some(secret.trim()) {
+        anyhow::bail!("Keychain refused to store {}", account);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn delete(account: &str) -> Result<()> {
+    let status = Command::new("security")
+        .args(["delete-generic-password", "-s", SERVICE, "-a", account])
+        .stdout(Stdio::null())
+        .status()
+        .context("Failed to run `security`")?;
+    if !status.success() {
+        anyhow::bail!("{} is not in the keychain", account);
+    }
+    Ok(())
+}
+
+// --- Linux and the BSDs: Secret Service via `secret-tool` 🐧 ---
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn get(account: &str) -> Option<String> {
+    let output = Command::new("secret-tool")
+        .args(["lookup", "service", SERVICE, "account", account])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn set(account: &str, secret: &str) -> Result<()> {
+    // secret-tool reads the secret from stdin so it never shows up in `ps`
+    let mut child = Command::new("secret-tool")
+        .args(["store", "--label", &format!("vyom {}", account), "service", SERVICE, "account", account])
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Failed to run `secret-tool` (install libsecret-tools)")?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(secret.as_bytes())?;
+    }
+    if !child.wait()?.success() {
+        anyhow::bail!("Secret service refused to store {}", account);
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn delete(account: &str) -> Result<()> {
+    let status = Command::new("secret-tool")
+        .args(["clear", "service", SERVICE, "account", account])
+        .status()
+        .context("Failed to run `secret-tool` (install libsecret-tools)")?;
+    if !status.success() {
+        anyhow::bail!("{} is not in the secret service", account);
+    }
+    Ok(())
+}
+
+// --- Elsewhere (Windows): no keychain yet, credentials live in config.toml ---
+
+#[cfg(not(unix))]
+pub fn get(_account: &str) -> Option<String> {
+    None
+}
+
+#[cfg(not(unix))]
+pub fn set(_account: &str, _secret: &str) -> Result<()> {
+    anyhow::bail!("No keychain support on this platform, put the credential in config.toml")
+}
+
+#[cfg(not(unix))]
+pub fn delete(_account: &str) -> Result<()> {
+    anyhow::bail!("No keychain support on this platform")
+}
+
+/// `vyom auth <list|set|remove> [name]`
+pub fn run_cli(args: &[String]) -> Result<()> {
+    let action = args.first().map(String::as_str).unwrap_or("list");
+    let lookup = |name: Option<&String>| -> Result<&'static str> {
+        let name = name.context("Missing credential name, see `vyom auth list`")?;
+        CREDENTIALS.iter()
+            .find(|(cli, _, _)| cli == name)
+            .map(|(_, account, _)| *account)
+            .with_context(|| format!("Unknown credential `{}`, see `vyom auth list`", name))
+    };
+
+    match action {
+        "list" => {
+            for (cli, account, desc) in CREDENTIALS {
+                let mark = if get(account).is_some() { "✓" } else { "·" };
+                println!(" {} {:<15} {}", mark, cli, desc);
+            }
+        }
+        "set" => {
+            let account = lookup(args.get(1))?;
+            print!("Paste secret for {} (input hidden): ", account);
+            std::io::stdout().flush()?;
+            let secret = read_secret()?;
+            println!();
+            if secret.is_empty() {
+                anyhow::bail!("Empty secret, nothing stored");
+            }
+            set(account, &secret)?;
+            println!("Stored {} in the keychain.", account);
+        }
+        "remove" => {
+            let account = lookup(args.get(1))?;
+            delete(account)?;
+            println!("Removed {} from the keychain.", account);
+        }
+        other => anyhow::bail!("Unknown auth action `{}` (expected list, set or remove)", other),
+    }
+    Ok(())
+}
+
+/// Read a line without echoing it. Falls back to plain stdin when not a TTY (piped secrets).
+fn read_secret() -> Result<String> {
+    use crossterm::event::{read, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+    use std::io::IsTerminal;
+
+    if !std::io::stdin().is_terminal() {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        return Ok(line.trim().to_string());
+    }
+
+    enable_raw_mode()?;
+    let mut secret = String::new();
+    let result = loop {
+        match read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    secret.clear();
+                    break Ok(());
+                }
+                KeyCode::Char(c) => secret.push(c),
+                KeyCode::Backspace => { secret.pop(); }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    disable_raw_mode()?;
+    result?;
+    Ok(secret.trim().to_string())
+}
@@ -0,0 +1,139 @@
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+
+// Keybindings ⌨️
+// `[keys]` in the config moves actions to other keys, e.g. `next = "j"`.
+// Pressed keys are translated back to the built-in ones before the event loop
+// sees them, so every action keeps a single code path. An action's old key
+// stops doing it once it has moved (unless another action takes it over).
+
+/// Action names usable under `[keys]` and the key each one starts on
+const ACTIONS: &[(&str, KeyCode)] = &[
+    ("quit", KeyCode::Char('q')),
+    ("play_pause", KeyCode::Char(' ')),
+    ("next", KeyCode::Char('n')),
+    ("prev", KeyCode::Char('p')),
+    ("undo", KeyCode::Char('u')),
+    ("volume_up", KeyCode::Char('+')),
+    ("volume_down", KeyCode::Char('-')),
+    ("love", KeyCode::Char('f')),
+    ("shuffle", KeyCode::Char('s')),
+    ("repeat", KeyCode::Char('r')),
+    ("ban", KeyCode::Char('X')),
+    ("snapshot", KeyCode::Char('e')),
+    ("detach", KeyCode::Char('d')),
+    ("chapter_next", KeyCode::Char(']')),
+    ("chapter_prev", KeyCode::Char('[')),
+    ("search", KeyCode::Char('/')),
+    ("loop_line", KeyCode::Char('l')),
+    ("typing", KeyCode::Char('T')),
+    ("scroll_lock", KeyCode::Char('L')),
+    ("translate", KeyCode::Char('t')),
+    ("lyrics_top", KeyCode::Char('g')),
+    ("lyrics_bottom", KeyCode::Char('G')),
+    ("open_context", KeyCode::Char('o')),
+    ("output_picker", KeyCode::Char('O')),
+    ("queue", KeyCode::Char('Q')),
+    ("reveal", KeyCode::Char('M')),
+    ("party", KeyCode::Char('P')),
+    ("power_profile", KeyCode::Char('b')),
+    ("recap", KeyCode::Char('R')),
+    ("pomodoro", KeyCode::Char('w')),
+    ("lightbox", KeyCode::Char('z')),
+    ("toggle_artwork", KeyCode::Char('A')),
+    ("toggle_visualizer", KeyCode::Char('V')),
+    ("toggle_controls", KeyCode::Char('C')),
+    ("toggle_footer", KeyCode::Char('F')),
+    ("toggle_icons", KeyCode::Char('I')),
+    ("edit_config", KeyCode::Char(',')),
+    ("debug", KeyCode::F(12)),
+];
+
+/// "j", "space", "enter", "tab", "up", "pagedown", "f5", ...
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let lower = name.to_lowercase();
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+        return Some(KeyCode::F(n));
+    }
+    Some(match lower.as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        _ => return None,
+    })
+}
+
+/// How a key is written in the footer hints
+fn label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    /// Pressed key -> built-in key it stands for (KeyCode::Null: does nothing now)
+    remap: HashMap<KeyCode, KeyCode>,
+    /// Action -> the key it's on, for the hints
+    bound: HashMap<&'static str, KeyCode>,
+}
+
+impl Keymap {
+    /// Unknown actions and keys are reported and skipped (before the TUI is up)
+    pub fn new(bindings: &HashMap<String, String>) -> Self {
+        let mut keymap = Self::default();
+        for (action, key) in bindings {
+            let Some(&(name, default)) = ACTIONS.iter().find(|(name, _)| name == action) else {
+                eprintln!("Unknown action in [keys]: {}", action);
+                continue;
+            };
+            let Some(code) = parse_key(key) else {
+                eprintln!("Unknown key for {} in [keys]: {:?}", action, key);
+                continue;
+            };
+            keymap.bound.insert(name, code);
+            keymap.remap.entry(default).or_insert(KeyCode::Null);
+        }
+        // New keys win over the old keys they replace
+        for (&name, &code) in &keymap.bound {
+            if let Some(&(_, default)) = ACTIONS.iter().find(|(n, _)| *n == name) {
+                keymap.remap.insert(code, default);
+            }
+        }
+        keymap
+    }
+
+    /// The built-in key `code` stands for
+    pub fn resolve(&self, code: KeyCode) -> KeyCode {
+        self.remap.get(&code).copied().unwrap_or(code)
+    }
+
+    /// The key `action` is on, as shown in the footer
+    pub fn label(&self, action: &str) -> String {
+        let code = self.bound.get(action).copied()
+            .or_else(|| ACTIONS.iter().find(|(name, _)| *name == action).map(|&(_, code)| code))
+            .unwrap_or(KeyCode::Null);
+        label(code)
+    }
+}
@@ -0,0 +1,91 @@
+// Lyrics language 🌐
+// A small guess at what language the lyrics are in, for the badge on the
+// lyrics card: the script gives most away (kana, hangul, Cyrillic...), and
+// common short words tell the Latin-script languages apart. Lyrics in the
+// user's own language ($LANG) skip the translation lines.
+
+/// Latin-script languages and words that are common in them but rare elsewhere
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "you", "that", "with", "what", "my", "your", "it's", "i'm", "don't", "love", "is"]),
+    ("es", &["el", "los", "las", "que", "y", "una", "por", "con", "mi", "tu", "yo", "qué", "es", "corazón"]),
+    ("fr", &["le", "les", "et", "je", "tu", "une", "des", "est", "pas", "dans", "mon", "moi", "qui", "c'est"]),
+    ("de", &["der", "die", "das", "und", "ich", "nicht", "ist", "du", "mein", "dich", "mich", "ein", "auf", "wir"]),
+    ("pt", &["o", "os", "e", "que", "não", "uma", "você", "meu", "com", "eu", "é", "do", "da", "coração"]),
+    ("it", &["il", "che", "e", "di", "non", "una", "sono", "mi", "ti", "per", "io", "è", "del", "cuore"]),
+];
+
+/// Fewer Latin words than this and it's anyone's guess
+const MIN_WORDS: usize = 12;
+
+/// ISO 639-1 code of the language `lines` are most likely in
+pub fn detect<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<&'static str> {
+    let text: String = lines.into_iter().collect::<Vec<_>>().join("\n");
+
+    // Letters per script; kana settles Japanese even among kanji
+    let (mut latin, mut kana, mut han, mut hangul, mut cyrillic, mut ukrainian) = (0, 0, 0, 0, 0, 0);
+    let mut other: [(usize, &str); 5] = [(0, "ar"), (0, "he"), (0, "el"), (0, "hi"), (0, "th")];
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        match c as u32 {
+            0x0041..=0x024F => latin += 1,
+            0x3040..=0x30FF => kana += 1,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF => han += 1,
+            0x1100..=0x11FF | 0xAC00..=0xD7AF => hangul += 1,
+            0x0400..=0x04FF => {
+                cyrillic += 1;
+                if matches!(c, 'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ') {
+                    ukrainian += 1;
+                }
+            }
+            0x0600..=0x06FF => other[0].0 += 1,
+            0x0590..=0x05FF => other[1].0 += 1,
+            0x0370..=0x03FF => other[2].0 += 1,
+            0x0900..=0x097F => other[3].0 += 1,
+            0x0E00..=0x0E7F => other[4].0 += 1,
+            _ => {}
+        }
+    }
+    let letters = latin + kana + han + hangul + cyrillic + other.iter().map(|(n, _)| n).sum::<usize>();
+    if letters == 0 {
+        return None;
+    }
+    // A script needs a fair share of the letters; the odd English hook doesn't count
+    let share = |n: usize| n * 4 >= letters;
+    if kana > 0 && share(kana + han) {
+        return Some("ja");
+    }
+    if share(hangul) {
+        return Some("ko");
+    }
+    if share(han) {
+        return Some("zh");
+    }
+    if share(cyrillic) {
+        return Some(if ukrainian > 0 { "uk" } else { "ru" });
+    }
+    if let Some((_, code)) = other.iter().filter(|(n, _)| share(*n)).max_by_key(|(n, _)| *n) {
+        return Some(code);
+    }
+
+    let words: Vec<String> = text.split(|c: char| !(c.is_alphabetic() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < MIN_WORDS {
+        return None;
+    }
+    STOPWORDS.iter()
+        .map(|(code, stopwords)| (words.iter().filter(|w| stopwords.contains(&w.as_str())).count(), *code))
+        .filter(|(hits, _)| *hits > 0)
+        .max_by_key(|(hits, _)| *hits)
+        .map(|(_, code)| code)
+}
+
+/// The user's language from the locale (`LC_ALL`, `LC_MESSAGES`, `LANG`), English without one
+pub fn user_language() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+        .map(|value| value.chars().take_while(|c| c.is_ascii_alphabetic()).collect::<String>().to_lowercase())
+        .filter(|code| code.len() == 2)
+        .unwrap_or_else(|| "en".to_string())
+}
@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
+
+use crate::config::LastfmConfig;
+use crate::hash;
+use crate::keychain;
+use crate::scrobble::Scrobble;
+
+// Last.fm 📻
+// Signed calls to the Last.fm API: loved tracks, scrobbles, and the browser login that
+// `vyom auth lastfm` runs to get a session. Signatures are MD5s, taken from
+// `md5` / `md5sum` so no hashing crate is needed.
+
+const API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+const AUTH_URL: &str = "https://www.last.fm/api/auth/";
+
+/// An authorized account: API credentials plus the user's session
+#[derive(Debug, Clone)]
+pub struct Account {
+    api_key: String,
+    api_secret: String,
+    session_key: String,
+}
+
+impl Account {
+    pub fn from_config(config: &LastfmConfig) -> Option<Self> {
+        Some(Self {
+            api_key: config.api_key.clone()?,
+            api_secret: config.api_secret.clone()?,
+            session_key: config.session_key.clone()?,
+        })
+    }
+}
+
+/// `api_sig`: every parameter as name+value in name order, then the secret, MD5'd
+fn sign(params: &BTreeMap<&str, String>, secret: &str) -> String {
+    let mut text: String = params.iter().map(|(name, value)| format!("{}{}", name, value)).collect();
+    text.push_str(secret);
+    hash::hex(&hash::md5(text.as_bytes()))
+}
+
+/// Signed API call. Writes go as POST, reads as GET.
+async fn call(client: &Client, api_key: &str, secret: &str, method: &str, mut params: BTreeMap<&str, String>, post: bool) -> Result<Value> {
+    params.insert("method", method.to_string());
+    params.insert("api_key", api_key.to_string());
+    let sig = sign(&params, secret);
+    params.insert("api_sig", sig);
+    // `format` is the one parameter left out of the signature
+    params.insert("format", "json".to_string());
+
+    let request = if post { client.post(API_URL).form(&params) } else { client.get(API_URL).query(&params) };
+    let data: Value = request.send().await?.json().await?;
+    if let Some(code) = data.get("error").and_then(Value::as_u64) {
+        let message = data.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+        anyhow::bail!("Last.fm error {}: {}", code, message);
+    }
+    Ok(data)
+}
+
+/// Love or unlove a track on the account
+pub async fn set_loved(client: &Client, account: &Account, artist: &str, title: &str, loved: bool) -> Result<()> {
+    let params = BTreeMap::from([
+        ("artist", artist.to_string()),
+        ("track", title.to_string()),
+        ("sk", account.session_key.clone()),
+    ]);
+    let method = if loved { "track.love" } else { "track.unlove" };
+    call(client, &account.api_key, &account.api_secret, method, params, true).await?;
+    Ok(())
+}
+
+/// Show the track as playing now on the profile
+pub async fn now_playing(client: &Client, account: &Account, scrobble: &Scrobble) -> Result<()> {
+    let mut params = BTreeMap::from([
+        ("artist", scrobble.artist.clone()),
+        ("track", scrobble.title.clone()),
+        ("sk", account.session_key.clone()),
+    ]);
+    if !scrobble.album.is_empty() {
+        params.insert("album", scrobble.album.clone());
+    }
+    if scrobble.duration_secs > 0 {
+        params.insert("duration", scrobble.duration_secs.to_string());
+    }
+    call(client, &account.api_key, &account.api_secret, "track.updateNowPlaying", params, true).await?;
+    Ok(())
+}
+
+/// Scrobble up to 50 plays in one request, as `artist[0]`, `track[0]`, ...
+pub async fn scrobble(client: &Client, account: &Account, scrobbles: &[Scrobble]) -> Result<()> {
+    // The map borrows its names, so the indexed ones are made up front
+    let names: Vec<[String; 5]> = (0..scrobbles.len())
+        .map(|i| ["artist", "track", "timestamp", "album", "duration"].map(|name| format!("{}[{}]", name, i)))
+        .collect();
+    let mut params = BTreeMap::from([("sk", account.session_key.clone())]);
+    for (scrobble, [artist, track, timestamp, album, duration]) in scrobbles.iter().zip(&names) {
+        params.insert(artist, scrobble.artist.clone());
+        params.insert(track, scrobble.title.clone());
+        params.insert(timestamp, scrobble.started_at.to_string());
+        if !scrobble.album.is_empty() {
+            params.insert(album, scrobble.album.clone());
+        }
+        if scrobble.duration_secs > 0 {
+            params.insert(duration, scrobble.duration_secs.to_string());
+        }
+    }
+    call(client, &account.api_key, &account.api_secret, "track.scrobble", params, true).await?;
+    Ok(())
+}
+
+/// Every loved track on the account as (title, artist)
+pub async fn loved_tracks(client: &Client, account: &Account) -> Result<Vec<(String, String)>> {
+    // The session knows whose it is; user.getLovedTracks wants the name
+    let params = BTreeMap::from([("sk", account.session_key.clone())]);
+    let info = call(client, &account.api_key, &account.api_secret, "user.getInfo", params, false).await?;
+    let user = info.pointer("/user/name").and_then(Value::as_str).context("Last.fm returned no user")?;
+
+    let params = BTreeMap::from([("user", user.to_string()), ("limit", "1000".to_string())]);
+    let data = call(client, &account.api_key, &account.api_secret, "user.getLovedTracks", params, false).await?;
+    // A single loved track comes back as an object instead of a list
+    let tracks = match data.pointer("/lovedtracks/track") {
+        Some(Value::Array(tracks)) => tracks.clone(),
+        Some(track @ Value::Object(_)) => vec![track.clone()],
+        _ => Vec::new(),
+    };
+    Ok(tracks.iter()
+        .filter_map(|track| {
+            let title = track.get("name")?.as_str()?;
+            let artist = track.pointer("/artist/name")?.as_str()?;
+            Some((title.to_string(), artist.to_string()))
+        })
+        .collect())
+}
+
+/// `vyom auth lastfm`: approve Vyom in the browser, then store the session in the keychain
+pub async fn login(client: &Client, config: &LastfmConfig) -> Result<()> {
+    let (Some(api_key), Some(secret)) = (config.api_key.as_deref(), config.api_secret.as_deref()) else {
+        anyhow::bail!("Set the API key and secret first: `vyom auth set lastfm-key`, `vyom auth set lastfm-secret`");
+    };
+    let data = call(client, api_key, secret, "auth.getToken", BTreeMap::new(), false).await?;
+    let token = data.get("token").and_then(Value::as_str).context("Last.fm returned no token")?;
+
+    let url = format!("{}?api_key={}&token={}", AUTH_URL, api_key, token);
+    let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+    let _ = Command::new(opener).arg(&url).stdout(Stdio::null()).stderr(Stdio::null()).status();
+    println!("Allow Vyom on Last.fm (opened in your browser):\n  {}\nthen press Enter.", url);
+    std::io::stdin().read_line(&mut String::new())?;
+
+    let params = BTreeMap::from([("token", token.to_string())]);
+    let data = call(client, api_key, secret, "auth.getSession", params, false).await?;
+    let key = data.pointer("/session/key").and_then(Value::as_str).context("Last.fm returned no session")?;
+    let name = data.pointer("/session/name").and_then(Value::as_str).unwrap_or("?");
+    keychain::set("lastfm_session_key", key)?;
+    println!("Logged in to Last.fm as {}.", name);
+    Ok(())
+}
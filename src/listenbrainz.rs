@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::scrobble::Scrobble;
+
+// ListenBrainz 🧠
+// Listens, and loved tracks as recording feedback (score 1 = loved, 0 =
+// cleared). Feedback is keyed by MusicBrainz recording, so tracks are looked
+// up by artist and title first.
+
+const API_URL: &str = "https://api.listenbrainz.org/1";
+
+async fn get(client: &Client, token: &str, path: &str, query: &[(&str, &str)]) -> Result<Value> {
+    let resp = client.get(format!("{}{}", API_URL, path))
+        .header("Authorization", format!("Token {}", token))
+        .query(query)
+        .send().await?
+        .error_for_status()?;
+    Ok(resp.json().await?)
+}
+
+/// Whose token it is
+async fn user_name(client: &Client, token: &str) -> Result<String> {
+    let data = get(client, token, "/validate-token", &[]).await?;
+    if data.get("valid").and_then(Value::as_bool) != Some(true) {
+        anyhow::bail!("ListenBrainz token rejected");
+    }
+    data.get("user_name").and_then(Value::as_str).map(str::to_string).context("ListenBrainz returned no user")
+}
+
+async fn recording_mbid(client: &Client, token: &str, artist: &str, title: &str) -> Result<Option<String>> {
+    let data = get(client, token, "/metadata/lookup/", &[("artist_name", artist), ("recording_name", title)]).await?;
+    Ok(data.get("recording_mbid").and_then(Value::as_str).map(str::to_string))
+}
+
+/// Love a track, or clear the feedback
+pub async fn set_loved(client: &Client, token: &str, artist: &str, title: &str, loved: bool) -> Result<()> {
+    let mbid = recording_mbid(client, token, artist, title).await?
+        .with_context(|| format!("{} · {} isn't on MusicBrainz", title, artist))?;
+    client.post(format!("{}/feedback/recording-feedback", API_URL))
+        .header("Authorization", format!("Token {}", token))
+        .json(&serde_json::json!({ "recording_mbid": mbid, "score": if loved { 1 } else { 0 } }))
+        .send().await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Submit listens: `listen_type` is "playing_now" (one, no timestamp), "single"
+/// for one finished play, or "import" for several
+pub async fn submit_listens(client: &Client, token: &str, listen_type: &str, scrobbles: &[Scrobble]) -> Result<()> {
+    let payload: Vec<Value> = scrobbles.iter()
+        .map(|scrobble| {
+            let mut listen = serde_json::json!({
+                "track_metadata": {
+                    "artist_name": scrobble.artist,
+                    "track_name": scrobble.title,
+                    "additional_info": {
+                        "media_player": scrobble.player,
+                        "submission_client": "vyom",
+                        "submission_client_version": env!("CARGO_PKG_VERSION"),
+                    },
+                },
+            });
+            if !scrobble.album.is_empty() {
+                listen["track_metadata"]["release_name"] = Value::from(scrobble.album.as_str());
+            }
+            if scrobble.duration_secs > 0 {
+                listen["track_metadata"]["additional_info"]["duration_ms"] = Value::from(scrobble.duration_secs * 1000);
+            }
+            if listen_type != "playing_now" {
+                listen["listened_at"] = Value::from(scrobble.started_at);
+            }
+            listen
+        })
+        .collect();
+    client.post(format!("{}/submit-listens", API_URL))
+        .header("Authorization", format!("Token {}", token))
+        .json(&serde_json::json!({ "listen_type": listen_type, "payload": payload }))
+        .send().await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Every loved recording as (title, artist)
+pub async fn loved_tracks(client: &Client, token: &str) -> Result<Vec<(String, String)>> {
+    let user = user_name(client, token).await?;
+    let path = format!("/feedback/user/{}/get-feedback", user);
+    let data = get(client, token, &path, &[("score", "1"), ("count", "1000"), ("metadata", "true")]).await?;
+    let feedback = data.get("feedback").and_then(Value::as_array).cloned().unwrap_or_default();
+    Ok(feedback.iter()
+        .filter_map(|item| {
+            let title = item.pointer("/track_metadata/track_name")?.as_str()?;
+            let artist = item.pointer("/track_metadata/artist_name")?.as_str()?;
+            Some((title.to_string(), artist.to_string()))
+        })
+        .collect())
+}
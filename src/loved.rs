@@ -0,0 +1,189 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::lastfm;
+use crate::listenbrainz;
+use crate::player::TrackInfo;
+
+// Loved tracks ♥
+// `f` hearts the current track. Hearts live in ~/.cache/vyom/loved.json and
+// sync both ways with Last.fm loved tracks and ListenBrainz feedback when
+// those accounts are set up: hearts given here show up on the profile, and
+// ones given (or taken back) there show up here.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub artist: String,
+    /// The services know about it (pushed there, or pulled from there)
+    #[serde(default)]
+    pub synced: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Loved {
+    tracks: BTreeMap<String, Entry>,
+    /// Hearts taken back here that the services haven't heard about yet
+    #[serde(default)]
+    unloved: BTreeMap<String, Entry>,
+}
+
+/// Services match tracks by artist and title only (no player ids)
+fn key(name: &str, artist: &str) -> String {
+    format!("{}|{}", artist.to_lowercase(), name.to_lowercase())
+}
+
+fn loved_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache").join("vyom").join("loved.json"))
+}
+
+impl Loved {
+    pub fn load() -> Self {
+        loved_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Some(path) = loved_path() else { return };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    pub fn contains(&self, track: &TrackInfo) -> bool {
+        self.tracks.contains_key(&key(&track.name, &track.artist))
+    }
+
+    /// Heart `track`, or take the heart back. Returns whether it's loved now.
+    pub fn toggle(&mut self, track: &TrackInfo) -> bool {
+        let key = key(&track.name, &track.artist);
+        if let Some(entry) = self.tracks.remove(&key) {
+            if entry.synced {
+                self.unloved.insert(key, entry);
+            }
+            return false;
+        }
+        // Loved again before the unlove went out: the services still have it
+        let synced = self.unloved.remove(&key).is_some();
+        self.tracks.insert(key, Entry { name: track.name.clone(), artist: track.artist.clone(), synced });
+        true
+    }
+
+    /// What the next sync has to push
+    pub fn pending(&self) -> Pending {
+        Pending {
+            love: self.tracks.values().filter(|e| !e.synced).cloned().collect(),
+            unlove: self.unloved.values().cloned().collect(),
+            known: self.tracks.iter().filter(|(_, e)| e.synced).map(|(k, _)| k.clone()).collect(),
+        }
+    }
+
+    /// Take in a finished sync
+    pub fn apply(&mut self, outcome: Outcome) {
+        for key in &outcome.pushed {
+            if let Some(entry) = self.tracks.get_mut(key) {
+                entry.synced = true;
+            }
+        }
+        for key in &outcome.unpushed {
+            self.unloved.remove(key);
+        }
+        let Some(remote) = outcome.remote else { return };
+        let remote_keys: BTreeSet<String> = remote.iter().map(|e| key(&e.name, &e.artist)).collect();
+        // Unloved on the service since the last sync
+        self.tracks.retain(|k, e| !(e.synced && outcome.known.contains(k) && !remote_keys.contains(k)));
+        // Loved on the service (unless it's being unloved here)
+        for entry in remote {
+            let key = key(&entry.name, &entry.artist);
+            if !self.unloved.contains_key(&key) {
+                self.tracks.insert(key, Entry { synced: true, ..entry });
+            }
+        }
+    }
+}
+
+/// Hearts to push, and which ones were already in sync when it started
+pub struct Pending {
+    love: Vec<Entry>,
+    unlove: Vec<Entry>,
+    known: BTreeSet<String>,
+}
+
+#[derive(Debug)]
+pub struct Outcome {
+    /// Loves and unloves every service took
+    pushed: Vec<String>,
+    unpushed: Vec<String>,
+    /// Everything loved on the services, when asked for and every one answered
+    remote: Option<Vec<Entry>>,
+    known: BTreeSet<String>,
+}
+
+/// The accounts to sync with
+#[derive(Debug, Clone)]
+pub struct Services {
+    lastfm: Option<lastfm::Account>,
+    listenbrainz: Option<String>,
+}
+
+impl Services {
+    /// None when neither account is set up
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let services = Self {
+            lastfm: lastfm::Account::from_config(&config.lastfm),
+            listenbrainz: config.listenbrainz.token.clone(),
+        };
+        (services.lastfm.is_some() || services.listenbrainz.is_some()).then_some(services)
+    }
+
+    async fn set_loved(&self, client: &Client, entry: &Entry, loved: bool) -> bool {
+        let mut ok = true;
+        if let Some(account) = &self.lastfm {
+            ok &= lastfm::set_loved(client, account, &entry.artist, &entry.name, loved).await.is_ok();
+        }
+        if let Some(token) = &self.listenbrainz {
+            ok &= listenbrainz::set_loved(client, token, &entry.artist, &entry.name, loved).await.is_ok();
+        }
+        ok
+    }
+
+    async fn loved_tracks(&self, client: &Client) -> Option<Vec<Entry>> {
+        let mut tracks = Vec::new();
+        if let Some(account) = &self.lastfm {
+            tracks.extend(lastfm::loved_tracks(client, account).await.ok()?);
+        }
+        if let Some(token) = &self.listenbrainz {
+            tracks.extend(listenbrainz::loved_tracks(client, token).await.ok()?);
+        }
+        Some(tracks.into_iter().map(|(name, artist)| Entry { name, artist, synced: true }).collect())
+    }
+}
+
+/// Push pending hearts, then (with `pull`) fetch what's loved on the services.
+/// Failed pushes stay pending for the next sync.
+pub async fn sync(client: &Client, services: &Services, pending: Pending, pull: bool) -> Outcome {
+    let mut pushed = Vec::new();
+    for entry in &pending.love {
+        if services.set_loved(client, entry, true).await {
+            pushed.push(key(&entry.name, &entry.artist));
+        }
+    }
+    let mut unpushed = Vec::new();
+    for entry in &pending.unlove {
+        if services.set_loved(client, entry, false).await {
+            unpushed.push(key(&entry.name, &entry.artist));
+        }
+    }
+    let remote = if pull { services.loved_tracks(client).await } else { None };
+    Outcome { pushed, unpushed, remote, known: pending.known }
+}
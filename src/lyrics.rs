@@ -4,6 +4,16 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::config::{expand_home, LyricsConfig};
+use crate::player::TrackInfo;
+
+mod genius;
+mod musixmatch;
+mod netease;
+mod qq;
+pub mod romanize;
+pub(crate) mod spotify;
+
 #[derive(Debug, Deserialize)]
 pub struct LrclibResponse {
     #[serde(rename = "syncedLyrics")]
@@ -18,6 +28,8 @@ pub struct LrclibResponse {
 #[derive(Debug)]
 pub enum LyricsFetchResult {
     Found(Vec<LyricLine>),
+    /// Plain text without timings, plus the provider it came from
+    Unsynced(Vec<String>, String),
     Instrumental,
     None,
 }
@@ -26,15 +38,123 @@ pub enum LyricsFetchResult {
 pub struct LyricLine {
     pub timestamp_ms: u64,
     pub text: String,
+    // Word-level timings (Musixmatch richsync), None for line-synced sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<LyricWord>>,
+    // Translated line (NetEase/QQ ship these alongside the original)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<String>,
+    // The line in Latin letters, for Japanese, Chinese and Korean (see romanize.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub romanization: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LyricWord {
+    /// Absolute track position at which this word starts
+    pub timestamp_ms: u64,
+    pub text: String,
+}
+
+/// A lyrics source. Providers are tried in order until one has a result.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// `.lrc` files in `lyrics.local_dir`
+    Local,
+    Lrclib,
+    Musixmatch,
+    NetEase,
+    QQ,
+    Spotify,
+    /// `custom_lyrics_provider` hooks from Lua plugins
+    Plugin,
 }
 
 pub struct LyricsFetcher {
     client: Client,
+    config: LyricsConfig,
+    offline: bool,
 }
 
 impl LyricsFetcher {
-    pub fn new(client: Client) -> Self {
-        Self { client }
+    pub fn new(client: Client, config: LyricsConfig, offline: bool) -> Self {
+        Self { client, config, offline }
+    }
+
+    /// Provider chain, in priority order. A per-source order from the config wins,
+    /// otherwise the automatic order is used. Either way, providers that can't run
+    /// (missing token/cookie) are dropped.
+    fn providers(&self, track: &TrackInfo) -> Vec<Provider> {
+        let chain = match self.config.providers.get(&track.source) {
+            Some(order) => order.clone(),
+            None => self.default_providers(track),
+        };
+        chain.into_iter().filter(|p| self.is_available(*p, track)).collect()
+    }
+
+    fn is_available(&self, provider: Provider, track: &TrackInfo) -> bool {
+        match provider {
+            Provider::Local => true,
+            // Offline mode: only local files (and the disk cache) are allowed
+            _ if self.offline => false,
+            Provider::Musixmatch => self.config.musixmatch_token.is_some(),
+            Provider::Spotify => track.id.is_some() && self.config.spotify_sp_dc.is_some(),
+            Provider::Plugin => crate::plugins::has_lyrics_provider(),
+            _ => true,
+        }
+    }
+
+    /// CJK titles go to NetEase/QQ first since lrclib barely covers those catalogs.
+    fn default_providers(&self, track: &TrackInfo) -> Vec<Provider> {
+        let (artist, title) = (track.artist.as_str(), track.name.as_str());
+        let mut chain = vec![Provider::Lrclib];
+        if self.config.musixmatch_token.is_some() {
+            chain.push(Provider::Musixmatch);
+        }
+
+        if has_cjk(artist) || has_cjk(title) {
+            chain.insert(0, Provider::NetEase);
+            chain.insert(1, Provider::QQ);
+        } else {
+            chain.push(Provider::NetEase);
+            chain.push(Provider::QQ);
+        }
+
+        // Spotify's own lyrics are an exact match, no fuzzy search
+        if track.source == "Spotify" {
+            chain.insert(0, Provider::Spotify);
+        }
+        // The user's own files beat everything
+        // User plugins outrank the built-in online providers
+        chain.insert(0, Provider::Plugin);
+        chain.insert(0, Provider::Local);
+        chain
+    }
+
+    /// Look for `<artist> - <title>.lrc` or `<title>.lrc` in the local lyrics folder.
+    fn fetch_local(&self, artist: &str, title: &str) -> Result<LyricsFetchResult> {
+        let dir = match &self.config.local_dir {
+            Some(dir) => expand_home(dir),
+            None => return Ok(LyricsFetchResult::None),
+        };
+
+        let safe_artist = artist.replace('/', "_");
+        let safe_title = title.replace('/', "_");
+        let candidates = [
+            format!("{} - {}.lrc", safe_artist, safe_title),
+            format!("{}.lrc", safe_title),
+        ];
+
+        for name in candidates {
+            if let Ok(raw) = fs::read_to_string(dir.join(name)) {
+                let lines = parse_lrc(&raw);
+                if !lines.is_empty() {
+                    return Ok(LyricsFetchResult::Found(lines));
+                }
+            }
+        }
+        Ok(LyricsFetchResult::None)
     }
 
     fn get_cache_path(&self, artist: &str, title: &str) -> Option<PathBuf> {
@@ -72,8 +192,8 @@ impl LyricsFetcher {
         let t = title.to_lowercase();
         // Cut off at common delimiters
         let t = t.split("feat.").next().unwrap_or(&t);
-        let t = t.split("(feat").next().unwrap_or(&t);
-        let t = t.split("with").next().unwrap_or(&t);
+        let t = t.split("(feat").next().unwrap_or(t);
+        let t = t.split("with").next().unwrap_or(t);
         
         // Remove specific phrases
         let t = t.replace("remastered", "")
@@ -115,7 +235,9 @@ impl LyricsFetcher {
         primary.trim().to_string()
     }
 
-    pub async fn fetch(&self, artist: &str, title: &str, duration_ms: u64) -> Result<LyricsFetchResult> {
+    pub async fn fetch(&self, track: &TrackInfo) -> Result<LyricsFetchResult> {
+        let (artist, title, duration_ms) = (track.artist.as_str(), track.name.as_str(), track.duration_ms);
+
         // 0. Check Disk Cache 💾
         let cache_path = self.get_cache_path(artist, title);
         if let Some(path) = &cache_path {
@@ -125,64 +247,120 @@ impl LyricsFetcher {
             }
         }
 
-        let url = "https://lrclib.net/api/get";
+        // 1. Walk the provider chain. A provider error doesn't stop the chain,
+        // but is reported if nobody else finds anything.
+        let mut last_err = None;
+        for provider in self.providers(track) {
+            let result = match provider {
+                Provider::Local => self.fetch_local(artist, title),
+                Provider::Lrclib => self.fetch_lrclib(artist, title, duration_ms).await,
+                Provider::Musixmatch => {
+                    let token = self.config.musixmatch_token.as_deref().unwrap_or_default();
+                    musixmatch::fetch(&self.client, token, artist, title, duration_ms).await
+                }
+                Provider::NetEase => netease::fetch(&self.client, artist, title, duration_ms).await,
+                Provider::QQ => qq::fetch(&self.client, artist, title, duration_ms).await,
+                Provider::Spotify => {
+                    let sp_dc = self.config.spotify_sp_dc.as_deref().unwrap_or_default();
+                    let uri = track.id.as_deref().unwrap_or_default();
+                    spotify::fetch(&self.client, sp_dc, uri).await
+                }
+                Provider::Plugin => Self::fetch_plugin(track).await,
+            };
+
+            match result {
+                Ok(LyricsFetchResult::Found(lines)) => {
+                    if let Some(path) = &cache_path {
+                        self.save_to_cache(path, &lines);
+                    }
+                    return Ok(LyricsFetchResult::Found(lines));
+                }
+                Ok(LyricsFetchResult::Instrumental) => return Ok(LyricsFetchResult::Instrumental),
+                Ok(LyricsFetchResult::Unsynced(lines, source)) => return Ok(LyricsFetchResult::Unsynced(lines, source)),
+                Ok(LyricsFetchResult::None) => {}
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        // 2. Nothing synced anywhere: plain lyrics from Genius beat an empty pane
+        if self.offline {
+            return Ok(LyricsFetchResult::None);
+        }
+        match genius::fetch(&self.client, self.config.genius_token.as_deref(), artist, title).await {
+            Ok(Some(lines)) => return Ok(LyricsFetchResult::Unsynced(lines, "Genius".to_string())),
+            Ok(None) => {}
+            Err(e) => last_err = last_err.or(Some(e)),
+        }
+
+        match last_err {
+            Some(e) => Err(e),
+            None => Ok(LyricsFetchResult::None),
+        }
+    }
+
+    /// Lua hooks are synchronous, so run them off the async workers
+    async fn fetch_plugin(track: &TrackInfo) -> Result<LyricsFetchResult> {
+        let track = track.clone();
+        let found = tokio::task::spawn_blocking(move || crate::plugins::fetch_lyrics(&track)).await?;
+        let Some((name, raw)) = found else {
+            return Ok(LyricsFetchResult::None);
+        };
+        let lines = parse_lrc(&raw);
+        if !lines.is_empty() {
+            return Ok(LyricsFetchResult::Found(lines));
+        }
+        // No timestamps: show it as plain text
+        let plain = raw.lines().map(|l| l.trim().to_string()).collect();
+        Ok(LyricsFetchResult::Unsynced(plain, name))
+    }
+
+    async fn fetch_lrclib(&self, artist: &str, title: &str, duration_ms: u64) -> Result<LyricsFetchResult> {
         let duration_sec = duration_ms as f64 / 1000.0;
-        let duration_str = duration_sec.to_string();
-        
         let safe_title = Self::clean_title(title); 
-        
-        let params = [
-            ("artist_name", artist),
-            ("track_name", title),
-            ("duration", duration_str.as_str()),
-        ];
 
-        // 1. Try Exact (/get)
-        let resp = self.client.get(url).query(&params).send().await?;
-        if resp.status().is_success() {
-             let data: LrclibResponse = resp.json().await?;
-             let result = self.parse(data);
-             
-             if let LyricsFetchResult::Found(ref lines) = result {
-                 if let Some(path) = &cache_path {
-                     self.save_to_cache(path, lines);
-                 }
-             }
-             
-             match result {
-                 LyricsFetchResult::None => {}, // fallthrough
-                 _ => return Ok(result),
-             }
+        // 1. Try Exact (/get). It only matches within a couple of seconds of the
+        // given duration, and Apple Music often reports a few seconds off, so
+        // retry with the duration nudged either way before giving up.
+        for offset in [0.0, -3.0, 3.0] {
+            let result = self.get_exact(artist, title, duration_sec + offset).await?;
+            if let LyricsFetchResult::Found(_) | LyricsFetchResult::Instrumental = result {
+                return Ok(result);
+            }
         }
 
         // 2. Try Search (/search) with CLEAN title and ORIGINAL artist
         let search_res = self.search(artist, &safe_title, duration_ms).await?;
-        if let LyricsFetchResult::Found(ref lines) = search_res {
-            if let Some(path) = &cache_path {
-                 self.save_to_cache(path, lines);
-            }
-            return Ok(search_res);
-        }
-        if let LyricsFetchResult::Instrumental = search_res {
+        if let LyricsFetchResult::Found(_) | LyricsFetchResult::Instrumental = search_res {
              return Ok(search_res);
         }
 
         // 3. Try Search with PRIMARY artist (NEW Fallback) 🎯
         let safe_artist = Self::clean_artist(artist);
         if safe_artist != artist.to_lowercase() {
-             let primary_res = self.search(&safe_artist, &safe_title, duration_ms).await?;
-             if let LyricsFetchResult::Found(ref lines) = primary_res {
-                if let Some(path) = &cache_path {
-                     self.save_to_cache(path, lines);
-                }
-             }
-             Ok(primary_res)
+             self.search(&safe_artist, &safe_title, duration_ms).await
         } else {
              // Already tried with this artist name (it was clean)
              Ok(search_res)
         }
     }
 
+    async fn get_exact(&self, artist: &str, title: &str, duration_sec: f64) -> Result<LyricsFetchResult> {
+        let url = "https://lrclib.net/api/get";
+        let duration_str = duration_sec.max(0.0).to_string();
+        let params = [
+            ("artist_name", artist),
+            ("track_name", title),
+            ("duration", duration_str.as_str()),
+        ];
+
+        let resp = self.client.get(url).query(&params).send().await?;
+        if !resp.status().is_success() {
+            return Ok(LyricsFetchResult::None);
+        }
+        let data: LrclibResponse = resp.json().await?;
+        Ok(self.parse(data))
+    }
+
     async fn search(&self, artist: &str, title: &str, duration_ms: u64) -> Result<LyricsFetchResult> {
         let url = "https://lrclib.net/api/search";
         let q = format!("{} {}", artist, title);
@@ -193,26 +371,29 @@ impl LyricsFetcher {
         
         let target_dur = duration_ms as f64 / 1000.0;
 
-        // Helper filter closure
-        let is_valid = |r: &LrclibResponse| -> bool {
-            if let Some(dur) = r.duration {
-                (dur - target_dur).abs() <= 3.0
-            } else {
-                // If API doesn't return duration, strict mode says NO? 
-                // Or YES to be safe? 
-                // Let's say NO to be strict. Most entries have duration.
-                false
-            }
+        // Distance from the playing duration, None when out of tolerance.
+        // Entries without a duration are rejected (strict), most have one.
+        let distance = |r: &LrclibResponse| -> Option<f64> {
+            r.duration
+                .map(|dur| (dur - target_dur).abs())
+                .filter(|d| *d <= 3.0)
         };
 
-        // Find first synced OR instrumental THAT MATCHES DURATION
-        // Prioritize Synced loops
-        if let Some(found) = results.iter().find(|r| r.synced_lyrics.is_some() && is_valid(r)) {
+        // Closest duration wins, not just the first hit. Synced first.
+        let closest = |want: &dyn Fn(&LrclibResponse) -> bool| {
+            results.iter()
+                .filter(|r| want(r))
+                .filter_map(|r| distance(r).map(|d| (r, d)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(r, _)| r)
+        };
+
+        if let Some(found) = closest(&|r| r.synced_lyrics.is_some()) {
              return Ok(self.parse_ref(found));
         }
         
         // If no synced, check if any match is instrumental
-        if let Some(_found) = results.iter().find(|r| r.instrumental && is_valid(r)) {
+        if closest(&|r| r.instrumental).is_some() {
              return Ok(LyricsFetchResult::Instrumental);
         }
 
@@ -220,29 +401,7 @@ impl LyricsFetcher {
     }
 
     fn parse(&self, data: LrclibResponse) -> LyricsFetchResult {
-        if data.instrumental {
-            return LyricsFetchResult::Instrumental;
-        }
-        
-        // Reuse parsing logic via helper or just inline? 
-        // LrclibResponse ownership consumed here
-        let raw = data.synced_lyrics.or(data.plain_lyrics);
-        if raw.is_none() { return LyricsFetchResult::None; }
-        
-        let mut lines = Vec::new();
-        for line in raw.unwrap().lines() {
-            if let Some(idx) = line.find(']') {
-                 if line.starts_with('[') {
-                    let timestamp_str = &line[1..idx];
-                    let text = line[idx+1..].trim().to_string();
-                    if let Some(ms) = self.parse_timestamp(timestamp_str) {
-                         lines.push(LyricLine { timestamp_ms: ms, text });
-                    }
-                 }
-            }
-        }
-        
-        if lines.is_empty() { LyricsFetchResult::None } else { LyricsFetchResult::Found(lines) }
+        self.parse_ref(&data)
     }
     
     // Helper for reference (Search)
@@ -250,46 +409,116 @@ impl LyricsFetcher {
         if data.instrumental {
             return LyricsFetchResult::Instrumental;
         }
-        let raw = data.synced_lyrics.as_ref().or(data.plain_lyrics.as_ref());
-         if raw.is_none() { return LyricsFetchResult::None; }
-         
-         // ... duplicate logic or clone? Clone data is cheap (strings)
-         // Let's just clone and call parse
-         // Actually LrclibResponse is not Clone. 
-         // Manual parse:
-        let mut lines = Vec::new();
-        for line in raw.unwrap().lines() {
-            if let Some(idx) = line.find(']') {
-                 if line.starts_with('[') {
-                    let timestamp_str = &line[1..idx];
-                    let text = line[idx+1..].trim().to_string();
-                    if let Some(ms) = self.parse_timestamp(timestamp_str) {
-                         lines.push(LyricLine { timestamp_ms: ms, text });
-                    }
-                 }
+        let raw = match data.synced_lyrics.as_ref().or(data.plain_lyrics.as_ref()) {
+            Some(raw) => raw,
+            None => return LyricsFetchResult::None,
+        };
+        
+        let lines = parse_lrc(raw);
+        if lines.is_empty() { LyricsFetchResult::None } else { LyricsFetchResult::Found(lines) }
+    }
+}
+
+/// True if the text contains Chinese, Japanese kana or Korean hangul.
+pub(crate) fn has_cjk(s: &str) -> bool {
+    s.chars().any(|c| matches!(c as u32,
+        0x3040..=0x30FF   // Hiragana + Katakana
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0x1100..=0x11FF // Hangul Jamo
+        | 0xAC00..=0xD7AF // Hangul Syllables
+    ))
+}
+
+/// Attach a translated LRC document to its original lines by timestamp.
+pub(crate) fn attach_translations(lines: &mut [LyricLine], translated_lrc: &str) {
+    let translated = parse_lrc(translated_lrc);
+    for line in lines.iter_mut() {
+        if let Some(t) = translated.iter().find(|t| t.timestamp_ms == line.timestamp_ms) {
+            if !t.text.is_empty() && t.text != line.text {
+                line.translation = Some(t.text.clone());
             }
         }
-         if lines.is_empty() { LyricsFetchResult::None } else { LyricsFetchResult::Found(lines) }
     }
+}
 
-    fn parse_timestamp(&self, ts: &str) -> Option<u64> {
-        let parts: Vec<&str> = ts.split(':').collect();
-        if parts.len() != 2 { return None; }
-        
-        let min: u64 = parts[0].parse().ok()?;
-        let sec_parts: Vec<&str> = parts[1].split('.').collect();
-        let sec: u64 = sec_parts[0].parse().ok()?;
-        let ms: u64 = if sec_parts.len() > 1 {
-            let frac = sec_parts[1];
-            if frac.len() == 2 {
-                frac.parse::<u64>().ok()? * 10
-            } else {
-                frac.parse::<u64>().ok()?
+/// Parse an LRC document into timed lines. Handles stacked tags
+/// (`[00:12.00][00:45.00]chorus`), enhanced LRC's word tags
+/// (`[00:12.00]<00:12.00>Hello <00:12.40>world`) and skips metadata tags like `[ar:...]`.
+pub(crate) fn parse_lrc(raw: &str) -> Vec<LyricLine> {
+    let mut lines = Vec::new();
+    for line in raw.lines() {
+        let mut rest = line.trim();
+        let mut stamps = Vec::new();
+        while rest.starts_with('[') {
+            let Some(idx) = rest.find(']') else { break };
+            match parse_timestamp(&rest[1..idx]) {
+                Some(ms) => stamps.push(ms),
+                None => break,
             }
-        } else {
-            0
-        };
-        
-        Some(min * 60000 + sec * 1000 + ms)
+            rest = &rest[idx + 1..];
+        }
+
+        // Word tags are timed for the first stamp; repeats shift them along
+        let first = stamps.first().copied().unwrap_or_default();
+        let (text, words) = parse_words(rest, first);
+        for ms in stamps {
+            let words = words.clone().map(|words| words.into_iter()
+                .map(|word| LyricWord { timestamp_ms: (word.timestamp_ms + ms).saturating_sub(first), ..word })
+                .collect());
+            lines.push(LyricLine { timestamp_ms: ms, text: text.clone(), words, translation: None, romanization: None });
+        }
+    }
+    lines.sort_by_key(|l| l.timestamp_ms);
+    lines
+}
+
+/// Strip `<mm:ss.xx>` word tags from the text of a line starting at
+/// `line_ms`, keeping them as word timings. Untagged text at the start begins
+/// with the line; a closing tag with nothing after it only ends the last word.
+fn parse_words(raw: &str, line_ms: u64) -> (String, Option<Vec<LyricWord>>) {
+    let mut text = String::new();
+    let mut words = Vec::new();
+    let mut start = line_ms;
+    let mut tagged = false;
+    let mut rest = raw.trim();
+    loop {
+        let tag = rest.find('<').and_then(|open| {
+            let close = open + rest[open..].find('>')?;
+            Some((open, close, parse_timestamp(&rest[open + 1..close])?))
+        });
+        let fragment = tag.map_or(rest, |(open, _, _)| &rest[..open]);
+        if !fragment.trim().is_empty() {
+            words.push(LyricWord { timestamp_ms: start, text: fragment.trim().to_string() });
+        }
+        text.push_str(fragment);
+        let Some((_, close, ms)) = tag else { break };
+        start = ms;
+        tagged = true;
+        rest = &rest[close + 1..];
     }
+    let text = text.trim().to_string();
+    // A line without tags is just text, not one long word
+    (text, tagged.then_some(words))
+}
+
+fn parse_timestamp(ts: &str) -> Option<u64> {
+    let parts: Vec<&str> = ts.split(':').collect();
+    if parts.len() != 2 { return None; }
+    
+    let min: u64 = parts[0].parse().ok()?;
+    let sec_parts: Vec<&str> = parts[1].split('.').collect();
+    let sec: u64 = sec_parts[0].parse().ok()?;
+    let ms: u64 = if sec_parts.len() > 1 {
+        let frac = sec_parts[1];
+        if frac.len() == 2 {
+            frac.parse::<u64>().ok()? * 10
+        } else {
+            frac.parse::<u64>().ok()?
+        }
+    } else {
+        0
+    };
+    
+    Some(min * 60000 + sec * 1000 + ms)
 }
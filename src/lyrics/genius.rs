@@ -0,0 +1,117 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde_json::Value;
+
+use super::LyricsFetcher;
+
+const API_SEARCH_URL: &str = "https://api.genius.com/search";
+const PUBLIC_SEARCH_URL: &str = "https://genius.com/api/search/song";
+
+/// Find the song on Genius and scrape its plain (unsynced) lyrics.
+/// Uses the official API when a token is configured, the public web search otherwise.
+pub async fn fetch(client: &Client, token: Option<&str>, artist: &str, title: &str) -> Result<Option<Vec<String>>> {
+    let q = format!("{} {}", LyricsFetcher::clean_artist(artist), LyricsFetcher::clean_title(title));
+
+    let hits: Vec<Value> = match token {
+        Some(token) => {
+            let data: Value = client.get(API_SEARCH_URL)
+                .query(&[("q", q.as_str())])
+                .bearer_auth(token)
+                .send().await?
+                .json().await?;
+            data.pointer("/response/hits").and_then(Value::as_array).cloned().unwrap_or_default()
+        }
+        None => {
+            let data: Value = client.get(PUBLIC_SEARCH_URL)
+                .query(&[("q", q.as_str()), ("per_page", "5")])
+                .send().await?
+                .json().await?;
+            data.pointer("/response/sections/0/hits").and_then(Value::as_array).cloned().unwrap_or_default()
+        }
+    };
+
+    // Guard against covers/parodies: the primary artist must match
+    let wanted_artist = LyricsFetcher::clean_artist(artist);
+    let url = hits.iter().find_map(|hit| {
+        let result = hit.get("result")?;
+        let hit_artist = result.pointer("/primary_artist/name")?.as_str()?.to_lowercase();
+        if hit_artist.contains(&wanted_artist) || wanted_artist.contains(&hit_artist) {
+            result.get("url")?.as_str().map(str::to_string)
+        } else {
+            None
+        }
+    });
+    let Some(url) = url else { return Ok(None) };
+
+    let html = client.get(&url).send().await?.text().await?;
+    let lines = extract_lyrics(&html);
+    Ok(if lines.is_empty() { None } else { Some(lines) })
+}
+
+/// Pull the text out of every `data-lyrics-container` div on a song page.
+fn extract_lyrics(html: &str) -> Vec<String> {
+    let marker = "data-lyrics-container=\"true\"";
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(pos) = rest.find(marker) {
+        let after = &rest[pos..];
+        let Some(open_end) = after.find('>') else { break };
+        let body = &after[open_end + 1..];
+
+        // Walk to the matching </div>, tracking nested divs
+        let mut depth = 1;
+        let mut i = 0;
+        while depth > 0 && i < body.len() {
+            if body[i..].starts_with("<div") {
+                depth += 1;
+            } else if body[i..].starts_with("</div") {
+                depth -= 1;
+                if depth == 0 { break; }
+            }
+            i += body[i..].chars().next().map(char::len_utf8).unwrap_or(1);
+        }
+
+        text.push_str(&strip_tags(&body[..i]));
+        text.push('\n');
+        rest = &body[i..];
+    }
+
+    decode_entities(&text)
+        .lines()
+        .map(|l| l.trim().to_string())
+        // Collapse runs of blank lines into a single stanza break
+        .fold(Vec::new(), |mut acc: Vec<String>, line| {
+            if !(line.is_empty() && acc.last().map(|l| l.is_empty()).unwrap_or(true)) {
+                acc.push(line);
+            }
+            acc
+        })
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+    let mut tag = String::new();
+    for c in html.chars() {
+        match c {
+            '<' => { in_tag = true; tag.clear(); }
+            '>' if in_tag => {
+                in_tag = false;
+                if tag.starts_with("br") { out.push('\n'); }
+            }
+            _ if in_tag => tag.push(c),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{LyricLine, LyricWord, LyricsFetchResult};
+
+const MACRO_URL: &str = "https://apic-desktop.musixmatch.com/ws/1.1/macro.subtitles.get";
+
+// Richsync body: a JSON *string* holding an array of timed lines
+#[derive(Debug, Deserialize)]
+struct RichsyncLine {
+    /// Line start, seconds
+    ts: f64,
+    /// Words (or word fragments) with offsets relative to `ts`
+    l: Vec<RichsyncWord>,
+    /// Full line text
+    x: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RichsyncWord {
+    c: String,
+    o: f64,
+}
+
+// Subtitle body: the line-synced fallback when no richsync exists
+#[derive(Debug, Deserialize)]
+struct SubtitleLine {
+    text: String,
+    time: SubtitleTime,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubtitleTime {
+    total: f64,
+}
+
+/// Query Musixmatch's desktop macro endpoint. Prefers richsync (word-level)
+/// lyrics and falls back to regular line-synced subtitles.
+pub async fn fetch(client: &Client, token: &str, artist: &str, title: &str, duration_ms: u64) -> Result<LyricsFetchResult> {
+    let duration_str = (duration_ms / 1000).to_string();
+    let params = [
+        ("format", "json"),
+        ("namespace", "lyrics_richsynched"),
+        ("subtitle_format", "mxm"),
+        ("optional_calls", "track.richsync"),
+        ("app_id", "web-desktop-app-v1.0"),
+        ("q_artist", artist),
+        ("q_track", title),
+        ("q_duration", duration_str.as_str()),
+        ("f_subtitle_length", duration_str.as_str()),
+        ("usertoken", token),
+    ];
+
+    let resp = client.get(MACRO_URL)
+        .query(&params)
+        .header("Cookie", "x-mxm-token-guid=")
+        .send().await?;
+    let data: Value = resp.json().await?;
+
+    // Musixmatch wraps everything as message.header.status_code / message.body
+    let status = data.pointer("/message/header/status_code").and_then(Value::as_u64).unwrap_or(0);
+    if status == 401 {
+        anyhow::bail!("Musixmatch token rejected");
+    }
+
+    let calls = match data.pointer("/message/body/macro_calls") {
+        Some(c) => c,
+        None => return Ok(LyricsFetchResult::None),
+    };
+
+    let instrumental = calls
+        .pointer("/matcher.track.get/message/body/track/instrumental")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) == 1;
+    if instrumental {
+        return Ok(LyricsFetchResult::Instrumental);
+    }
+
+    // 1. Richsync (word-level) 🎯
+    if let Some(body) = calls
+        .pointer("/track.richsync.get/message/body/richsync/richsync_body")
+        .and_then(Value::as_str)
+    {
+        let lines = parse_richsync(body);
+        if !lines.is_empty() {
+            return Ok(LyricsFetchResult::Found(lines));
+        }
+    }
+
+    // 2. Line-synced subtitles
+    if let Some(body) = calls
+        .pointer("/track.subtitles.get/message/body/subtitle_list/0/subtitle/subtitle_body")
+        .and_then(Value::as_str)
+    {
+        let lines = parse_subtitles(body);
+        if !lines.is_empty() {
+            return Ok(LyricsFetchResult::Found(lines));
+        }
+    }
+
+    Ok(LyricsFetchResult::None)
+}
+
+fn parse_richsync(body: &str) -> Vec<LyricLine> {
+    let raw: Vec<RichsyncLine> = serde_json::from_str(body).unwrap_or_default();
+    raw.into_iter()
+        .map(|line| {
+            let start_ms = (line.ts * 1000.0) as u64;
+            // Musixmatch emits the spaces between words as their own fragments
+            let words = line.l.into_iter()
+                .filter(|w| !w.c.trim().is_empty())
+                .map(|w| LyricWord {
+                    timestamp_ms: start_ms + (w.o * 1000.0) as u64,
+                    text: w.c,
+                })
+                .collect();
+            LyricLine {
+                timestamp_ms: start_ms,
+                text: line.x.trim().to_string(),
+                words: Some(words),
+                translation: None,
+                romanization: None,
+            }
+        })
+        .collect()
+}
+
+fn parse_subtitles(body: &str) -> Vec<LyricLine> {
+    let raw: Vec<SubtitleLine> = serde_json::from_str(body).unwrap_or_default();
+    raw.into_iter()
+        .map(|line| LyricLine {
+            timestamp_ms: (line.time.total * 1000.0) as u64,
+            text: line.text.trim().to_string(),
+            words: None,
+            translation: None,
+            romanization: None,
+        })
+        .collect()
+}
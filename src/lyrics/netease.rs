@@ -0,0 +1,82 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::{attach_translations, parse_lrc, LyricsFetchResult};
+
+const SEARCH_URL: &str = "https://music.163.com/api/search/get";
+const LYRIC_URL: &str = "https://music.163.com/api/song/lyric";
+const REFERER: &str = "https://music.163.com/";
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    result: Option<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    #[serde(default)]
+    songs: Vec<Song>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Song {
+    id: u64,
+    /// Milliseconds
+    duration: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LyricResponse {
+    lrc: Option<LrcBody>,
+    tlyric: Option<LrcBody>,
+    #[serde(default)]
+    nolyric: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LrcBody {
+    lyric: Option<String>,
+}
+
+/// NetEase Cloud Music: search by "artist title", take the first song within
+/// 3s of the playing duration, then pull its LRC plus the translated LRC.
+pub async fn fetch(client: &Client, artist: &str, title: &str, duration_ms: u64) -> Result<LyricsFetchResult> {
+    let q = format!("{} {}", artist, title);
+    let params = [("s", q.as_str()), ("type", "1"), ("limit", "10")];
+
+    let resp = client.get(SEARCH_URL)
+        .query(&params)
+        .header("Referer", REFERER)
+        .send().await?;
+    let search: SearchResponse = resp.json().await?;
+    let songs = search.result.map(|r| r.songs).unwrap_or_default();
+
+    let song = songs.iter().find(|s| {
+        duration_ms == 0 || s.duration.abs_diff(duration_ms) <= 3000
+    });
+    let Some(song) = song else { return Ok(LyricsFetchResult::None) };
+
+    let id = song.id.to_string();
+    let params = [("id", id.as_str()), ("lv", "1"), ("tv", "-1")];
+    let resp = client.get(LYRIC_URL)
+        .query(&params)
+        .header("Referer", REFERER)
+        .send().await?;
+    let data: LyricResponse = resp.json().await?;
+
+    if data.nolyric {
+        return Ok(LyricsFetchResult::Instrumental);
+    }
+
+    let raw = data.lrc.and_then(|l| l.lyric).unwrap_or_default();
+    let mut lines = parse_lrc(&raw);
+    if lines.is_empty() {
+        return Ok(LyricsFetchResult::None);
+    }
+
+    if let Some(translated) = data.tlyric.and_then(|l| l.lyric) {
+        attach_translations(&mut lines, &translated);
+    }
+    Ok(LyricsFetchResult::Found(lines))
+}
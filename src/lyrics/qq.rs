@@ -0,0 +1,102 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::{attach_translations, parse_lrc, LyricsFetchResult};
+
+const SEARCH_URL: &str = "https://c.y.qq.com/soso/fcgi-bin/client_search_cp";
+const LYRIC_URL: &str = "https://c.y.qq.com/lyric/fcgi-bin/fcg_query_lyric_new.fcg";
+const REFERER: &str = "https://y.qq.com/";
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    data: Option<SearchData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchData {
+    song: SongList,
+}
+
+#[derive(Debug, Deserialize)]
+struct SongList {
+    #[serde(default)]
+    list: Vec<Song>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Song {
+    songmid: String,
+    /// Seconds
+    interval: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LyricResponse {
+    #[serde(default)]
+    lyric: String,
+    #[serde(default)]
+    trans: String,
+}
+
+/// QQ Music: same shape as NetEase, search then fetch LRC + translation.
+/// `nobase64=1` returns the LRC as text, with punctuation as HTML entities.
+pub async fn fetch(client: &Client, artist: &str, title: &str, duration_ms: u64) -> Result<LyricsFetchResult> {
+    let q = format!("{} {}", artist, title);
+    let params = [("w", q.as_str()), ("format", "json"), ("p", "1"), ("n", "10")];
+
+    let resp = client.get(SEARCH_URL)
+        .query(&params)
+        .header("Referer", REFERER)
+        .send().await?;
+    let search: SearchResponse = resp.json().await?;
+    let songs = search.data.map(|d| d.song.list).unwrap_or_default();
+
+    let song = songs.iter().find(|s| {
+        duration_ms == 0 || (s.interval * 1000).abs_diff(duration_ms) <= 3000
+    });
+    let Some(song) = song else { return Ok(LyricsFetchResult::None) };
+
+    let params = [("songmid", song.songmid.as_str()), ("format", "json"), ("nobase64", "1")];
+    let resp = client.get(LYRIC_URL)
+        .query(&params)
+        .header("Referer", REFERER)
+        .send().await?;
+    let data: LyricResponse = resp.json().await?;
+
+    let mut lines = parse_lrc(&decode_entities(&data.lyric));
+    if lines.is_empty() {
+        return Ok(LyricsFetchResult::None);
+    }
+
+    if !data.trans.is_empty() {
+        attach_translations(&mut lines, &decode_entities(&data.trans));
+    }
+    Ok(LyricsFetchResult::Found(lines))
+}
+
+/// Decode the numeric HTML entities (`&#58;`, `&#10;`, ...) QQ uses in LRC text.
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("&#") {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start + 2..];
+        let decoded = tail.find(';').and_then(|end| {
+            let code = tail[..end].parse::<u32>().ok()?;
+            Some((char::from_u32(code)?, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push_str("&#");
+                rest = tail;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Romanization 🔤
+// Lyrics in Japanese, Chinese or Korean get a line in Latin letters under each
+// one, to sing along without reading the script. Japanese goes through
+// `kakasi` and Chinese through the `pypinyin` Python package, when they're
+// installed; Hangul is regular enough to spell out here.
+
+/// Whether `language` (ISO 639-1) is one this can romanize
+pub fn supported(language: &str) -> bool {
+    matches!(language, "ja" | "zh" | "ko")
+}
+
+/// One romanized line per line of `lines` (empty where there was nothing to
+/// romanize). Blocking: Japanese and Chinese run a program.
+pub fn romanize(lines: &[String], language: &str) -> Result<Vec<String>> {
+    let romanized = match language {
+        "ja" => pipe("kakasi", &["-i", "utf8", "-o", "utf8", "-Ja", "-Ha", "-Ka", "-Ea", "-s"], lines)
+            .context("romanizing Japanese needs kakasi")?,
+        "zh" => pipe("python3", &["-c", PINYIN_SCRIPT], lines)
+            .context("romanizing Chinese needs pypinyin (pip install pypinyin)")?,
+        "ko" => lines.iter().map(|line| hangul(line)).collect(),
+        other => anyhow::bail!("no romanization for {}", other),
+    };
+    // A line that comes back as it went in (English hook, ad-lib) needs no second line
+    Ok(lines.iter().zip(romanized)
+        .map(|(line, romanized)| if romanized.trim() == line.trim() { String::new() } else { romanized.trim().to_string() })
+        .collect())
+}
+
+/// Tone-marked pinyin, a line in for a line out
+const PINYIN_SCRIPT: &str = "import sys\n\
+from pypinyin import lazy_pinyin, Style\n\
+for line in sys.stdin:\n    print(' '.join(lazy_pinyin(line.rstrip('\\n'), style=Style.TONE)))";
+
+/// Feed the lines to `program` and read as many back
+fn pipe(program: &str, args: &[&str], lines: &[String]) -> Result<Vec<String>> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(lines.join("\n").as_bytes())?;
+        stdin.write_all(b"\n")?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!("`{}` failed", program);
+    }
+    let out: Vec<String> = String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect();
+    if out.len() != lines.len() {
+        anyhow::bail!("`{}` returned {} lines for {}", program, out.len(), lines.len());
+    }
+    Ok(out)
+}
+
+/// Revised Romanization, syllable by syllable (no sound changes across them)
+fn hangul(text: &str) -> String {
+    const INITIALS: [&str; 19] = ["g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p", "h"];
+    const VOWELS: [&str; 21] = [
+        "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we", "wi", "yu", "eu", "ui", "i",
+    ];
+    const FINALS: [&str; 28] = [
+        "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p", "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+    ];
+    let mut out = String::new();
+    for c in text.chars() {
+        // Each syllable block is (initial * 21 + vowel) * 28 + final past U+AC00
+        let Some(index) = (c as u32).checked_sub(0xAC00).filter(|&i| i < 11172) else {
+            out.push(c);
+            continue;
+        };
+        let index = index as usize;
+        out.push_str(INITIALS[index / 588]);
+        out.push_str(VOWELS[index % 588 / 28]);
+        out.push_str(FINALS[index % 28]);
+    }
+    out
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{LyricLine, LyricsFetchResult};
+
+const TOKEN_URL: &str = "https://open.spotify.com/get_access_token";
+const LYRICS_URL: &str = "https://spclient.wg.spotify.com/color-lyrics/v2/track";
+
+// Web player tokens live for an hour, no need to re-auth on every track change
+static ACCESS_TOKEN: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenResponse {
+    access_token: String,
+    access_token_expiration_timestamp_ms: u64,
+    #[serde(default)]
+    is_anonymous: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LyricsResponse {
+    lyrics: SpotifyLyrics,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpotifyLyrics {
+    sync_type: String,
+    lines: Vec<SpotifyLine>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpotifyLine {
+    /// Milliseconds, sent as a string
+    start_time_ms: String,
+    words: String,
+}
+
+/// Web player access token from the sp_dc cookie, also used for the Web API
+pub(crate) async fn access_token(client: &Client, sp_dc: &str) -> Result<String> {
+    if let Ok(guard) = ACCESS_TOKEN.lock() {
+        if let Some((token, expires)) = guard.as_ref() {
+            if Instant::now() < *expires {
+                return Ok(token.clone());
+            }
+        }
+    }
+
+    let data: TokenResponse = client.get(TOKEN_URL)
+        .query(&[("reason", "transport"), ("productType", "web_player")])
+        .header("Cookie", format!("sp_dc={}", sp_dc))
+        .header("App-Platform", "WebPlayer")
+        .send().await?
+        .json().await?;
+
+    if data.is_anonymous {
+        anyhow::bail!("Spotify sp_dc cookie is invalid or expired");
+    }
+
+    // Refresh a minute early to avoid racing the expiry
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let ttl_ms = data.access_token_expiration_timestamp_ms.saturating_sub(now_ms).saturating_sub(60_000);
+    let expires = Instant::now() + Duration::from_millis(ttl_ms);
+
+    if let Ok(mut guard) = ACCESS_TOKEN.lock() {
+        *guard = Some((data.access_token.clone(), expires));
+    }
+    Ok(data.access_token)
+}
+
+/// Spotify's own lyrics for the exact playing track. `track_uri` is the
+/// `spotify:track:<id>` URI reported by the desktop app.
+pub async fn fetch(client: &Client, sp_dc: &str, track_uri: &str) -> Result<LyricsFetchResult> {
+    let Some(track_id) = track_uri.strip_prefix("spotify:track:") else {
+        // Local files and podcasts have no lyrics endpoint
+        return Ok(LyricsFetchResult::None);
+    };
+
+    let token = access_token(client, sp_dc).await?;
+    let resp = client.get(format!("{}/{}", LYRICS_URL, track_id))
+        .query(&[("format", "json"), ("market", "from_token")])
+        .bearer_auth(token)
+        .header("App-Platform", "WebPlayer")
+        .send().await?;
+
+    if resp.status() == StatusCode::NOT_FOUND {
+        return Ok(LyricsFetchResult::None);
+    }
+    let data: LyricsResponse = resp.error_for_status()?.json().await?;
+
+    // Spotify marks instrumental gaps with a lone music note
+    let lines: Vec<LyricLine> = data.lyrics.lines.into_iter()
+        .map(|l| LyricLine {
+            timestamp_ms: l.start_time_ms.parse().unwrap_or(0),
+            text: if l.words == "♪" { String::new() } else { l.words },
+            words: None,
+            translation: None,
+            romanization: None,
+        })
+        .collect();
+
+    if lines.is_empty() {
+        return Ok(LyricsFetchResult::None);
+    }
+
+    if data.lyrics.sync_type == "UNSYNCED" {
+        let plain = lines.into_iter().map(|l| l.text).collect();
+        return Ok(LyricsFetchResult::Unsynced(plain, "Spotify".to_string()));
+    }
+    Ok(LyricsFetchResult::Found(lines))
+}
@@ -1,11 +1,11 @@
 use anyhow::Result;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, EventStream},
+    event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, EventStream},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{io, time::Duration};
+use ratatui::Terminal;
+use std::{collections::VecDeque, io::{self, Write}, sync::Arc, time::Duration};
 use tokio::sync::mpsc;
 use futures::{StreamExt};
 
@@ -13,14 +13,53 @@ use futures::{StreamExt};
 
 mod app;
 mod artwork;
+mod audio;
+mod banned;
+mod base64;
+mod bookmarks;
+mod clock;
+mod config;
+mod doctor;
+mod focus;
+mod glyphs;
+mod hash;
+mod history;
+mod ipc;
+mod keychain;
+mod keys;
+mod language;
+mod lastfm;
+mod listenbrainz;
+mod loved;
+mod notification;
+mod output;
 mod theme; 
 mod lyrics;
 mod player; 
+mod replay;
+mod report;
+mod scrobble;
+mod plugins;
+mod podcast;
+mod pomodoro;
+mod power;
+mod session;
+mod snapshot;
+mod spotify_api;
+mod state;
+mod status;
+mod stats;
+mod taskbar;
+mod tempo;
+mod translate;
+mod typing;
 mod ui;
+mod update;
 
 use app::{App, ArtworkState, LyricsState};
 use player::{TrackInfo}; 
 use crate::lyrics::{LyricsFetcher}; 
+use session::SessionBackend;
 use artwork::{ArtworkRenderer}; 
 
 
@@ -30,17 +69,400 @@ use theme::{Theme};
 enum AppEvent {
     Input(Event),
     TrackUpdate(Option<TrackInfo>),
+    PlayersUpdate(Vec<player::PlayerSummary>),
+    PlayerError(player::PlayerError),
     LyricsUpdate(String, LyricsState),
-    ArtworkUpdate(ArtworkState),
+    TempoUpdate(String, f32),
+    // Machine translation of a track's lyrics, one per line (or why it failed)
+    TranslationUpdate(String, Result<Vec<String>, String>),
+    // Romanized lyrics, one per line (or why they couldn't be)
+    RomanizationUpdate(String, Result<Vec<String>, String>),
+    // Context and private session from the Web API (None when the lookup failed)
+    SpotifyStatusUpdate(String, Option<spotify_api::PlayerStatus>),
+    AnalysisUpdate(String, spotify_api::Analysis),
+    // Shuffle and repeat, each None when the player wouldn't say
+    ModesUpdate(Option<bool>, Option<player::Repeat>),
+    // Whether a Spotify track (by URI) is in Liked Songs
+    SavedUpdate(String, bool),
+    ArtworkUpdate(String, ArtworkState),
+    // Half-block lines for (artwork key, pane size), rendered off the draw path
+    ArtworkRendered(String, (u16, u16), artwork::Rendered),
     ThemeUpdate(Theme),
     Tick,
+    // Play/Pause or Next pressed on a now-playing notification
+    NotificationAction(notification::Action),
+    OutputDevice(Option<String>),
+    OutputDevices(Vec<output::Device>),
+    // What's up next for the queue pane, or why there's nothing to show
+    QueueUpdate(Result<Vec<TrackInfo>, String>),
+    // How a Web API action sent from a task went (shuffle, repeat, device transfer)
+    Toast(String),
+    // A loved-tracks sync with Last.fm / ListenBrainz finished
+    LovedSynced(loved::Outcome),
+    // Resource numbers for the debug overlay, once a second while it's open
+    DebugSample(stats::Sample),
+    // Fetches failing to connect (false), or the network answering again (true)
+    Connectivity(bool),
+    // SIGINT from outside the terminal (raw mode turns Ctrl+C into a key)
+    Interrupt,
+    // SIGTERM: leave now, without asking (a service manager is stopping us)
+    Terminate,
+    // config.toml changed on disk: the new config, or why it didn't parse
+    ConfigReload(Result<Box<config::Config>, String>),
+    // `vyom attach` connected with its terminal size, then its input
+    Attach(u64, Box<dyn session::Client>, u16, u16),
+    RemoteInput(u64, Event),
+    // A `vyom ctl` command and where its answer goes
+    Command(String, tokio::sync::oneshot::Sender<String>),
+    // `--replay` ran out of recorded events
+    ReplayFinished,
+}
+
+/// The one HTTP client every fetcher shares, with the user's proxy/timeout/TLS settings.
+fn build_http_client(net: &config::NetworkConfig) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .user_agent("vyom-rs/1.0.1")
+        .connect_timeout(Duration::from_secs(net.connect_timeout_secs))
+        .timeout(Duration::from_secs(net.timeout_secs))
+        .danger_accept_invalid_certs(net.accept_invalid_certs);
+
+    if let Some(proxy_url) = &net.proxy {
+        match reqwest::Proxy::all(proxy_url) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => eprintln!("Ignoring invalid proxy {}: {}", proxy_url, e),
+        }
+    }
+
+    if let Some(path) = &net.ca_cert {
+        let cert = std::fs::read(config::expand_home(path))
+            .map_err(anyhow::Error::from)
+            .and_then(|pem| Ok(reqwest::Certificate::from_pem(&pem)?));
+        match cert {
+            Ok(cert) => builder = builder.add_root_certificate(cert),
+            Err(e) => eprintln!("Ignoring CA certificate {}: {}", path, e),
+        }
+    }
+
+    builder.build().unwrap_or_default()
+}
+
+/// Tell plugins (scrobblers, presence...) about a new track, unless it plays
+/// in a Spotify private session (or might: the lookup hasn't answered). Plugins listed in `quiet_during_focus` also
+/// sit out while Focus / Do Not Disturb is on, and so does the notification.
+fn announce_track(app: &App, track: &TrackInfo) {
+    if (app.private_session || app.private_session_pending) && !app.config.plugins.notify_private {
+        return;
+    }
+    let quiet = &app.config.plugins.quiet_during_focus;
+    let notify = app.config.notifications.enabled;
+    let focus = (notify || !quiet.is_empty()) && focus::is_active();
+    plugins::on_track_change(track, |name| focus && quiet.iter().any(|q| q == name));
+    if notify && !focus {
+        notification::show(track);
+    }
+}
+
+/// Cheap request to tell when the network is back
+const CONNECTIVITY_PROBE_URL: &str = "https://lrclib.net";
+
+/// Connection failures and timeouts, as opposed to a server answering with an error
+fn is_network_error(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_connect() || e.is_timeout())
+    })
+}
+
+/// Romanize the current lyrics in the background when their script takes it
+/// (and they aren't in the user's own language). Failures, like a missing
+/// kakasi, only come back as a toast when `report_errors`.
+fn spawn_romanize(tx: &mpsc::Sender<AppEvent>, app: &App, id: &str, report_errors: bool) {
+    let (LyricsState::Loaded(lines), Some(language)) = (&app.lyrics, app.lyrics_language) else { return };
+    if !lyrics::romanize::supported(language) || app.lyrics_in_user_language() || lines.iter().any(|l| l.romanization.is_some()) {
+        return;
+    }
+    let texts: Vec<String> = lines.iter().map(|l| l.text.clone()).collect();
+    let (tx, id) = (tx.clone(), id.to_string());
+    tokio::spawn(async move {
+        let result = tokio::task::spawn_blocking(move || lyrics::romanize::romanize(&texts, language)).await;
+        let result = match result {
+            Ok(Ok(romanized)) => Ok(romanized),
+            Ok(Err(e)) if report_errors => Err(e.to_string()),
+            _ => return,
+        };
+        let _ = tx.send(AppEvent::RomanizationUpdate(id, result)).await;
+    });
+}
+
+/// Fetch lyrics in the background and report back as a LyricsUpdate keyed by `id`.
+fn spawn_lyrics_fetch(
+    tx: mpsc::Sender<AppEvent>,
+    client: reqwest::Client,
+    lyrics_config: config::LyricsConfig,
+    offline: bool,
+    track: TrackInfo,
+    id: String,
+) {
+    tokio::spawn(async move {
+        use crate::lyrics::LyricsFetchResult;
+        let result = if podcast::is_episode(&track) {
+            // Episodes have show notes instead of lyrics
+            if offline {
+                Ok(LyricsState::NotFound)
+            } else {
+                podcast::fetch_show_notes(&client, lyrics_config.spotify_sp_dc.as_deref(), &track).await
+                    .map(|notes| notes.map_or(LyricsState::NotFound, LyricsState::ShowNotes))
+            }
+        } else {
+            let fetcher = LyricsFetcher::new(client, lyrics_config, offline);
+            fetcher.fetch(&track).await.map(|result| match result {
+                LyricsFetchResult::Found(lyrics) => LyricsState::Loaded(lyrics),
+                LyricsFetchResult::Unsynced(lines, source) => LyricsState::Unsynced(lines, source),
+                LyricsFetchResult::Instrumental => LyricsState::Instrumental,
+                LyricsFetchResult::None => LyricsState::NotFound,
+            })
+        };
+        let state = match result {
+            Ok(state) => state,
+            Err(e) => {
+                if is_network_error(&e) {
+                    let _ = tx.send(AppEvent::Connectivity(false)).await;
+                }
+                LyricsState::Failed(e.to_string())
+            }
+        };
+        let _ = tx.send(AppEvent::LyricsUpdate(id, state)).await;
+    });
+}
+
+/// Download the cover for `track` at `size` px in the background and report
+/// back as an ArtworkUpdate keyed by `key`.
+fn spawn_artwork_fetch(tx: mpsc::Sender<AppEvent>, client: reqwest::Client, track: &TrackInfo, key: String, size: u32, offline: bool) {
+    let (url, artist, album) = (track.artwork_url.clone(), track.artist.clone(), track.album.clone());
+    tokio::spawn(async move {
+        let renderer = ArtworkRenderer::new(client);
+        // Offline: only what earlier sessions left in the disk cache
+        let fetched = if offline {
+            ArtworkRenderer::fetch_cached(&artist, &album, size)
+        } else {
+            renderer.fetch_racing(url.as_deref(), &artist, &album, size).await
+        };
+        let state = match fetched {
+            Ok(img) => ArtworkState::Loaded(Arc::new(img)),
+            Err(e) => {
+                if is_network_error(&e) {
+                    let _ = tx.send(AppEvent::Connectivity(false)).await;
+                }
+                ArtworkState::Failed
+            }
+        };
+        let _ = tx.send(AppEvent::ArtworkUpdate(key, state)).await;
+    });
+}
+
+/// How stale the loved tracks may get before a track change pulls them again
+const LOVED_PULL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// `system_profiler` takes a moment, so the output device isn't asked for often
+const OUTPUT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Push pending hearts (and with `pull`, fetch the services' loved tracks) in
+/// the background, reporting back as a LovedSynced.
+fn spawn_loved_sync(tx: mpsc::Sender<AppEvent>, client: reqwest::Client, services: loved::Services, pending: loved::Pending, pull: bool) {
+    tokio::spawn(async move {
+        let outcome = loved::sync(&client, &services, pending, pull).await;
+        let _ = tx.send(AppEvent::LovedSynced(outcome)).await;
+    });
+}
+
+/// Send a now-playing or a scrobble in the background. Scrobbles that don't
+/// make it (all of them with `offline`) wait in the queue for the next one.
+fn spawn_scrobble(client: reqwest::Client, services: scrobble::Services, submission: scrobble::Submission, offline: bool) {
+    tokio::spawn(async move {
+        scrobble::submit(&client, &services, submission, offline).await;
+    });
+}
+
+/// Read shuffle and repeat in the background, reporting back as a ModesUpdate.
+/// Spotify goes through the Web API when logged in: only that knows repeat-one.
+fn spawn_modes_fetch(tx: mpsc::Sender<AppEvent>, web: Option<spotify_api::SpotifyWebPlayer>) {
+    tokio::spawn(async move {
+        let (shuffle, repeat) = match web {
+            Some(web) => web.modes().await.map_or((None, None), |(shuffle, repeat)| (Some(shuffle), Some(repeat))),
+            None => tokio::task::spawn_blocking(|| {
+                let player = player::get_player();
+                (player.get_shuffle().ok(), player.get_repeat().ok())
+            }).await.unwrap_or((None, None)),
+        };
+        let _ = tx.send(AppEvent::ModesUpdate(shuffle, repeat)).await;
+    });
+}
+
+/// Ask what's up next for the queue pane, reporting back as a QueueUpdate.
+/// Spotify's queue needs the Web API; Music only knows the next track in the
+/// playlist (and nothing with shuffle on).
+fn spawn_queue_fetch(tx: mpsc::Sender<AppEvent>, web: Option<spotify_api::SpotifyWebPlayer>, spotify: bool, offline: bool) {
+    tokio::spawn(async move {
+        let tracks = match web {
+            Some(web) => web.queue().await.map_err(|e| e.to_string()),
+            None if spotify && offline => Err("Spotify's queue can't be fetched offline".to_string()),
+            None if spotify => Err("Spotify's queue needs the Web API login (`vyom auth spotify`)".to_string()),
+            None => tokio::task::spawn_blocking(|| player::get_player().get_next_track())
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|next| next.map_err(|e| e.to_string()))
+                .map(|next| next.into_iter().collect()),
+        };
+        let _ = tx.send(AppEvent::QueueUpdate(tracks)).await;
+    });
+}
+
+/// `s` flips shuffle, `r` moves repeat along off → all → one. The new modes
+/// come back as a ModesUpdate, with a toast saying what changed.
+fn spawn_mode_toggle(tx: mpsc::Sender<AppEvent>, web: Option<spotify_api::SpotifyWebPlayer>, shuffle: bool) {
+    tokio::spawn(async move {
+        let toggled = match web {
+            Some(web) => async {
+                let (mut on, mut repeat) = web.modes().await?;
+                if shuffle {
+                    on = !on;
+                    web.set_shuffle(on).await?;
+                } else {
+                    repeat = repeat.next();
+                    web.set_repeat(repeat).await?;
+                }
+                anyhow::Ok((on, repeat))
+            }.await,
+            None => tokio::task::spawn_blocking(move || {
+                let player = player::get_player();
+                let (mut on, mut repeat) = (player.get_shuffle()?, player.get_repeat()?);
+                if shuffle {
+                    on = !on;
+                    player.set_shuffle(on)?;
+                } else {
+                    repeat = repeat.next();
+                    match player.set_repeat(repeat) {
+                        // Spotify's AppleScript has no repeat-one: straight back to off
+                        Err(_) if repeat == player::Repeat::Track => {
+                            repeat = player::Repeat::Off;
+                            player.set_repeat(repeat)?;
+                        },
+                        result => result?,
+                    }
+                }
+                anyhow::Ok((on, repeat))
+            }).await.unwrap_or_else(|e| Err(e.into())),
+        };
+        let message = match toggled {
+            Ok((on, repeat)) => {
+                let _ = tx.send(AppEvent::ModesUpdate(Some(on), Some(repeat))).await;
+                match (shuffle, on) {
+                    (true, true) => "Shuffle on",
+                    (true, false) => "Shuffle off",
+                    (false, _) => repeat.label(),
+                }.to_string()
+            },
+            Err(e) => e.to_string(),
+        };
+        let _ = tx.send(AppEvent::Toast(message)).await;
+    });
+}
+
+/// Drop queued events a newer one of the same kind makes pointless: only the
+/// last Tick and the last TrackUpdate in the backlog are kept. Input and
+/// everything else go through untouched and in order.
+fn coalesce(events: &mut VecDeque<AppEvent>) {
+    stats::record_events(events.len() as u64);
+    let last_tick = events.iter().rposition(|e| matches!(e, AppEvent::Tick));
+    let last_track = events.iter().rposition(|e| matches!(e, AppEvent::TrackUpdate(_)));
+    let before = events.len();
+    let mut i = 0;
+    events.retain(|event| {
+        let keep = match event {
+            AppEvent::Tick => Some(i) == last_tick,
+            AppEvent::TrackUpdate(_) => Some(i) == last_track,
+            _ => true,
+        };
+        i += 1;
+        keep
+    });
+    stats::record_dropped((before - events.len()) as u64);
+}
+
+/// Key name handed to plugin `on_key` hooks: the character itself, or e.g. "Enter", "Up", "F5"
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args: Vec<String> = std::env::args().collect();
+
+    // Subcommands (no TUI)
+    if args.get(1).is_some_and(|a| a == "--version" || a == "-V") {
+        println!("vyom {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+    if args.get(1).map(String::as_str) == Some("doctor") {
+        // Quietly: a broken config is one of the things the doctor reports
+        let config = config::parse_config().unwrap_or_default();
+        return doctor::run_cli(&build_http_client(&config.network)).await;
+    }
+    if args.get(1).map(String::as_str) == Some("auth") {
+        if args.get(2).map(String::as_str) == Some("lastfm") {
+            let config = config::load_config();
+            return lastfm::login(&build_http_client(&config.network), &config.lastfm).await;
+        }
+        if args.get(2).map(String::as_str) == Some("spotify") {
+            let config = config::load_config();
+            return spotify_api::login(&build_http_client(&config.network), &config.spotify).await;
+        }
+        return keychain::run_cli(&args[2..]);
+    }
+    if args.get(1).map(String::as_str) == Some("attach") {
+        return session::attach();
+    }
+    if args.get(1).map(String::as_str) == Some("report") {
+        return report::run_cli(&args[2..]);
+    }
+    if args.get(1).map(String::as_str) == Some("ctl") {
+        return ipc::send(&args[2..]);
+    }
+    if args.get(1).map(String::as_str) == Some("status") {
+        return status::run_cli(&args[2..], &config::load_settings());
+    }
+    if args.get(1).map(String::as_str) == Some("update") {
+        let config = config::load_settings();
+        return update::run_cli(&args[2..], &build_http_client(&config.network)).await;
+    }
+
+    // `vyom daemon`: the whole app without a terminal, for `vyom attach` clients to share
+    let daemon = args.get(1).map(String::as_str) == Some("daemon");
     let is_standalone = args.iter().any(|a| a == "--standalone");
     let is_tmux = std::env::var("TMUX").is_ok();
+    let mut config = config::load_config();
+    // `--replay session.json` / `--record session.json` (see replay.rs)
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let replay = flag("--replay").map(|path| replay::load(path)).transpose()?;
+    let mut recorder = flag("--record").map(|path| replay::Recorder::new(std::path::Path::new(path)));
+    let sandbox = if replay.is_some() { Some(replay::sandbox()?) } else { None };
+    let offline = config.offline || replay.is_some() || args.iter().any(|a| a == "--offline");
+    config.offline = offline;
+    power::set(config.power_profile);
+    power::set_polling(&config.polling);
+    artwork::set_cache_limit(config.artwork.cache_mb);
+    let keymap = keys::Keymap::new(&config.keys);
+    // Monochrome covers are drawn in half blocks, to be thresholded like the rest
+    let artwork_protocol = if config.ui.monochrome || theme::no_color() {
+        config::ArtworkProtocol::Halfblocks
+    } else {
+        artwork::detect_protocol(config.artwork.protocol, is_tmux)
+    };
+    for e in plugins::load() {
+        eprintln!("Plugin error: {}", e);
+    }
 
     // Smart Window Logic
     let want_lyrics = args.iter().any(|a| a == "--lyrics");
@@ -49,18 +471,27 @@ async fn main() -> Result<()> {
     let exe_path = current_exe.to_str().unwrap();
 
     // 1. WINDOW TITLE (For Yabai/Amethyst) 🏷️
-    print!("\x1b]2;Vyom\x07");
+    if !daemon {
+        print!("\x1b]2;Vyom\x07");
+    }
 
     // 2. TMUX LOGIC
-    if is_tmux && !is_standalone {
-        // Auto-split logic (Tmux)
-        let status = std::process::Command::new("tmux")
-            .arg("split-window")
+    if is_tmux && !is_standalone && !daemon && config.layout.tmux_split {
+        // Auto-split logic (Tmux). tmux hands the command to `sh -c`, so every
+        // argument is quoted; the pane starts here so relative paths still work.
+        let command: Vec<String> = std::iter::once(exe_path).chain(std::iter::once("--standalone"))
+            .chain(args[1..].iter().map(String::as_str))
+            .map(shell_quote)
+            .collect();
+        let mut split = std::process::Command::new("tmux");
+        split.arg("split-window")
             .arg("-h")
             .arg("-p")
-            .arg("22") // Changed from "29" to "22"
-            .arg(format!("{} --standalone {}", exe_path, if want_lyrics { "--lyrics" } else { "" }))
-            .status();
+            .arg(config.layout.tmux_split_percent.to_string());
+        if let Ok(dir) = std::env::current_dir() {
+            split.arg("-c").arg(dir);
+        }
+        let status = split.arg(command.join(" ")).status();
 
         match status {
             Ok(_) => return Ok(()),
@@ -71,56 +502,109 @@ async fn main() -> Result<()> {
     } 
     // No else block for Standalone Resize - User manages window size manually.
 
+    // 3. SINGLE INSTANCE: with a session already running (detached or the
+    // daemon), this terminal becomes another view of it instead of a second poller
+    if !daemon && session::is_running() {
+        return session::attach();
+    }
 
     // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    let backend = if daemon {
+        SessionBackend::detached()
+    } else {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        SessionBackend::new(stdout)
+    };
     let mut terminal = Terminal::new(backend)?;
 
     // In Tmux, we assume full split/window, so show lyrics by default.
     // In Standalone, strict mode applies.
-    let app_show_lyrics = want_lyrics || is_tmux;
+    let app_show_lyrics = want_lyrics || is_tmux || config.ui.show_lyrics;
 
     // 1. Initial State
-    let mut app = App::new(app_show_lyrics, is_tmux);
-    let player = player::get_player(); // Factory Pattern
+    let mut app = App::new(app_show_lyrics, is_tmux, config.clone());
+    app.keymap = keymap;
+    state::load().apply(&mut app);
+    let player: Box<dyn player::PlayerTrait> = if replay.is_some() { Box::new(player::DummyPlayer) } else { player::get_player() }; // Factory Pattern
     let (tx, mut rx) = mpsc::channel(100); 
 
     // Performance Optimization: Global HTTP Client (Reused)
-    let client = reqwest::Client::builder()
-        .user_agent("vyom-rs/1.0.1")
-        .build()
-        .unwrap_or_default();
+    let client = build_http_client(&config.network);
 
+    // Loved tracks ♥: push hearts given while offline, pull the services' ones
+    let loved_services = if offline { None } else { loved::Services::from_config(&config) };
+    if let Some(services) = &loved_services {
+        spawn_loved_sync(tx.clone(), client.clone(), services.clone(), app.loved.pending(), true);
+    }
+    let mut loved_pulled_at = std::time::Instant::now();
+    // Scrobbling 📡: send what was queued while offline, then follow the plays
+    let scrobble_services = scrobble::Services::from_config(&config);
+    if scrobble_services.is_some() {
+        scrobble::count_queued();
+    }
+    if let (Some(services), false) = (scrobble_services.clone(), offline) {
+        let client = client.clone();
+        tokio::spawn(async move { scrobble::flush(&client, &services).await });
+    }
+    let mut scrobbler = scrobble::Scrobbler::default();
+    // Submissions for a Spotify track whose private session lookup is still out
+    let mut held_submissions: Vec<scrobble::Submission> = Vec::new();
+    // Spotify Web API login (`vyom auth spotify`), for what AppleScript can't do
+    let spotify_web = if offline { None } else { spotify_api::SpotifyWebPlayer::from_config(&client, &config.spotify) };
 
 
-    // 1. Input Event Task
-    let tx_input = tx.clone();
-    tokio::spawn(async move {
-        let mut reader = EventStream::new();
-        while let Some(Ok(event)) = reader.next().await {
-            if tx_input.send(AppEvent::Input(event)).await.is_err() { break; }
-        }
-    });
 
-    // 2. Spotify Polling Task
-    let tx_spotify = tx.clone();
-    tokio::spawn(async move {
-        loop {
-            // Create fresh player for thread safety (MacOsPlayer is stateless)
-            let track_result = tokio::task::spawn_blocking(|| {
-                let p = player::get_player();
-                p.get_current_track()
-            }).await;
+    // 1. Input Event Task (stopped while an editor has the terminal; the daemon has none)
+    let mut input_task = if daemon { tokio::spawn(async {}) } else { spawn_input(tx.clone()) };
+
+    // 2. Spotify Polling Task (a replay brings its own track updates, and ticks)
+    let replaying = replay.is_some();
+    if let Some(entries) = replay {
+        replay::spawn(entries, tx.clone());
+    }
+    if !replaying {
+        let tx_spotify = tx.clone();
+        tokio::spawn(async move {
+            loop {
+                // Create fresh player for thread safety (MacOsPlayer is stateless)
+                let started = std::time::Instant::now();
+                let track_result = tokio::task::spawn_blocking(|| {
+                    let p = player::get_player();
+                    p.get_current_track()
+                }).await;
+                stats::record_poll(started.elapsed());
             
-            if let Ok(Ok(info)) = track_result {
-                 if tx_spotify.send(AppEvent::TrackUpdate(info)).await.is_err() { break; }
+                let event = match track_result {
+                    Ok(Ok(info)) => Some(AppEvent::TrackUpdate(info)),
+                    Ok(Err(e)) => Some(AppEvent::PlayerError(match e.downcast::<player::PlayerError>() {
+                        Ok(player_err) => player_err,
+                        Err(e) => player::PlayerError::Errored("Player".to_string(), e.to_string()),
+                    })),
+                    Err(_) => None,
+                };
+                if let Some(event) = event {
+                     if tx_spotify.send(event).await.is_err() { break; }
+                }
+                tokio::time::sleep(power::current().poll_interval()).await;
             }
-            tokio::time::sleep(Duration::from_millis(250)).await;
-        }
-    });
+        });
+    }
+
+    // 2b. Player List Task: which players are running, for the selector strip
+    if !replaying {
+        let tx_players = tx.clone();
+        tokio::spawn(async move {
+            loop {
+                let players = tokio::task::spawn_blocking(|| player::get_player().list_players()).await;
+                if let Ok(Ok(players)) = players {
+                    if tx_players.send(AppEvent::PlayersUpdate(players)).await.is_err() { break; }
+                }
+                tokio::time::sleep(power::current().players_interval()).await;
+            }
+        });
+    }
 
     // 3. Theme Watcher Task 🎨
     let tx_theme = tx.clone();
@@ -129,7 +613,7 @@ async fn main() -> Result<()> {
         let mut last_theme_debug = format!("{:?}", theme::load_current_theme());
 
         loop {
-            tokio::time::sleep(Duration::from_millis(250)).await;
+            tokio::time::sleep(power::current().poll_interval()).await;
             
             // Reload & Check difference based on Debug impl (hacky but cheap)
             let new_theme = theme::load_current_theme();
@@ -142,221 +626,1070 @@ async fn main() -> Result<()> {
         }
     });
 
-    // 4. Animation Tick Task ⚡
-    let tx_tick = tx.clone();
+    // 3b. Config Watcher Task ⚙️: edits to config.toml apply without a restart
+    let tx_config = tx.clone();
+    tokio::spawn(async move {
+        let modified = || std::fs::metadata(config::config_path()).and_then(|m| m.modified()).ok();
+        let mut last_modified = modified();
+        loop {
+            tokio::time::sleep(power::current().poll_interval()).await;
+            let now_modified = modified();
+            if now_modified != last_modified {
+                last_modified = now_modified;
+                let Ok(reloaded) = tokio::task::spawn_blocking(config::reload_config).await else { continue };
+                if tx_config.send(AppEvent::ConfigReload(reloaded.map(Box::new))).await.is_err() { break; }
+            }
+        }
+    });
+
+    // 4. Animation Tick Task ⚡ (slower on the battery profile)
+    if !replaying {
+        let tx_tick = tx.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(power::current().tick_interval()).await;
+                // A tick waiting in a full queue is as good as a new one: don't pile them up
+                match tx_tick.try_send(AppEvent::Tick) {
+                    Err(mpsc::error::TrySendError::Full(_)) => stats::record_dropped(1),
+                    Err(mpsc::error::TrySendError::Closed(_)) => break,
+                    Ok(()) => {}
+                }
+            }
+        });
+    }
+
+    // 4b. Power Source Task 🔋: `auto` switches to the battery profile when unplugged
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            if power::is_auto() {
+                let _ = tokio::task::spawn_blocking(|| power::set(config::PowerProfile::Auto)).await;
+            }
+        }
+    });
+
+
+    // 4c. Debug Sample Task 🩺: CPU, memory, caches and tasks for the F12 overlay
+    let tx_debug = tx.clone();
     tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_millis(50));
         loop {
-            interval.tick().await;
-            if tx_tick.send(AppEvent::Tick).await.is_err() { break; }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            if !stats::enabled() { continue; }
+            let tasks = tokio::runtime::Handle::current().metrics().num_alive_tasks();
+            if let Ok(sample) = tokio::task::spawn_blocking(move || stats::sample(tasks)).await {
+                if tx_debug.send(AppEvent::DebugSample(sample)).await.is_err() { break; }
+            }
         }
     });
 
+    // 4d. Audio Capture 🎧: real spectrum for the visualizer (opt-in)
+    audio::start(&config.visualizer, app.visualizer_bars.len());
+
+    // 4e. Notification buttons 🔔 (Linux)
+    if config.notifications.enabled {
+        notification::listen(tx.clone());
+    }
+
+    // 4f. Output Device Task 🎧: where the audio goes, for the header and `pause_on_change`
+    let tx_output = tx.clone();
+    tokio::spawn(async move {
+        let mut last = None;
+        loop {
+            if let Ok(device) = tokio::task::spawn_blocking(output::current).await {
+                if device != last {
+                    last = device.clone();
+                    if tx_output.send(AppEvent::OutputDevice(device)).await.is_err() { break; }
+                }
+            }
+            tokio::time::sleep(OUTPUT_POLL_INTERVAL).await;
+        }
+    });
+
+    // 5. Control socket for `vyom ctl` (a second instance leaves the first one's alone)
+    let _ = ipc::listen(tx.clone());
+
+    // 6. Signal Task: SIGINT goes through the normal quit path, SIGTERM (how a daemon
+    // gets stopped) skips the confirmation but still cleans up on the way out
+    let tx_signal = tx.clone();
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if tx_signal.send(AppEvent::Interrupt).await.is_err() { break; }
+        }
+    });
+    #[cfg(unix)]
+    if let Ok(mut terminate) = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+        let tx_signal = tx.clone();
+        tokio::spawn(async move {
+            while terminate.recv().await.is_some() {
+                if tx_signal.send(AppEvent::Terminate).await.is_err() { break; }
+            }
+        });
+    }
 
     let mut last_track_id = String::new();
-    let mut last_artwork_url = None;
+    let mut last_artwork_key: Option<String> = None;
+    // Events taken off the channel but not handled yet (see `coalesce`)
+    let mut pending: VecDeque<AppEvent> = VecDeque::new();
+    // Pane size the artwork is being (or was last) rendered at
+    let mut artwork_rendering: Option<(u16, u16)> = None;
+    // Where the graphics-protocol artwork was last written, and whether it changed since
+    let mut artwork_placed: Option<ratatui::layout::Rect> = None;
+    let mut artwork_stale = false;
+    let mut preloaded_for = String::new();
+    let mut listening = false;
+    if daemon {
+        session::listen(tx.clone())?;
+        listening = true;
+    }
+    // Attached client the event being handled came from (None: the local terminal)
+    let mut input_client: Option<u64> = None;
+    let mut frame_times = replay::FrameTimes::default();
+    // Progress in the terminal's tab/taskbar (not from a daemon: its clients sit in all sorts of terminals)
+    let mut terminal_progress = if daemon { None } else { taskbar::TerminalProgress::detect(is_tmux) };
 
     loop {
         // Auto-Reset Lyrics Scroll Logic
-        if let Some(t) = app.last_scroll_time {
-            if t.elapsed().as_secs() >= 3 {
+        // (an open search holds the view on its match)
+        // (and so does the reading-ahead lock, until it's released)
+        if let (Some(t), None, false) = (app.last_scroll_time, &app.lyrics_search, app.scroll_lock) {
+            if t.elapsed().as_secs() >= app.config.ui.scroll_timeout_secs {
                 // Time up! removing "manual mode" flag to let Tick animation take over
                 app.last_scroll_time = None;
             }
         }
 
-        terminal.draw(|f| ui::ui(f, &mut app))?;
+        // Detached with nobody attached: keep the state moving, skip drawing.
+        // With a backlog queued, catch up first and draw once at the end.
+        if pending.is_empty() && !terminal.backend().is_detached() {
+            let started = std::time::Instant::now();
+            terminal.draw(|f| ui::ui(f, &mut app))?;
+            // Graphics-protocol artwork: written after the frame, and only again when it moves or changes
+            if app.artwork_placement != artwork_placed || artwork_stale {
+                place_artwork(&mut terminal, &mut app, artwork_placed.is_some(), artwork_protocol, is_tmux)?;
+                artwork_placed = app.artwork_placement;
+                artwork_stale = false;
+            }
+            if let Some(escape) = terminal_progress.as_mut().and_then(|progress| progress.update(&app)) {
+                terminal.backend_mut().write_all(escape.as_bytes())?;
+                terminal.backend_mut().flush()?;
+            }
+            stats::record_frame(started.elapsed());
+            frame_times.record(started.elapsed());
+        }
+
+        // Artwork pane changed size (or a new cover came in): render it in the background
+        if let (Some(size), ArtworkState::Loaded(img), Some(key)) = (app.artwork_wanted, &app.artwork, &last_artwork_key) {
+            if artwork_rendering != Some(size) {
+                artwork_rendering = Some(size);
+                let (img, key, tx_render) = (img.clone(), key.clone(), tx.clone());
+                tokio::spawn(async move {
+                    let rendered = tokio::task::spawn_blocking(move || artwork::render_cached(&img, size.0, size.1, artwork_protocol, is_tmux)).await;
+                    if let Ok(rendered) = rendered {
+                        let _ = tx_render.send(AppEvent::ArtworkRendered(key, size, rendered)).await;
+                    }
+                });
+            }
+        }
 
-        if let Some(event) = rx.recv().await {
+        if pending.is_empty() {
+            if let Some(event) = rx.recv().await {
+                pending.push_back(event);
+                while let Ok(event) = rx.try_recv() {
+                    pending.push_back(event);
+                }
+                coalesce(&mut pending);
+            }
+        }
+
+        if let Some(event) = pending.pop_front() {
+            // Input comes from whichever terminal we're drawing to
+            let event = match event {
+                AppEvent::Input(_) if !terminal.backend().is_local() => continue,
+                AppEvent::Input(event) => {
+                    input_client = None;
+                    AppEvent::Input(event)
+                }
+                AppEvent::RemoteInput(id, event) => {
+                    if let Event::Resize(width, height) = event {
+                        terminal.backend_mut().set_remote_size(id, ratatui::layout::Size::new(width, height));
+                    }
+                    input_client = Some(id);
+                    AppEvent::Input(event)
+                }
+                event => event,
+            };
+            if let Some(recorder) = &mut recorder {
+                recorder.record(&event);
+            }
             match event {
                 // ... (Input handling omitted)
                 AppEvent::Input(Event::Mouse(mouse)) => {
                      // ... same as before
                      use crossterm::event::{MouseEventKind, MouseButton};
                      match mouse.kind {
+                        // A click closes the lightbox like a key does
+                        MouseEventKind::Down(_) if app.lightbox => app.lightbox = false,
                         MouseEventKind::Down(MouseButton::Left) => {
                              let (col, row) = (mouse.column, mouse.row);
                             // ...
-                            let mut hit_lyrics = false;
-                            for (rect, timestamp) in &app.lyrics_hitboxes {
-                                if rect.contains((col, row).into()) {
-                                    let seconds = *timestamp as f64 / 1000.0;
-                                    let _ = tokio::task::block_in_place(|| {
-                                         // Fresh player
-                                         let p = crate::player::get_player();
-                                         p.seek(seconds)
-                                    });
-                                    if let Some(track) = &mut app.track {
-                                        track.position_ms = *timestamp;
-                                    }
-                                    hit_lyrics = true;
-                                    app.lyrics_offset = None; 
-                                    break;
+                            let hit_lyrics = app.lyrics_hitboxes.iter()
+                                .find(|(rect, _)| rect.contains((col, row).into()))
+                                .map(|(_, timestamp)| *timestamp);
+                            if let (Some(timestamp), false) = (hit_lyrics, app.party_view) {
+                                app.remember_jump(app::Jump::Seek);
+                                let seconds = timestamp as f64 / 1000.0;
+                                let seeked = tokio::task::block_in_place(|| {
+                                     // Fresh player
+                                     let p = crate::player::get_player();
+                                     p.seek(seconds)
+                                });
+                                if seeked.is_ok() {
+                                    app.clock.seek(timestamp);
                                 }
-                            }
-                            
-                            if !hit_lyrics {
+                                app.lyrics_offset = None; 
+                                app.scroll_lock = false;
+                            } else {
                                 app.handle_click(col, row, player.as_ref());
                             }
 
                         }
-                        MouseEventKind::ScrollDown => {
-                            if let (LyricsState::Loaded(lyrics), Some(track)) = (&app.lyrics, &app.track) {
-                                if app.lyrics_offset.is_none() {
-                                    let current_idx = lyrics.iter()
-                                       .position(|l| l.timestamp_ms > track.position_ms)
-                                       .map(|i| if i > 0 { i - 1 } else { 0 })
-                                       .unwrap_or(0);
-                                     app.lyrics_offset = Some(current_idx);
-                                }
-                                
-                                if let Some(off) = &mut app.lyrics_offset {
-                                    *off = off.saturating_add(1).min(lyrics.len().saturating_sub(1));
+                        MouseEventKind::Moved => app.hover = Some((mouse.column, mouse.row)),
+                        // Over the progress bar the wheel seeks (up: forward), over the volume hint it
+                        // turns the volume, elsewhere it scrolls the lyrics
+                        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+                            let up = mouse.kind == MouseEventKind::ScrollUp;
+                            if app.volume_rect.contains((mouse.column, mouse.row).into()) {
+                                app.step_volume(up, player.as_ref());
+                            } else if let Some(target_ms) = app.wheel_seek_target(mouse.column, mouse.row, up) {
+                                if player.seek(target_ms as f64 / 1000.0).is_ok() {
+                                    app.clock.seek(target_ms);
                                 }
-                                app.last_scroll_time = Some(std::time::Instant::now());
+                            } else {
+                                app.scroll_lyrics(if up { -1 } else { 1 });
                             }
-                        }
-                        MouseEventKind::ScrollUp => {
-                             if let (LyricsState::Loaded(lyrics), Some(track)) = (&app.lyrics, &app.track) {
-                                if app.lyrics_offset.is_none() {
-                                     let current_idx = lyrics.iter()
-                                       .position(|l| l.timestamp_ms > track.position_ms)
-                                       .map(|i| if i > 0 { i - 1 } else { 0 })
-                                       .unwrap_or(0);
-                                     app.lyrics_offset = Some(current_idx);
-                                }
-                                
-                                if let Some(off) = &mut app.lyrics_offset {
-                                    *off = off.saturating_sub(1);
-                                }
-                                app.last_scroll_time = Some(std::time::Instant::now());
-                             }
-                        }
+                        },
                         _ => {}
                     }
                 },
                 AppEvent::Input(Event::Key(key)) => {
-                    match key.code {
-                        KeyCode::Char('q') => app.is_running = false,
+                    // Rebound keys stand in for the built-in ones, except while typing or answering a prompt
+                    let typing_text = app.typing.is_some() || app.lyrics_search.as_ref().is_some_and(|s| s.editing);
+                    let code = if typing_text || app.confirm_quit || app.resume_prompt.is_some() || key.modifiers.contains(KeyModifiers::CONTROL) {
+                        key.code
+                    } else {
+                        app.keymap.resolve(key.code)
+                    };
+                    match code {
+                        // The daemon outlives its clients: quitting one only detaches it
+                        KeyCode::Char('c') | KeyCode::Char('q') if daemon && (code == KeyCode::Char('q') || key.modifiers.contains(KeyModifiers::CONTROL)) => {
+                            if let Some(id) = input_client {
+                                terminal.backend_mut().detach_client(id);
+                            }
+                        },
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => app.request_quit(),
+                        // Quit prompt: y/q/Enter confirms, anything else cancels
+                        KeyCode::Char('y') | KeyCode::Char('q') | KeyCode::Enter if app.confirm_quit => app.is_running = false,
+                        _ if app.confirm_quit => app.confirm_quit = false,
+                        // Resume prompt: y/Enter jumps to the bookmark, n/Esc starts over
+                        KeyCode::Char('y') | KeyCode::Enter if app.resume_prompt.is_some() => app.answer_resume(true, player.as_ref()),
+                        KeyCode::Char('n') | KeyCode::Esc if app.resume_prompt.is_some() => app.answer_resume(false, player.as_ref()),
+                        _ if app.lightbox => app.lightbox = false,
+                        // Automation onboarding dialog: Enter asks macOS, Esc hides it
+                        KeyCode::Enter if app.permission_dialog_player().is_some() => {
+                            if let Some(name) = app.permission_dialog_player() {
+                                tokio::task::spawn_blocking(move || player::request_automation_permission(&name));
+                            }
+                        },
+                        KeyCode::Esc if app.permission_dialog_player().is_some() => app.permission_dialog_dismissed = true,
+                        KeyCode::Esc if app.recap.is_some() => app.recap = None,
+                        // Output picker: ↑/↓ (or j/k) to choose, Enter switches, Esc closes
+                        _ if app.output_picker.is_some() => {
+                            let count = app.output_picker.as_ref().and_then(|p| p.devices.as_ref()).map_or(0, Vec::len);
+                            match key.code {
+                                KeyCode::Up | KeyCode::Char('k') => {
+                                    if let Some(picker) = &mut app.output_picker { picker.selected = picker.selected.saturating_sub(1); }
+                                }
+                                KeyCode::Down | KeyCode::Char('j') => {
+                                    if let Some(picker) = &mut app.output_picker { picker.selected = (picker.selected + 1).min(count.saturating_sub(1)); }
+                                }
+                                KeyCode::Enter => {
+                                    let picked = app.output_picker.take()
+                                        .and_then(|p| p.devices.and_then(|devices| devices.get(p.selected).cloned()));
+                                    if let (Some(device), Some(web)) = (picked.as_ref().filter(|d| d.connect), spotify_web.clone()) {
+                                        let (tx_transfer, device) = (tx.clone(), device.clone());
+                                        tokio::spawn(async move {
+                                            let message = match web.transfer(&device.id).await {
+                                                Ok(()) => format!("Spotify playing on {}", device.name),
+                                                Err(e) => format!("Couldn't move Spotify to {}: {}", device.name, e),
+                                            };
+                                            let _ = tx_transfer.send(AppEvent::Toast(message)).await;
+                                        });
+                                    } else if let Some(device) = picked {
+                                        match output::switch(&device) {
+                                            // Ours, so `pause_on_change` leaves it be
+                                            Ok(()) => app.output_device = Some(device.name),
+                                            Err(e) => app.show_toast(e.to_string()),
+                                        }
+                                    }
+                                }
+                                _ if key.code == KeyCode::Esc || code == KeyCode::Char('O') => app.output_picker = None,
+                                _ => {}
+                            }
+                        },
+                        // Lyrics search: while typing, keys edit the query
+                        _ if app.lyrics_search.as_ref().is_some_and(|s| s.editing) => {
+                            match key.code {
+                                KeyCode::Tab | KeyCode::BackTab => app.cycle_focus(key.code == KeyCode::BackTab),
+                                KeyCode::Up => app.step_search(-1),
+                                KeyCode::Down => app.step_search(1),
+                                KeyCode::Char(c) => {
+                                    if let Some(search) = &mut app.lyrics_search { search.query.push(c); }
+                                    app.update_search_matches();
+                                }
+                                KeyCode::Backspace => {
+                                    if let Some(search) = &mut app.lyrics_search { search.query.pop(); }
+                                    app.update_search_matches();
+                                }
+                                KeyCode::Enter => {
+                                    if let Some(search) = &mut app.lyrics_search { search.editing = false; }
+                                }
+                                KeyCode::Esc => {
+                                    app.lyrics_search = None;
+                                    app.lyrics_offset = None;
+                                }
+                                _ => {}
+                            }
+                        },
+                        // Typing practice: every key is input until Esc
+                        _ if app.typing.is_some() => {
+                            match key.code {
+                                KeyCode::Char(c) => { if let Some(t) = &mut app.typing { t.typed.push(c); } }
+                                KeyCode::Backspace => { if let Some(t) = &mut app.typing { t.typed.pop(); } }
+                                KeyCode::Esc => app.typing = None,
+                                _ => {}
+                            }
+                        },
+                        KeyCode::Char('T') if matches!(app.lyrics, LyricsState::Loaded(_)) => {
+                            app.typing = app.current_lyric_idx().map(typing::TypingPractice::new);
+                        },
+                        KeyCode::Char('/') if app.lyrics_len() > 0 => {
+                            app.lyrics_search = Some(app::LyricsSearch { editing: true, ..Default::default() });
+                            app.focus = app::Focus::Search;
+                        },
+                        KeyCode::Char('n') if app.lyrics_search.is_some() => app.step_search(1),
+                        KeyCode::Char('N') if app.lyrics_search.is_some() => app.step_search(-1),
+                        KeyCode::Enter if app.lyrics_search.is_some() && matches!(app.focused(), app::Focus::Lyrics | app::Focus::Search) => {
+                            // Seek to the matched line
+                            if let Some(ts) = app.search_match_timestamp() {
+                                app.remember_jump(app::Jump::Seek);
+                                if player.seek(ts as f64 / 1000.0).is_ok() {
+                                    app.clock.seek(ts);
+                                }
+                            }
+                            app.lyrics_search = None;
+                            app.lyrics_offset = None;
+                        },
+                        KeyCode::Esc if app.lyrics_search.is_some() => {
+                            app.lyrics_search = None;
+                            app.lyrics_offset = None;
+                        },
+                        // Focus: Tab moves between the panes, the arrows and Enter act on the one focused
+                        KeyCode::Tab | KeyCode::BackTab => app.cycle_focus(code == KeyCode::BackTab),
+                        // ←/→ seek 5s, with Shift 30s; the clock jumps ahead of the next poll
+                        KeyCode::Left | KeyCode::Right => {
+                            let secs = if key.modifiers.contains(KeyModifiers::SHIFT) { 30 } else { 5 };
+                            if let Some(target_ms) = app.step_seek_target(code == KeyCode::Right, secs) {
+                                if player.seek(target_ms as f64 / 1000.0).is_ok() {
+                                    app.clock.seek(target_ms);
+                                }
+                            }
+                        },
+                        KeyCode::Up | KeyCode::Down if app.focused() == app::Focus::Card => app.step_volume(code == KeyCode::Up, player.as_ref()),
+                        KeyCode::Up | KeyCode::Down if app.focused() == app::Focus::Search => app.step_search(if code == KeyCode::Up { -1 } else { 1 }),
+                        KeyCode::Up | KeyCode::Down if app.focused() == app::Focus::Queue => app.step_queue(if code == KeyCode::Up { -1 } else { 1 }),
+                        KeyCode::Up | KeyCode::Down => app.scroll_lyrics(if code == KeyCode::Up { -1 } else { 1 }),
+                        KeyCode::Enter if app.focused() == app::Focus::Card => { let _ = player.play_pause(); },
+                        KeyCode::Enter if app.focused() == app::Focus::Queue => {
+                            // Skip ahead to the picked track; the track change asks for the queue again
+                            let ahead = app.queue.as_ref().map_or(0, |queue| queue.selected + 1);
+                            for _ in 0..ahead {
+                                if player.next().is_err() {
+                                    break;
+                                }
+                            }
+                            if let Some(queue) = &mut app.queue {
+                                queue.selected = 0;
+                            }
+                        },
+                        KeyCode::Esc if app.focused() == app::Focus::Queue => app.queue = None,
+                        KeyCode::Enter => {
+                            // The line in the middle of the pane, like clicking it
+                            if let Some(timestamp) = app.centered_line_timestamp() {
+                                app.remember_jump(app::Jump::Seek);
+                                if player.seek(timestamp as f64 / 1000.0).is_ok() {
+                                    app.clock.seek(timestamp);
+                                }
+                            }
+                            app.lyrics_offset = None;
+                            app.scroll_lock = false;
+                        },
+                        // Lua plugins get the keys the search, focus and navigation above didn't
+                        // take, before the single-key bindings below
+                        _ if plugins::on_key(&key_name(key.code)) => {},
+                        // Snap straight back to the sung line, no timer, no glide
+                        KeyCode::Char('c') | KeyCode::Esc if app.lyrics_offset.is_some() => {
+                            app.lyrics_offset = None;
+                            app.last_scroll_time = None;
+                            app.scroll_lock = false;
+                        },
+                        // Lyrics paging: a pane height at a time, or straight to either end
+                        KeyCode::PageDown => app.scroll_lyrics(app.lyrics_page as isize),
+                        KeyCode::PageUp => app.scroll_lyrics(-(app.lyrics_page as isize)),
+                        KeyCode::Home | KeyCode::Char('g') => app.scroll_lyrics_to(0),
+                        KeyCode::End | KeyCode::Char('G') => app.scroll_lyrics_to(usize::MAX),
+                        KeyCode::Char('l') => app.toggle_loop_line(),
+                        KeyCode::Char('L') => app.toggle_scroll_lock(),
+                        KeyCode::Char('t') => {
+                            // Show/hide the lines under the lyrics, romanizing and translating them the first time
+                            let lines = match &app.lyrics {
+                                LyricsState::Loaded(lines) => lines.clone(),
+                                _ => Vec::new(),
+                            };
+                            let has_translation = lines.iter().any(|l| l.translation.is_some());
+                            let has_romanization = lines.iter().any(|l| l.romanization.is_some());
+                            let romanizable = app.lyrics_language.is_some_and(lyrics::romanize::supported);
+                            let can_translate = translate::configured(&app.config.translation) && !app.config.offline;
+                            if lines.is_empty() {
+                                app.show_toast("translation needs synced lyrics");
+                            } else if !app.translations_hidden && (has_translation || has_romanization) {
+                                app.translations_hidden = true;
+                            } else if app.lyrics_in_user_language() && !has_translation {
+                                app.show_toast(format!("lyrics are already in {}", app.user_language.to_uppercase()));
+                            } else {
+                                app.translations_hidden = false;
+                                if romanizable && !has_romanization {
+                                    spawn_romanize(&tx, &app, &last_track_id, true);
+                                }
+                                if !has_translation && can_translate {
+                                    if let Some(track) = app.track.clone() {
+                                        app.show_toast("translating…");
+                                        let texts: Vec<String> = lines.iter().map(|l| l.text.clone()).collect();
+                                        let (tx_translate, client, config) = (tx.clone(), client.clone(), app.config.translation.clone());
+                                        let (id, target) = (last_track_id.clone(), app.user_language.clone());
+                                        tokio::spawn(async move {
+                                            let result = translate::translate(&client, &config, &track.artist, &track.name, &texts, &target).await;
+                                            let _ = tx_translate.send(AppEvent::TranslationUpdate(id, result.map_err(|e| e.to_string()))).await;
+                                        });
+                                    }
+                                } else if !has_translation && !has_romanization && !romanizable {
+                                    app.show_toast(if app.config.offline {
+                                        "can't translate offline"
+                                    } else {
+                                        "set up [translation] in the config to translate lyrics"
+                                    });
+                                }
+                            }
+                        },
+                        KeyCode::Char('w') => {
+                            app.pomodoro = match app.pomodoro {
+                                Some(_) => None,
+                                None => Some(pomodoro::Pomodoro::start(&app.config.pomodoro)),
+                            };
+                        },
+                        KeyCode::Char('q') => app.request_quit(),
+                        KeyCode::Char('d') => {
+                            if let Some(escape) = terminal_progress.as_mut().and_then(|progress| progress.clear()) {
+                                let _ = terminal.backend_mut().write_all(escape.as_bytes());
+                            }
+                            if let Err(e) = detach(&mut terminal, &tx, &mut listening, is_standalone, input_client) {
+                                app.show_toast(e.to_string());
+                            }
+                        },
                         KeyCode::Char(' ') => { let _ = player.play_pause(); },
-                        KeyCode::Char('n') => { let _ = player.next(); },
-                        KeyCode::Char('p') => { let _ = player.prev(); },
-                        KeyCode::Char('+') | KeyCode::Char('=') => { let _ = player.volume_up(); },
-                        KeyCode::Char('-') | KeyCode::Char('_') => { let _ = player.volume_down(); },
+                        KeyCode::Char('n') => { app.remember_jump(app::Jump::Next); let _ = player.next(); },
+                        KeyCode::Char('p') => { app.remember_jump(app::Jump::Prev); let _ = player.prev(); },
+                        KeyCode::Char('u') => app.undo_jump(player.as_ref()),
+                        KeyCode::Char('e') => {
+                            // Now-playing note for a journal: to the configured file or the clipboard
+                            if let Some(track) = &app.track {
+                                let note = snapshot::markdown(track, app.clock.position_ms(), app.current_lyric_text());
+                                let message = match snapshot::export(&note, app.config.snapshot.file.as_deref()) {
+                                    Ok(snapshot::Destination::Clipboard) => "snapshot copied".to_string(),
+                                    Ok(snapshot::Destination::File(file)) => format!("snapshot added to {}", file),
+                                    Err(e) => format!("snapshot failed: {}", e),
+                                };
+                                app.show_toast(message);
+                            }
+                        },
+                        KeyCode::Char('f') => {
+                            // Heart the track, or take the heart back, everywhere at once: here (Last.fm /
+                            // ListenBrainz hear about it right away), Music's love, Spotify's Liked Songs
+                            if let Some(track) = app.track.clone() {
+                                let loved = !app.is_loved(&track);
+                                if app.loved.contains(&track) != loved {
+                                    app.loved.toggle(&track);
+                                    app.loved.save();
+                                    if let Some(services) = &loved_services {
+                                        spawn_loved_sync(tx.clone(), client.clone(), services.clone(), app.loved.pending(), false);
+                                    }
+                                }
+                                // Spotify's AppleScript can't like tracks, so that goes through the Web API
+                                let spotify = spotify_web.clone().zip(track.id.clone()).filter(|_| track.source == "Spotify");
+                                if let Some((web, uri)) = spotify {
+                                    app.spotify_saved = Some((uri.clone(), loved));
+                                    let tx_saved = tx.clone();
+                                    tokio::spawn(async move {
+                                        if let Err(e) = web.set_saved(&uri, loved).await {
+                                            let _ = tx_saved.send(AppEvent::Toast(format!("Liked Songs: {}", e))).await;
+                                        }
+                                    });
+                                } else if track.favorite != Some(loved) {
+                                    // Players without likes keep just the heart here
+                                    if let Ok(now) = player.toggle_favorite() {
+                                        if let Some(current) = &mut app.track {
+                                            current.favorite = Some(now);
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        KeyCode::Char('X') => {
+                            // Ban and skip; on a banned track (auto-skip off) it lifts the ban
+                            if let Some(track) = &app.track {
+                                if app.banned.toggle(track) {
+                                    app.remember_jump(app::Jump::Next);
+                                    let _ = player.next();
+                                }
+                                app.banned.save();
+                            }
+                        },
+                        KeyCode::Char(']') | KeyCode::Char('[') => {
+                            // Chapter skip (episodes with timestamped show notes)
+                            if let Some(target_ms) = app.chapter_seek_target(code == KeyCode::Char(']')) {
+                                app.remember_jump(app::Jump::Seek);
+                                if player.seek(target_ms as f64 / 1000.0).is_ok() {
+                                    app.clock.seek(target_ms);
+                                }
+                            }
+                        },
+                        // Star rating (Music): 0-5, or Alt+0-5 while the player strip has the numbers
+                        KeyCode::Char(c @ '0'..='5')
+                            if app.track.as_ref().is_some_and(|t| t.rating.is_some())
+                                && (app.players.len() <= 1 || key.modifiers.contains(KeyModifiers::ALT)) =>
+                        {
+                            let stars = c as u8 - b'0';
+                            match player.set_rating(stars) {
+                                Ok(()) => {
+                                    if let Some(track) = &mut app.track {
+                                        track.rating = Some(stars);
+                                    }
+                                },
+                                Err(e) => app.show_toast(format!("rating failed: {}", e)),
+                            }
+                        },
+                        KeyCode::Char(c @ '1'..='9') => {
+                            // Pick which player the card tracks (selector strip)
+                            let idx = c as usize - '1' as usize;
+                            if let Some(picked) = app.players.get(idx) {
+                                player::set_preferred_player(Some(picked.name.clone()));
+                                app.party_view = false;
+                            }
+                        },
+                        KeyCode::Char('P') => app.party_view ^= true,
+                        KeyCode::Char('R') if app.recap.is_some() => app.recap = None,
+                        KeyCode::Char('R') => app.open_recap(),
+                        KeyCode::Char(',') if terminal.backend().is_local() => {
+                            // The editor gets the terminal and stdin: nothing reads keys meanwhile
+                            input_task.abort();
+                            let _ = (&mut input_task).await;
+                            let edited = edit_config(&mut terminal);
+                            input_task = spawn_input(tx.clone());
+                            artwork_placed = None;
+                            if let Err(e) = edited {
+                                app.show_toast(e.to_string());
+                            }
+                        },
+                        KeyCode::Char(',') => app.show_toast("the config opens in the local terminal only"),
+                        KeyCode::F(12) => {
+                            app.debug_overlay ^= true;
+                            app.debug_sample = None;
+                            stats::set_enabled(app.debug_overlay);
+                        }
+                        // Cycle performance → balanced → battery (stops following the power source)
+                        KeyCode::Char('b') => power::set(power::current().next()),
+                        KeyCode::Esc if app.party_view => app.party_view = false,
+                        KeyCode::Char('o') => {
+                            // Open the playlist/album we're playing from in Spotify
+                            if let Some(context) = &app.spotify_context {
+                                let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+                                let _ = std::process::Command::new(opener)
+                                    .arg(&context.uri)
+                                    .stdout(std::process::Stdio::null())
+                                    .stderr(std::process::Stdio::null())
+                                    .spawn();
+                            }
+                        },
+                        KeyCode::Char('z') => {
+                            app.lightbox = true;
+                            // The card may have asked for less than a full-terminal cover: fetch the bigger one
+                            if let (ArtworkState::Loaded(img), Some(track), Some(key)) = (&app.artwork, &app.track, &last_artwork_key) {
+                                let term_size = terminal.size()?;
+                                let unpinned = config::ArtworkConfig { resolution: None, ..config.artwork.clone() };
+                                let size = artwork::pick_resolution(&unpinned, artwork_protocol, term_size.width, term_size.height);
+                                if !offline && size > img.width().max(img.height()) {
+                                    spawn_artwork_fetch(tx.clone(), client.clone(), track, key.clone(), size, offline);
+                                }
+                            }
+                        },
+                        KeyCode::Char('M') => {
+                            // Over to the player's own window, e.g. Music's Get Info for tags and counts
+                            if let Err(e) = player.reveal() {
+                                app.show_toast(e.to_string());
+                            }
+                        },
+                        KeyCode::Char('s') | KeyCode::Char('r') => {
+                            let web = spotify_web.clone().filter(|_| app.track.as_ref().is_some_and(|t| t.source == "Spotify"));
+                            spawn_mode_toggle(tx.clone(), web, code == KeyCode::Char('s'));
+                        },
+                        KeyCode::Char('Q') if app.queue.is_some() => app.queue = None,
+                        KeyCode::Char('Q') => {
+                            app.queue = Some(app::QueuePane::default());
+                            app.focus = app::Focus::Queue;
+                            let spotify = app.track.as_ref().is_some_and(|t| t.source == "Spotify");
+                            spawn_queue_fetch(tx.clone(), spotify_web.clone().filter(|_| spotify), spotify, offline);
+                        },
+                        KeyCode::Char('O') => {
+                            app.output_picker = Some(app::OutputPicker::default());
+                            let (tx_devices, web) = (tx.clone(), spotify_web.clone());
+                            tokio::spawn(async move {
+                                let mut devices = tokio::task::spawn_blocking(output::devices).await.unwrap_or_default();
+                                // Connect devices go after the system's outputs
+                                if let Some(web) = web {
+                                    devices.extend(web.devices().await.unwrap_or_default());
+                                }
+                                let _ = tx_devices.send(AppEvent::OutputDevices(devices)).await;
+                            });
+                        },
+                        KeyCode::Char('+') | KeyCode::Char('=') => { let _ = player.volume_up(app.config.ui.volume_step); },
+                        KeyCode::Char('-') | KeyCode::Char('_') => { let _ = player.volume_down(app.config.ui.volume_step); },
+                        // Show/hide parts of the UI for this session
+                        KeyCode::Char('A') => app.config.ui.show_artwork ^= true,
+                        KeyCode::Char('V') => app.config.ui.show_visualizer ^= true,
+                        KeyCode::Char('C') => app.config.ui.show_controls ^= true,
+                        KeyCode::Char('F') => app.config.ui.show_footer ^= true,
+                        KeyCode::Char('I') => app.config.ui.info_icons ^= true,
                         _ => {}
                     }
                 },
                 AppEvent::Input(_) => {},
+                AppEvent::Interrupt => app.request_quit(),
+                AppEvent::Terminate => app.is_running = false,
+                AppEvent::ReplayFinished => app.is_running = false,
+                AppEvent::Command(line, reply) => {
+                    let _ = reply.send(ipc::handle(&mut app, player.as_ref(), &line));
+                },
+                AppEvent::ConfigReload(Ok(mut reloaded)) => {
+                    // `--offline` from the command line outlasts the file
+                    reloaded.offline = offline;
+                    power::set(reloaded.power_profile);
+                    power::set_polling(&reloaded.polling);
+                    artwork::set_cache_limit(reloaded.artwork.cache_mb);
+                    app.keymap = keys::Keymap::new(&reloaded.keys);
+                    app.config = (*reloaded).clone();
+                    config = *reloaded;
+                    // Unknown [keys] are reported on stderr, right over the frame
+                    let _ = terminal.clear();
+                    artwork_placed = None;
+                    app.show_toast("config reloaded");
+                },
+                AppEvent::ConfigReload(Err(e)) => app.show_toast(format!("config.toml: {}", e)),
+                AppEvent::Attach(id, client, width, height) => {
+                    terminal.backend_mut().attach(id, client, ratatui::layout::Size::new(width, height));
+                    let _ = terminal.clear();
+                    artwork_placed = None;
+                },
+                AppEvent::RemoteInput(..) => {},
                 
+                AppEvent::NotificationAction(action) => match action {
+                    notification::Action::PlayPause => { let _ = player.play_pause(); },
+                    notification::Action::Next => { app.remember_jump(app::Jump::Next); let _ = player.next(); },
+                },
+                AppEvent::OutputDevice(device) => {
+                    // Switched while playing (not the first reading): stop before the room hears it
+                    let playing = app.track.as_ref().is_some_and(|t| t.state == player::PlayerState::Playing);
+                    if app.config.output.pause_on_change && playing && app.output_device.is_some() && device != app.output_device {
+                        if let Some(name) = &device {
+                            let _ = player.play_pause();
+                            app.show_toast(format!("paused: output is now {}", name));
+                        }
+                    }
+                    app.output_device = device;
+                },
+                AppEvent::QueueUpdate(tracks) => {
+                    if let Some(queue) = &mut app.queue {
+                        let len = tracks.as_ref().map_or(0, Vec::len);
+                        queue.selected = queue.selected.min(len.saturating_sub(1));
+                        queue.tracks = Some(tracks);
+                    }
+                },
+                AppEvent::OutputDevices(devices) => {
+                    // Start on the device playing now
+                    if let Some(picker) = &mut app.output_picker {
+                        picker.selected = devices.iter().position(|d| !d.connect && Some(&d.name) == app.output_device.as_ref()).unwrap_or(0);
+                        picker.devices = Some(devices);
+                    }
+                },
+                AppEvent::Toast(message) => app.show_toast(message),
+                AppEvent::SavedUpdate(uri, saved) => app.spotify_saved = Some((uri, saved)),
+                AppEvent::ModesUpdate(shuffle, repeat) => {
+                    app.shuffle = shuffle;
+                    app.repeat = repeat;
+                },
+                AppEvent::LovedSynced(outcome) => {
+                    app.loved.apply(outcome);
+                    app.loved.save();
+                },
+                AppEvent::PlayerError(e) => {
+                    // The card shows the error until the next good poll clears it
+                    app.player_error = Some(e);
+                },
                 AppEvent::TrackUpdate(info) => {
+                    app.player_error = None;
                     app.track = info.clone();
+                    app.clock.sync(info.as_ref());
                     if let Some(track) = info {
+                        app.log_listening(&track);
+                        let submission = scrobbler.update(&track);
+                        if app.is_long_track(&track) {
+                            app.bookmarks.record(&track);
+                        }
                         let id = format!("{}{}", track.name, track.artist);
                         if id != last_track_id {
                             last_track_id = id.clone();
+                            // Banned with `X`: straight on to the next one
+                            if app.config.banned.auto_skip && app.banned.contains(&track) {
+                                let _ = player.next();
+                            }
+                            // Hearts given or taken back on the services since the last pull
+                            if let Some(services) = loved_services.as_ref().filter(|_| loved_pulled_at.elapsed() >= LOVED_PULL_INTERVAL) {
+                                loved_pulled_at = std::time::Instant::now();
+                                spawn_loved_sync(tx.clone(), client.clone(), services.clone(), app.loved.pending(), true);
+                            }
+                            // Long tracks: offer to pick up where they were left off
+                            app.bookmarks.save();
+                            app.resume_prompt = if app.is_long_track(&track) { app.bookmarks.resume_point(&track) } else { None };
+                            app.track_changed_at = Some(std::time::Instant::now());
                             // Critical: Set Loading state immediately
-                            app.lyrics = LyricsState::Loading;
+                            app.set_lyrics(LyricsState::Loading);
                             // Critical Fix: Reset manual scroll state on song change
                             app.lyrics_offset = None;
+                            app.lyrics_search = None;
+                            app.loop_line = None;
+                            app.typing = None;
                             app.last_scroll_time = None;
-                            
-                            // 1. Check Cache
-                            if let Some(cached) = app.lyrics_cache.get(&id) {
-                                app.lyrics = LyricsState::Loaded(cached.clone());
-                            } else {
-                                // 2. If not in cache, fetch
-                                let tx_lyrics = tx.clone();
-                                let (artist, name, dur) = (track.artist.clone(), track.name.clone(), track.duration_ms);
-                                let fetch_id = id.clone();
-                                
-                                let client = client.clone();
+                            app.scroll_lock = false;
+                            app.intro_checked = false;
+                            app.outro_checked = false;
+
+                            // Playback context and private session: only Spotify needs the Web API for them.
+                            // Plugins hear about the track once we know whether it's private.
+                            app.spotify_context = None;
+                            held_submissions.clear();
+                            let sp_dc = config.lyrics.spotify_sp_dc.clone().filter(|_| track.source == "Spotify");
+                            match (sp_dc, offline) {
+                                (Some(sp_dc), false) => {
+                                    app.private_session_pending = true;
+                                    let tx_status = tx.clone();
+                                    let (client, status_id) = (client.clone(), id.clone());
+                                    tokio::spawn(async move {
+                                        let status = spotify_api::fetch_status(&client, &sp_dc).await.ok();
+                                        let _ = tx_status.send(AppEvent::SpotifyStatusUpdate(status_id, status)).await;
+                                    });
+                                }
+                                (sp_dc, _) => {
+                                    // Offline the lookup can't run: like a failed one, the last known state stands
+                                    if sp_dc.is_none() {
+                                        app.private_session = false;
+                                    }
+                                    app.private_session_pending = false;
+                                    announce_track(&app, &track);
+                                }
+                            }
+
+                            // Analysis: choruses get bolder lyrics and the loudness goes
+                            // in the corner (same Web API token)
+                            app.sections.clear();
+                            app.loudness_db = None;
+                            app.normalization_off = track.source == "Spotify" && spotify_api::normalization_enabled() == Some(false);
+                            if let (true, Some(uri), Some(sp_dc), false) = (track.source == "Spotify", track.id.clone(), config.lyrics.spotify_sp_dc.clone(), offline) {
+                                let tx_analysis = tx.clone();
+                                let (client, analysis_id) = (client.clone(), id.clone());
                                 tokio::spawn(async move {
-                                    let fetcher = LyricsFetcher::new(client);
-                                    use crate::lyrics::LyricsFetchResult;
-                                    match fetcher.fetch(&artist, &name, dur).await {
-                                        Ok(LyricsFetchResult::Found(lyrics)) => { 
-                                            let _ = tx_lyrics.send(AppEvent::LyricsUpdate(fetch_id, LyricsState::Loaded(lyrics))).await;
-                                        },
-                                        Ok(LyricsFetchResult::Instrumental) => {
-                                             let _ = tx_lyrics.send(AppEvent::LyricsUpdate(fetch_id, LyricsState::Instrumental)).await;
-                                        },
-                                        Ok(LyricsFetchResult::None) => {
-                                             let _ = tx_lyrics.send(AppEvent::LyricsUpdate(fetch_id, LyricsState::NotFound)).await;
-                                        }
-                                        Err(e) => {
-                                            // Send Error State
-                                            let _ = tx_lyrics.send(AppEvent::LyricsUpdate(fetch_id, LyricsState::Failed(e.to_string()))).await;
-                                        }
+                                    if let Ok(analysis) = spotify_api::fetch_analysis(&client, &sp_dc, &uri).await {
+                                        let _ = tx_analysis.send(AppEvent::AnalysisUpdate(analysis_id, analysis)).await;
                                     }
                                 });
                             }
 
-                            // 2. Artwork Logic (Once per song checks - Apple Music Fallback)
-                            if track.source == "Music" && track.artwork_url.is_none() {
-                                app.artwork = ArtworkState::Loading;
-                                let tx_art = tx.clone();
-                                let (artist, album) = (track.artist.clone(), track.album.clone());
-                                let client = client.clone();
+                            // Shuffle and repeat for the controls row
+                            spawn_modes_fetch(tx.clone(), spotify_web.clone().filter(|_| track.source == "Spotify"));
+
+                            // The queue pane moves along with the track
+                            if app.queue.is_some() {
+                                let spotify = track.source == "Spotify";
+                                spawn_queue_fetch(tx.clone(), spotify_web.clone().filter(|_| spotify), spotify, offline);
+                            }
+
+                            // Liked Songs, for the heart (Web API login)
+                            if let (true, Some(uri), Some(web)) = (track.source == "Spotify", track.id.clone(), spotify_web.clone()) {
+                                let tx_saved = tx.clone();
                                 tokio::spawn(async move {
-                                    let renderer = ArtworkRenderer::new(client);
-                                    match renderer.fetch_itunes_artwork(&artist, &album).await {
-                                        Ok(url) => {
-                                             match renderer.fetch_image(&url).await {
-                                                 Ok(img) => { let _ = tx_art.send(AppEvent::ArtworkUpdate(ArtworkState::Loaded(img))).await; },
-                                                 Err(_) => { let _ = tx_art.send(AppEvent::ArtworkUpdate(ArtworkState::Failed)).await; }
-                                             }
-                                        },
-                                        Err(_) => { let _ = tx_art.send(AppEvent::ArtworkUpdate(ArtworkState::Failed)).await; }
+                                    if let Ok(saved) = web.is_saved(&uri).await {
+                                        let _ = tx_saved.send(AppEvent::SavedUpdate(uri, saved)).await;
                                     }
                                 });
                             }
-                        }
 
-                        // Standard URL-based update (Spotify or when Music eventually resolves one)
-                        if let Some(url) = track.artwork_url.clone() {
-                            if Some(url.clone()) != last_artwork_url {
-                                last_artwork_url = Some(url.clone());
-                                app.artwork = ArtworkState::Loading;
-                                let tx_art = tx.clone();
-                                let client = client.clone();
+                            // Tempo: Music's own BPM tag, else ask GetSongBPM
+                            app.tempo_bpm = track.bpm;
+                            if let (None, Some(key), false) = (track.bpm, config.visualizer.getsongbpm_key.clone(), offline) {
+                                let tx_tempo = tx.clone();
+                                let (artist, name) = (track.artist.clone(), track.name.clone());
+                                let (client, tempo_id) = (client.clone(), id.clone());
                                 tokio::spawn(async move {
-                                    let renderer = ArtworkRenderer::new(client);
-                                    match renderer.fetch_image(&url).await {
-                                         Ok(img) => { let _ = tx_art.send(AppEvent::ArtworkUpdate(ArtworkState::Loaded(img))).await; },
-                                         Err(_) => { let _ = tx_art.send(AppEvent::ArtworkUpdate(ArtworkState::Failed)).await; }
+                                    if let Ok(Some(bpm)) = tempo::fetch_bpm(&client, &key, &artist, &name).await {
+                                        let _ = tx_tempo.send(AppEvent::TempoUpdate(tempo_id, bpm)).await;
                                     }
                                 });
                             }
+                            
+                            // 1. Check Cache
+                            if let Some(cached) = app.lyrics_cache.get(&id) {
+                                app.set_lyrics(cached.clone());
+                                spawn_romanize(&tx, &app, &id, false);
+                            } else if !replaying {
+                                // 2. If not in cache, fetch (a replay has its lyrics recorded)
+                                spawn_lyrics_fetch(tx.clone(), client.clone(), config.lyrics.clone(), offline, track.clone(), id.clone());
+                            }
+                        }
+
+                        // Private sessions stay off the profiles too. Until a Spotify track's
+                        // status is known its submissions wait for it, and are dropped if the
+                        // track changes first.
+                        if let (Some(services), Some(submission)) = (&scrobble_services, submission) {
+                            if app.private_session_pending {
+                                held_submissions.push(submission);
+                            } else if !app.private_session {
+                                spawn_scrobble(client.clone(), services.clone(), submission, offline);
+                            }
+                        }
+
+                        // 2. Artwork: fetched once per cover (see `fetch_racing`). Keyed on the player URL
+                        // when there is one, else on artist+album (Music has no URL).
+                        let artwork_key = track.artwork_url.clone()
+                            .unwrap_or_else(|| format!("{}|{}", track.artist, track.album));
+                        if Some(&artwork_key) != last_artwork_key.as_ref() {
+                            last_artwork_key = Some(artwork_key.clone());
+                            app.artwork = ArtworkState::Loading;
+                            app.album_colors = None;
+                            let term_size = terminal.size()?;
+                            let size = artwork::pick_resolution(&config.artwork, artwork_protocol, term_size.width, term_size.height);
+                            spawn_artwork_fetch(tx.clone(), client.clone(), &track, artwork_key, size, offline);
+                        }
+
+                        // 3. Preload: at ~80% ask the player what's next and warm the lyrics and
+                        // artwork caches, so the next song has both the moment it starts.
+                        let near_end = app.clock.progress() >= 0.8;
+                        if near_end && preloaded_for != id && !replaying {
+                            preloaded_for = id.clone();
+                            let tx_preload = tx.clone();
+                            let client = client.clone();
+                            let lyrics_config = config.lyrics.clone();
+                            let cached: Vec<String> = app.lyrics_cache.keys().cloned().collect();
+                            let term_size = terminal.size()?;
+                            let art_size = artwork::pick_resolution(&config.artwork, artwork_protocol, term_size.width, term_size.height);
+                            let current_album = (track.artist.clone(), track.album.clone());
+                            // Spotify's AppleScript has no queue, the Web API does
+                            let web = spotify_web.clone().filter(|_| track.source == "Spotify");
+                            tokio::spawn(async move {
+                                let next = match web {
+                                    Some(web) => web.queue().await.ok().and_then(|queue| queue.into_iter().next()),
+                                    None => tokio::task::spawn_blocking(|| player::get_player().get_next_track()).await.ok().and_then(Result::ok).flatten(),
+                                };
+                                if let Some(next) = next {
+                                    // Same album means same cover, which is already on screen
+                                    if !offline && (next.artist.clone(), next.album.clone()) != current_album {
+                                        let renderer = ArtworkRenderer::new(client.clone());
+                                        let (url, artist, album) = (next.artwork_url.clone(), next.artist.clone(), next.album.clone());
+                                        tokio::spawn(async move {
+                                            let _ = renderer.prefetch(url.as_deref(), &artist, &album, art_size).await;
+                                        });
+                                    }
+
+                                    let next_id = format!("{}{}", next.name, next.artist);
+                                    if !cached.contains(&next_id) {
+                                        spawn_lyrics_fetch(tx_preload, client, lyrics_config, offline, next, next_id);
+                                    }
+                                }
+                            });
                         }
                     } else {
                         last_track_id.clear();
-                        last_artwork_url = None;
+                        last_artwork_key = None;
                         app.artwork = ArtworkState::Idle;
+                        app.album_colors = None;
                     }
                 },
+                AppEvent::PlayersUpdate(players) => app.players = players,
                 AppEvent::LyricsUpdate(id, state) => {
                     // Update cache if loaded
-                    if let LyricsState::Loaded(ref l) = state {
-                         app.lyrics_cache.insert(id.clone(), l.clone());
+                    if let LyricsState::Loaded(_) | LyricsState::Unsynced(..) | LyricsState::ShowNotes(_) = state {
+                         app.lyrics_cache.insert(id.clone(), state.clone());
                     }
                     
                     // Only update UI if we are still on the same song
                     if id == last_track_id {
-                         app.lyrics = state;
+                         app.set_lyrics(state);
+                         spawn_romanize(&tx, &app, &id, false);
+                    }
+                },
+                AppEvent::TranslationUpdate(id, result) => match result {
+                    Ok(translations) => app.attach_translations(&id, id == last_track_id, &translations),
+                    Err(e) if id == last_track_id => app.show_toast(format!("translation failed: {}", e)),
+                    Err(_) => {}
+                },
+                AppEvent::RomanizationUpdate(id, result) => match result {
+                    Ok(romanized) => app.attach_romanizations(&id, id == last_track_id, &romanized),
+                    Err(e) if id == last_track_id => app.show_toast(e),
+                    Err(_) => {}
+                },
+                AppEvent::SpotifyStatusUpdate(id, status) => {
+                    if id == last_track_id {
+                        // A failed lookup keeps the last known private session state
+                        if let Some(status) = status {
+                            app.spotify_context = status.context;
+                            app.private_session = status.private_session;
+                        }
+                        app.private_session_pending = false;
+                        for submission in held_submissions.drain(..) {
+                            if let (Some(services), false) = (&scrobble_services, app.private_session) {
+                                spawn_scrobble(client.clone(), services.clone(), submission, offline);
+                            }
+                        }
+                        if let Some(track) = &app.track {
+                            announce_track(&app, track);
+                        }
+                    }
+                },
+                AppEvent::AnalysisUpdate(id, analysis) => {
+                    if id == last_track_id {
+                        app.sections = analysis.sections;
+                        app.loudness_db = analysis.loudness_db;
+                    }
+                },
+                AppEvent::TempoUpdate(id, bpm) => {
+                    if id == last_track_id {
+                        app.tempo_bpm = Some(bpm);
+                    }
+                },
+                AppEvent::ArtworkUpdate(key, data) => {
+                    // A slow source may answer after the track already changed
+                    // A bigger copy for the lightbox replaces the cover in place; if that fails, the old one stays
+                    let upgrade = matches!(app.artwork, ArtworkState::Loaded(_));
+                    if Some(&key) == last_artwork_key.as_ref() && (!upgrade || matches!(data, ArtworkState::Loaded(_))) {
+                        if let ArtworkState::Loaded(img) = &data {
+                            app.album_colors = artwork::accent_colors(img);
+                        }
+                        app.artwork = data;
+                        if !upgrade {
+                            app.artwork_rendered = None;
+                        }
+                        artwork_rendering = None;
+                    }
+                },
+                AppEvent::ArtworkRendered(key, size, rendered) => {
+                    if Some(&key) == last_artwork_key.as_ref() {
+                        // The fade starts once there's something to see
+                        if app.artwork_rendered.is_none() {
+                            app.artwork_loaded_at = Some(std::time::Instant::now());
+                        }
+                        app.artwork_rendered = Some((size, rendered));
+                        artwork_stale = true;
+                    }
+                },
+                AppEvent::Connectivity(false) => {
+                    if !app.network_down {
+                        app.network_down = true;
+                        // Probe until it's back
+                        let (tx_probe, client) = (tx.clone(), client.clone());
+                        tokio::spawn(async move {
+                            loop {
+                                tokio::time::sleep(Duration::from_secs(5)).await;
+                                if client.head(CONNECTIVITY_PROBE_URL).send().await.is_ok() {
+                                    let _ = tx_probe.send(AppEvent::Connectivity(true)).await;
+                                    break;
+                                }
+                            }
+                        });
+                    }
+                },
+                AppEvent::Connectivity(true) => {
+                    app.network_down = false;
+                    // Retry whatever failed while we were cut off
+                    if let (LyricsState::Failed(_), Some(track)) = (&app.lyrics, &app.track) {
+                        spawn_lyrics_fetch(tx.clone(), client.clone(), config.lyrics.clone(), offline, track.clone(), last_track_id.clone());
+                        app.set_lyrics(LyricsState::Loading);
+                    }
+                    if matches!(app.artwork, ArtworkState::Failed) {
+                        // The next track poll starts a new artwork race
+                        last_artwork_key = None;
                     }
                 },
-                AppEvent::ArtworkUpdate(data) => app.artwork = data,
                 AppEvent::ThemeUpdate(new_theme) => app.theme = new_theme,
+                AppEvent::DebugSample(sample) => app.debug_sample = Some(sample),
                 AppEvent::Tick => {
-                    // Animation Logic: Return to center
-                    if app.last_scroll_time.is_none() && app.lyrics_offset.is_some() {
-                        if let (LyricsState::Loaded(lyrics), Some(track)) = (&app.lyrics, &app.track) {
-                            // 1. Calculate Target
-                            let target_idx = lyrics.iter()
-                               .position(|l| l.timestamp_ms > track.position_ms)
-                               .map(|i| if i > 0 { i - 1 } else { 0 })
-                               .unwrap_or(0);
-                            
+                    app.ticks = app.ticks.wrapping_add(1);
+                    app.tick_visualizer();
+                    app.plugin_widget = plugins::custom_widget();
+                    app.sync_typing();
+
+                    if app.recap_due() {
+                        app.open_recap();
+                    }
+
+                    // Focus timer: pause or resume as an interval ends
+                    if let Some(timer) = &mut app.pomodoro {
+                        if let Some(phase) = timer.advance() {
+                            let playing = app.track.as_ref().is_some_and(|t| t.state == player::PlayerState::Playing);
+                            if timer.wants_music(phase) != playing {
+                                let _ = player.play_pause();
+                            }
+                        }
+                    }
+
+                    // Instrumental intros and outros, when asked to skip them
+                    if let Some(target) = app.intro_skip_target() {
+                        if player.seek(target as f64 / 1000.0).is_ok() {
+                            app.clock.seek(target);
+                            app.show_toast(format!("{} skipped intro", glyphs::get(app.config.ui.glyphs).next));
+                        }
+                    }
+                    if app.outro_reached() && player.next().is_ok() {
+                        app.show_toast(format!("{} skipped outro", glyphs::get(app.config.ui.glyphs).next));
+                    }
+
+                    // Loop this line: jump back once the next line would start
+                    if let Some(start) = app.loop_seek_target() {
+                        if player.seek(start as f64 / 1000.0).is_ok() {
+                            app.clock.seek(start);
+                        }
+                    }
+
+                    // Animation Logic: Return to center (show notes stay where the reader left them)
+                    let follows_playback = !matches!(app.lyrics, LyricsState::ShowNotes(_));
+                    if follows_playback && app.last_scroll_time.is_none() && app.lyrics_offset.is_some() {
+                        // 1. Calculate Target
+                        if let Some(target_idx) = app.current_lyric_idx() {
                             // 2. Animate Offset
                             if let Some(curr) = &mut app.lyrics_offset {
                                 if *curr < target_idx {
@@ -377,8 +1710,127 @@ async fn main() -> Result<()> {
         if !app.is_running { break; }
     }
 
+    if app.config.quit.pause_playback
+        && app.track.as_ref().is_some_and(|t| t.state == player::PlayerState::Playing)
+    {
+        let _ = player.play_pause();
+    }
+
+    state::save(&app, &config);
+    app.bookmarks.save();
+    if let Some(listening) = app.now_listening.take() {
+        listening.finish();
+    }
+    session::cleanup();
+    ipc::cleanup();
+    if let Some(escape) = terminal_progress.as_mut().and_then(|progress| progress.clear()) {
+        let _ = terminal.backend_mut().write_all(escape.as_bytes());
+    }
+    if terminal.backend().is_local() {
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+        terminal.show_cursor()?;
+    } else {
+        // Hang up on an attached client so it restores its own terminal
+        terminal.backend_mut().detach();
+    }
+    if let Some(recorder) = &recorder {
+        recorder.save()?;
+    }
+    if let Some(dir) = sandbox {
+        let _ = std::fs::remove_dir_all(dir);
+        println!("{}", frame_times.summary());
+    }
+    Ok(())
+}
+
+/// `arg` as one word for `sh`: single-quoted, with any `'` closed, escaped and reopened
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Take the last graphics-protocol cover down (if `was_placed`) and write the
+/// current one where this frame put it
+fn place_artwork(terminal: &mut Terminal<SessionBackend>, app: &mut App, was_placed: bool, protocol: config::ArtworkProtocol, is_tmux: bool) -> Result<()> {
+    if was_placed {
+        match artwork::remove_escape(protocol, is_tmux) {
+            Some(escape) => terminal.backend_mut().write_all(escape.as_bytes())?,
+            // Inline images are text: a full redraw paints over them
+            None => {
+                terminal.clear()?;
+                terminal.draw(|f| ui::ui(f, app))?;
+            }
+        }
+    }
+    if let (Some(at), Some((_, artwork::Rendered::Graphic(graphic)))) = (app.artwork_placement, &app.artwork_rendered) {
+        terminal.backend_mut().write_all(graphic.escape_at(at).as_bytes())?;
+    }
+    terminal.backend_mut().flush()?;
+    Ok(())
+}
+
+/// Forward terminal input to the event loop until aborted
+fn spawn_input(tx: mpsc::Sender<AppEvent>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut reader = EventStream::new();
+        while let Some(Ok(event)) = reader.next().await {
+            if tx.send(AppEvent::Input(event)).await.is_err() { break; }
+        }
+    })
+}
+
+/// `,`: config.toml in $VISUAL / $EDITOR with the TUI out of the way. The
+/// config watcher applies whatever was saved.
+fn edit_config(terminal: &mut Terminal<SessionBackend>) -> Result<()> {
+    let path = config::config_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let fallback = if cfg!(target_os = "windows") { "notepad" } else { "vi" };
+    let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR")).unwrap_or_else(|_| fallback.to_string());
+    // "code --wait" and the like: the first word is the program
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(fallback);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show)?;
+    let status = std::process::Command::new(program).args(words).arg(&path).status();
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(_) => anyhow::bail!("{} exited with an error", program),
+        Err(e) => anyhow::bail!("couldn't run {}: {}", program, e),
+    }
+}
+
+/// `d`: hand back the terminal (or that attached client's) and keep running
+/// headless until `vyom attach`
+fn detach(terminal: &mut Terminal<SessionBackend>, tx: &mpsc::Sender<AppEvent>, listening: &mut bool, is_standalone: bool, client: Option<u64>) -> Result<()> {
+    if !*listening {
+        session::listen(tx.clone())?;
+        *listening = true;
+    }
+    if !terminal.backend().is_local() {
+        match client {
+            Some(id) => terminal.backend_mut().detach_client(id),
+            None => terminal.backend_mut().detach(),
+        }
+        return Ok(());
+    }
+
+    terminal.backend_mut().detach();
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
-    terminal.show_cursor()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show)?;
+    println!("Vyom detached. Run `vyom attach` to bring it back.");
+
+    // The tmux split was made for us, no need to leave it open
+    if is_standalone {
+        if let Ok(pane) = std::env::var("TMUX_PANE") {
+            let _ = std::process::Command::new("tmux").args(["kill-pane", "-t", &pane]).status();
+        }
+    }
     Ok(())
 }
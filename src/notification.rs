@@ -0,0 +1,105 @@
+#[cfg(target_os = "linux")]
+use std::io::{BufRead, BufReader};
+#[cfg(target_os = "linux")]
+use std::process::{Command, Stdio};
+#[cfg(target_os = "linux")]
+use std::sync::atomic::{AtomicU32, Ordering};
+use tokio::sync::mpsc;
+
+use crate::player::TrackInfo;
+use crate::AppEvent;
+
+// Now-playing notifications 🔔
+// On Linux, every new track gets a desktop notification with Play/Pause and
+// Next buttons (D-Bus notification actions, through `gdbus`), so playback can
+// be driven from the notification while the TUI is buried. Each one replaces
+// the last instead of stacking up.
+
+/// A button pressed on one of our notifications
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    PlayPause,
+    Next,
+}
+
+// --- Linux: org.freedesktop.Notifications over `gdbus` 🐧 ---
+
+#[cfg(target_os = "linux")]
+const SERVICE: &str = "org.freedesktop.Notifications";
+#[cfg(target_os = "linux")]
+const OBJECT_PATH: &str = "/org/freedesktop/Notifications";
+
+/// Id of the notification on screen (0 = none yet), replaced by the next one
+#[cfg(target_os = "linux")]
+static CURRENT: AtomicU32 = AtomicU32::new(0);
+
+/// GVariant string literal, so titles with quotes stay titles
+#[cfg(target_os = "linux")]
+fn quoted(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Show (or replace) the now-playing notification. Runs in the background.
+#[cfg(target_os = "linux")]
+pub fn show(track: &TrackInfo) {
+    let (title, body) = (quoted(&track.name), quoted(&format!("{} — {}", track.artist, track.album)));
+    std::thread::spawn(move || {
+        let actions = format!("[{}, {}, {}, {}]", quoted("playpause"), quoted("Play/Pause"), quoted("next"), quoted("Next"));
+        let Ok(output) = Command::new("gdbus")
+            .args(["call", "--session", "--dest", SERVICE, "--object-path", OBJECT_PATH])
+            .args(["--method", "org.freedesktop.Notifications.Notify"])
+            .args(["'Vyom'", &CURRENT.load(Ordering::Relaxed).to_string(), "'audio-x-generic'", &title, &body, &actions, "{}", "5000"])
+            .stderr(Stdio::null())
+            .output()
+        else {
+            return;
+        };
+        // "(uint32 42,)"
+        let text = String::from_utf8_lossy(&output.stdout);
+        if let Some(id) = text.trim().trim_start_matches("(uint32 ").split(',').next().and_then(|id| id.parse().ok()) {
+            CURRENT.store(id, Ordering::Relaxed);
+        }
+    });
+}
+
+/// Watch for buttons pressed on our notifications and hand them to the main loop
+#[cfg(target_os = "linux")]
+pub fn listen(tx: mpsc::Sender<AppEvent>) {
+    std::thread::spawn(move || {
+        let Ok(mut child) = Command::new("gdbus")
+            .args(["monitor", "--session", "--dest", SERVICE, "--object-path", OBJECT_PATH])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return;
+        };
+        let Some(stdout) = child.stdout.take() else { return };
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            // "/org/freedesktop/Notifications: org.freedesktop.Notifications.ActionInvoked (uint32 42, 'next')"
+            let Some((_, args)) = line.split_once(".ActionInvoked (") else { continue };
+            let Some((id, key)) = args.trim_end_matches(')').split_once(", ") else { continue };
+            if id.trim_start_matches("uint32 ").parse::<u32>().ok() != Some(CURRENT.load(Ordering::Relaxed)) {
+                continue;
+            }
+            let action = match key.trim_matches('\'') {
+                "playpause" => Action::PlayPause,
+                "next" => Action::Next,
+                _ => continue,
+            };
+            if tx.blocking_send(AppEvent::NotificationAction(action)).is_err() {
+                break;
+            }
+        }
+        let _ = child.kill();
+    });
+}
+
+// --- Elsewhere: not supported yet ---
+
+#[cfg(not(target_os = "linux"))]
+pub fn show(_track: &TrackInfo) {}
+
+#[cfg(not(target_os = "linux"))]
+pub fn listen(_tx: mpsc::Sender<AppEvent>) {}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use std::process::{Command, Stdio};
+
+// Output device 🎧
+// Which device the system plays through (AirPods, speakers, a DAC), shown in
+// the header. Checked every few seconds so a change, like headphones dropping
+// back to the laptop speakers, can pause playback (`[output] pause_on_change`).
+// `O` lists the devices and switches the system default, with Spotify Connect
+// devices under them once the Web API login is set up.
+
+/// An output the system can play through
+#[derive(Debug, Clone, PartialEq)]
+pub struct Device {
+    /// What `switch` takes: the sink name on Linux, the device name on macOS
+    pub id: String,
+    pub name: String,
+    /// A Spotify Connect device (`vyom auth spotify`): picking it moves Spotify's playback there
+    pub connect: bool,
+    /// Spotify is playing on it (Connect devices only)
+    pub active: bool,
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).stderr(Stdio::null()).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// --- macOS: CoreAudio's default output via `system_profiler` 🍎 ---
+
+/// Name of the default output device
+#[cfg(target_os = "macos")]
+pub fn current() -> Option<String> {
+    let json = run("system_profiler", &["SPAudioDataType", "-json"])?;
+    let data: serde_json::Value = serde_json::from_str(&json).ok()?;
+    data.pointer("/SPAudioDataType/0/_items")?
+        .as_array()?
+        .iter()
+        .find(|device| device.get("coreaudio_default_audio_output_device").and_then(|v| v.as_str()) == Some("spaudio_yes"))
+        .and_then(|device| device.get("_name")?.as_str().map(str::to_string))
+}
+
+/// Every output device. `SwitchAudioSource` (Homebrew's switchaudio-osx) when
+/// it's installed, else the devices with output channels in the profiler.
+#[cfg(target_os = "macos")]
+pub fn devices() -> Vec<Device> {
+    if let Some(list) = run("SwitchAudioSource", &["-a", "-t", "output"]) {
+        return list.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Device { id: line.trim().to_string(), name: line.trim().to_string(), connect: false, active: false })
+            .collect();
+    }
+    let Some(json) = run("system_profiler", &["SPAudioDataType", "-json"]) else { return Vec::new() };
+    let Ok(data) = serde_json::from_str::<serde_json::Value>(&json) else { return Vec::new() };
+    let items = data.pointer("/SPAudioDataType/0/_items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    items.iter()
+        .filter(|device| device.get("coreaudio_device_output").is_some())
+        .filter_map(|device| device.get("_name")?.as_str())
+        .map(|name| Device { id: name.to_string(), name: name.to_string(), connect: false, active: false })
+        .collect()
+}
+
+/// Make `device` the default output. CoreAudio has no command for it, so this
+/// needs `SwitchAudioSource`.
+#[cfg(target_os = "macos")]
+pub fn switch(device: &Device) -> Result<()> {
+    let status = Command::new("SwitchAudioSource")
+        .args(["-t", "output", "-s", &device.id])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|_| anyhow::anyhow!("switching needs SwitchAudioSource (brew install switchaudio-osx)"))?;
+    if !status.success() {
+        anyhow::bail!("couldn't switch to {}", device.name);
+    }
+    Ok(())
+}
+
+// --- Linux: PulseAudio/PipeWire's default sink via `pactl` 🐧 ---
+
+/// Every sink, named by its description
+#[cfg(not(target_os = "macos"))]
+pub fn devices() -> Vec<Device> {
+    let list = run("pactl", &["list", "sinks"]).unwrap_or_default();
+    list.split("\nSink #")
+        .filter_map(|block| {
+            let field = |name: &str| block.lines().find_map(|line| line.trim().strip_prefix(name).map(str::to_string));
+            let id = field("Name: ")?;
+            let name = field("Description: ").unwrap_or_else(|| id.clone());
+            Some(Device { id, name, connect: false, active: false })
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn current() -> Option<String> {
+    let sink = run("pactl", &["get-default-sink"])?.trim().to_string();
+    if sink.is_empty() {
+        return None;
+    }
+    // The sink's name is an id ("bluez_output.…"); its description is what people know it by
+    let name = devices().into_iter().find(|device| device.id == sink).map(|device| device.name);
+    Some(name.unwrap_or(sink))
+}
+
+/// Make `device` the default sink; PipeWire and recent PulseAudio move the
+/// playing streams over with it
+#[cfg(not(target_os = "macos"))]
+pub fn switch(device: &Device) -> Result<()> {
+    let status = Command::new("pactl")
+        .args(["set-default-sink", &device.id])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|_| anyhow::anyhow!("switching needs pactl"))?;
+    if !status.success() {
+        anyhow::bail!("couldn't switch to {}", device.name);
+    }
+    Ok(())
+}
@@ -1,6 +1,14 @@
+#[cfg(any(target_os = "macos", target_os = "windows"))]
 use std::process::Command;
-use anyhow::{Result, Context};
+use anyhow::Result;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+#[cfg(target_os = "macos")]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(target_os = "macos")]
+use std::process::{Child, ChildStdin, ChildStdout, Stdio};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PlayerState {
@@ -9,6 +17,102 @@ pub enum PlayerState {
     Stopped,
 }
 
+/// Why a running player couldn't be read, so the UI can say something better than "Not Running"
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayerError {
+    /// macOS Automation permission not granted (AppleScript error -1743)
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    PermissionDenied(String),
+    /// The player is running but the script failed: (player, message)
+    Errored(String, String),
+}
+
+impl std::fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayerError::PermissionDenied(app) => write!(f, "Not allowed to control {}", app),
+            PlayerError::Errored(app, msg) => write!(f, "{} error: {}", app, msg),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
+
+/// Ask macOS for Automation permission to control `app_name`. Any Apple event
+/// raises the consent prompt while the choice is undecided. Once it has been
+/// refused macOS never asks again, so open the Automation settings instead.
+/// Blocks until the user answers the prompt.
+#[cfg(target_os = "macos")]
+pub fn request_automation_permission(app_name: &str) {
+    let asked = Command::new("osascript")
+        .arg("-e")
+        .arg(format!("tell application \"{}\" to get name", app_name))
+        .output();
+    let still_denied = match asked {
+        Ok(o) => String::from_utf8_lossy(&o.stderr).contains("-1743"),
+        Err(_) => true,
+    };
+    if still_denied {
+        let _ = Command::new("open")
+            .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_Automation")
+            .status();
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn request_automation_permission(_app_name: &str) {}
+
+/// A running player as shown in the selector strip and the party view
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerSummary {
+    pub name: String, // "Spotify" or "Music"
+    pub state: PlayerState,
+    pub title: Option<String>, // What it has loaded, if anything
+    pub artist: Option<String>,
+}
+
+/// Player the user picked with the number keys. Every poll creates a fresh
+/// player, so the choice lives here instead of on the player itself.
+static PREFERRED_PLAYER: Mutex<Option<String>> = Mutex::new(None);
+
+/// Track `name` instead of the default pick (None restores the default)
+pub fn set_preferred_player(name: Option<String>) {
+    if let Ok(mut preferred) = PREFERRED_PLAYER.lock() {
+        *preferred = name;
+    }
+}
+
+pub fn preferred_player() -> Option<String> {
+    PREFERRED_PLAYER.lock().ok()?.clone()
+}
+
+/// Repeat setting, in the order `r` cycles through them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Repeat {
+    Off,
+    /// The playlist or album
+    Context,
+    Track,
+}
+
+impl Repeat {
+    pub fn next(self) -> Self {
+        match self {
+            Repeat::Off => Repeat::Context,
+            Repeat::Context => Repeat::Track,
+            Repeat::Track => Repeat::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Repeat::Off => "Repeat off",
+            Repeat::Context => "Repeat all",
+            Repeat::Track => "Repeat one",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackInfo {
     pub name: String,
@@ -19,17 +123,60 @@ pub struct TrackInfo {
     pub position_ms: u64,
     pub state: PlayerState,
     pub source: String, // "Spotify" or "Music"
+    pub id: Option<String>, // Spotify URI (spotify:track:...), None for Music
+    pub bpm: Option<f32>, // Music's BPM tag when set, None otherwise
+    #[serde(default)]
+    pub volume: Option<u8>, // Player volume 0-100
+    #[serde(default)]
+    pub context: Option<String>, // Playlist/album the track plays from, when known
+    #[serde(default)]
+    pub rating: Option<u8>, // Music's rating in stars (0-5, 0 = unrated), None for players without one
+    #[serde(default)]
+    pub play_count: Option<u32>, // Music's play count, None for players that don't keep one
+    #[serde(default)]
+    pub last_played: Option<u64>, // Unix seconds Music last finished the track (before this play)
+    #[serde(default)]
+    pub favorite: Option<bool>, // Loved in the player itself (Music's love), None where the player doesn't say
+}
+
+impl TrackInfo {
+    /// Stable key for remembering a track: the Spotify URI when there is one,
+    /// else title/artist/album (Music has no ids)
+    pub fn key(&self) -> String {
+        match &self.id {
+            Some(id) => id.clone(),
+            None => format!("{}|{}|{}", self.name, self.artist, self.album),
+        }
+    }
 }
 
 /// The unified interface for any OS Media Player 🎵
 pub trait PlayerTrait {
     fn get_current_track(&self) -> Result<Option<TrackInfo>>;
+    /// The track that plays after this one, when the player exposes its queue
+    fn get_next_track(&self) -> Result<Option<TrackInfo>>;
+    /// Every supported player that is currently running
+    fn list_players(&self) -> Result<Vec<PlayerSummary>>;
     fn play_pause(&self) -> Result<()>;
     fn next(&self) -> Result<()>;
     fn prev(&self) -> Result<()>;
     fn seek(&self, position_secs: f64) -> Result<()>;
-    fn volume_up(&self) -> Result<()>;
-    fn volume_down(&self) -> Result<()>;
+    /// Start a specific track by its `TrackInfo::id`, where the player supports it
+    fn play_track(&self, id: &str) -> Result<()>;
+    /// Rate the current track 0-5 stars (0 clears it), where the player has ratings
+    fn set_rating(&self, stars: u8) -> Result<()>;
+    /// Bring the player to the front with the current track selected, for editing it there
+    fn reveal(&self) -> Result<()>;
+    /// Love the current track in the player itself, or take it back. Returns
+    /// whether it's loved now.
+    fn toggle_favorite(&self) -> Result<bool>;
+    fn get_shuffle(&self) -> Result<bool>;
+    fn set_shuffle(&self, on: bool) -> Result<()>;
+    fn get_repeat(&self) -> Result<Repeat>;
+    fn set_repeat(&self, repeat: Repeat) -> Result<()>;
+    /// Volume up/down by `step` percent. The new level, where the player reports it.
+    fn volume_up(&self, step: u8) -> Result<Option<u8>>;
+    fn volume_down(&self, step: u8) -> Result<Option<u8>>;
 }
 
 /// Factory to get the correct player for the current OS
@@ -38,21 +185,52 @@ pub fn get_player() -> Box<dyn PlayerTrait> {
     {
         Box::new(MacOsPlayer)
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(WindowsPlayer)
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     {
-        // Placeholder for Linux/Windows
+        // Placeholder for Linux
         Box::new(DummyPlayer)
     }
 }
 
 // --- macOS Implementation 🍎 ---
 
+#[cfg(target_os = "macos")]
 pub struct MacOsPlayer;
 
+#[cfg(target_os = "macos")]
 impl MacOsPlayer {
+    const APPS: [&'static str; 2] = ["Spotify", "Music"];
+
+    /// Turn a failed script into Ok(None) when nothing is loaded (-1728,
+    /// "can't get current track"), or a PlayerError the UI can explain.
+    fn classify_error(app_name: &str, e: anyhow::Error) -> Result<Option<TrackInfo>> {
+        let msg = e.to_string();
+        if msg.contains("-1728") {
+            Ok(None)
+        } else if msg.contains("-1743") {
+            Err(PlayerError::PermissionDenied(app_name.to_string()).into())
+        } else {
+            let msg = msg.trim_start_matches("AppleScript error: ").to_string();
+            Err(PlayerError::Errored(app_name.to_string(), msg).into())
+        }
+    }
+
     /// Detect which player is active: "Spotify", "Music", or None.
-    /// Prioritizes Spotify if both are running.
+    /// The one picked in the selector strip wins while it's running,
+    /// otherwise Spotify is prioritized if both are running.
     fn detect_active_player(&self) -> Option<&'static str> {
+        if let Some(preferred) = preferred_player() {
+            if let Some(app) = Self::APPS.iter().find(|a| **a == preferred) {
+                if Self::is_app_running(app) {
+                    return Some(app);
+                }
+            }
+        }
+
         if Self::is_app_running("Spotify") {
             Some("Spotify")
         } else if Self::is_app_running("Music") {
@@ -62,19 +240,34 @@ impl MacOsPlayer {
         }
     }
 
+    /// Move the volume by `delta` percent and read back where it landed
+    fn step_volume(&self, delta: i32) -> Result<Option<u8>> {
+        let Some(app) = self.detect_active_player() else { return Ok(None) };
+        let volume = Self::run_script(&format!(r#"
+            tell application "{}"
+                set sound volume to (sound volume + ({}))
+                return sound volume
+            end tell
+        "#, app, delta))?;
+        Ok(volume.parse().ok())
+    }
+
     fn is_app_running(app_name: &str) -> bool {
-        let output = Command::new("pgrep")
-            .arg("-x")
-            .arg(app_name)
-            .output();
-        match output {
-            Ok(o) => o.status.success(),
-            Err(_) => false,
-        }
+        // Asking by name doesn't launch the app, and goes through the bridge instead of forking pgrep
+        Self::run_script(&format!("return (application \"{}\" is running) as text", app_name))
+            .is_ok_and(|running| running == "true")
     }
 
-    /// Run an AppleScript command
+    /// Run an AppleScript command, through the bridge when it's up
     fn run_script(script: &str) -> Result<String> {
+        if let Some(reply) = ScriptBridge::run(script) {
+            return match reply {
+                BridgeReply { ok: Some(output), .. } => Ok(output.trim().to_string()),
+                BridgeReply { error, .. } => anyhow::bail!("AppleScript error: {}", error.unwrap_or_default()),
+            };
+        }
+
+        // No bridge: one osascript per call
         let output = Command::new("osascript")
             .arg("-e")
             .arg(script)
@@ -83,13 +276,110 @@ impl MacOsPlayer {
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("AppleScript error: {}", stderr);
+            anyhow::bail!("AppleScript error: {}", stderr.trim());
         }
 
         Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
     }
 }
 
+/// A long-lived `osascript` running a JXA loop that compiles and runs every
+/// AppleScript it's sent with NSAppleScript, in-process. Polling twice a
+/// second then costs no fork at all. Line protocol: a JSON string (the
+/// script) in, `{"ok": "..."}` or `{"error": "message (-1728)"}` out.
+#[cfg(target_os = "macos")]
+struct ScriptBridge {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+#[cfg(target_os = "macos")]
+#[derive(Deserialize)]
+struct BridgeReply {
+    ok: Option<String>,
+    error: Option<String>,
+}
+
+/// Shared by every MacOsPlayer (they're created per poll)
+#[cfg(target_os = "macos")]
+static BRIDGE: Mutex<Option<ScriptBridge>> = Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+impl ScriptBridge {
+    const JXA: &'static str = r#"
+        ObjC.import('Foundation');
+        const input = $.NSFileHandle.fileHandleWithStandardInput;
+        const output = $.NSFileHandle.fileHandleWithStandardOutput;
+        const reply = (obj) => output.writeData($(JSON.stringify(obj) + '\n').dataUsingEncoding($.NSUTF8StringEncoding));
+        let buffer = '';
+        while (true) {
+            const data = input.availableData;
+            if (data.length === 0) break;
+            buffer += $.NSString.alloc.initWithDataEncoding(data, $.NSUTF8StringEncoding).js;
+            let newline;
+            while ((newline = buffer.indexOf('\n')) >= 0) {
+                const source = JSON.parse(buffer.slice(0, newline));
+                buffer = buffer.slice(newline + 1);
+                const error = Ref();
+                const result = $.NSAppleScript.alloc.initWithSource(source).executeAndReturnError(error);
+                if (result.isNil()) {
+                    const info = error[0];
+                    reply({ error: ObjC.unwrap(info.objectForKey('NSAppleScriptErrorMessage')) + ' (' + ObjC.unwrap(info.objectForKey('NSAppleScriptErrorNumber')) + ')' });
+                } else {
+                    reply({ ok: ObjC.unwrap(result.stringValue) || '' });
+                }
+            }
+        }
+    "#;
+
+    fn spawn() -> Result<Self> {
+        let mut child = Command::new("osascript")
+            .args(["-l", "JavaScript", "-e", Self::JXA])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to start the AppleScript bridge")?;
+        let stdin = child.stdin.take().context("Bridge has no stdin")?;
+        let stdout = BufReader::new(child.stdout.take().context("Bridge has no stdout")?);
+        Ok(Self { child, stdin, stdout })
+    }
+
+    fn request(&mut self, script: &str) -> Result<BridgeReply> {
+        writeln!(self.stdin, "{}", serde_json::to_string(script)?)?;
+        self.stdin.flush()?;
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            anyhow::bail!("AppleScript bridge exited");
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    /// Run `script` on the bridge, starting it if needed. None when it can't
+    /// be started or just died (it's restarted on the next call).
+    fn run(script: &str) -> Option<BridgeReply> {
+        let mut bridge = BRIDGE.lock().ok()?;
+        if bridge.is_none() {
+            *bridge = Self::spawn().ok();
+        }
+        let reply = bridge.as_mut()?.request(script);
+        if reply.is_err() {
+            *bridge = None;
+        }
+        reply.ok()
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for ScriptBridge {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(target_os = "macos")]
 impl PlayerTrait for MacOsPlayer {
     fn get_current_track(&self) -> Result<Option<TrackInfo>> {
         let app_name = match self.detect_active_player() {
@@ -109,54 +399,163 @@ impl PlayerTrait for MacOsPlayer {
                 set tDuration to duration of current track
                 set tPosition to player position
                 set tState to player state as string
+                set tVolume to sound volume
                 
                 if "{}" is "Spotify" then
                     -- Spotify Duration is ms
                     set tArtwork to artwork url of current track
-                    return tName & "|||" & tArtist & "|||" & tAlbum & "|||" & tDuration & "|||" & tPosition & "|||" & tState & "|||" & tArtwork
+                    set tId to id of current track
+                    return tName & "|||" & tArtist & "|||" & tAlbum & "|||" & tDuration & "|||" & tPosition & "|||" & tState & "|||" & tArtwork & "|||" & tId & "|||" & "0" & "|||" & tVolume & "|||" & "NONE" & "|||" & "NONE" & "|||" & "NONE" & "|||" & "NONE" & "|||" & "NONE"
                 else
                     -- Music App: duration is seconds
                     set tDurSec to duration of current track
                     set tDuration to tDurSec * 1000
-                    return tName & "|||" & tArtist & "|||" & tAlbum & "|||" & tDuration & "|||" & tPosition & "|||" & tState & "|||" & "NONE"
+                    set tBpm to bpm of current track
+                    -- 0-100, 20 per star
+                    set tRating to rating of current track
+                    set tPlays to played count of current track
+                    -- Seconds since, so the date needs no parsing; never-played tracks have no date
+                    set tPlayedAgo to "NONE"
+                    try
+                        set tPlayedAgo to ((current date) - (played date of current track)) as integer
+                    end try
+                    set tLoved to loved of current track
+                    set tContext to "NONE"
+                    try
+                        set tContext to name of current playlist
+                    end try
+                    return tName & "|||" & tArtist & "|||" & tAlbum & "|||" & tDuration & "|||" & tPosition & "|||" & tState & "|||" & "NONE" & "|||" & "NONE" & "|||" & tBpm & "|||" & tVolume & "|||" & tContext & "|||" & tRating & "|||" & tPlays & "|||" & tPlayedAgo & "|||" & tLoved
                 end if
             end tell
         "#, app_name, app_name);
 
-        match Self::run_script(&script) {
-            Ok(output) => {
-                if output == "STOPPED" {
-                    return Ok(None);
-                }
+        let output = match Self::run_script(&script) {
+            Ok(output) => output,
+            Err(e) => return Self::classify_error(app_name, e),
+        };
+        if output == "STOPPED" {
+            return Ok(None);
+        }
 
-                let parts: Vec<&str> = output.split("|||").collect();
-                if parts.len() < 7 {
-                    return Ok(None);
-                }
+        let parts: Vec<&str> = output.split("|||").collect();
+        if parts.len() < 9 {
+            return Ok(None);
+        }
 
-                let position_secs: f64 = parts[4].replace(',', ".").parse().unwrap_or(0.0);
-                
-                let state = match parts[5] {
-                    "playing" => PlayerState::Playing,
-                    "paused" => PlayerState::Paused,
-                    _ => PlayerState::Stopped,
-                };
-                
-                let duration_ms: u64 = parts[3].parse::<f64>().unwrap_or(0.0) as u64;
+        let position_secs: f64 = parts[4].replace(',', ".").parse().unwrap_or(0.0);
+        
+        let state = match parts[5] {
+            "playing" => PlayerState::Playing,
+            "paused" => PlayerState::Paused,
+            _ => PlayerState::Stopped,
+        };
+        
+        let duration_ms: u64 = parts[3].parse::<f64>().unwrap_or(0.0) as u64;
 
-                Ok(Some(TrackInfo {
-                    name: parts[0].to_string(),
-                    artist: parts[1].to_string(),
-                    album: parts[2].to_string(),
-                    duration_ms,
-                    position_ms: (position_secs * 1000.0) as u64,
-                    state,
-                    artwork_url: Some(parts[6].to_string()).filter(|s| !s.is_empty() && s != "NONE"),
-                    source: app_name.to_string(),
-                }))
-            },
-            Err(_) => Ok(None)
+        Ok(Some(TrackInfo {
+            name: parts[0].to_string(),
+            artist: parts[1].to_string(),
+            album: parts[2].to_string(),
+            duration_ms,
+            position_ms: (position_secs * 1000.0) as u64,
+            state,
+            artwork_url: Some(parts[6].to_string()).filter(|s| !s.is_empty() && s != "NONE"),
+            source: app_name.to_string(),
+            id: Some(parts[7].to_string()).filter(|s| !s.is_empty() && s != "NONE"),
+            bpm: parts[8].parse::<f32>().ok().filter(|b| *b > 0.0),
+            volume: parts.get(9).and_then(|v| v.parse::<u8>().ok()),
+            // Spotify's AppleScript has no playlist, Music names its current one
+            context: parts.get(10).map(|c| c.to_string()).filter(|c| !c.is_empty() && c != "NONE"),
+            // Spotify has no ratings (only "NONE" there)
+            rating: parts.get(11).and_then(|r| r.parse::<u8>().ok()).map(|r| (r / 20).min(5)),
+            play_count: parts.get(12).and_then(|n| n.parse().ok()),
+            last_played: parts.get(13).and_then(|secs| secs.parse::<u64>().ok()).map(|ago| crate::history::unix_now().saturating_sub(ago)),
+            // Spotify's AppleScript can't see Liked Songs; the Web API fills that in
+            favorite: parts.get(14).and_then(|loved| loved.parse::<bool>().ok()),
+        }))
+    }
+
+    fn get_next_track(&self) -> Result<Option<TrackInfo>> {
+        // Spotify's AppleScript dictionary has no queue, Music's playlist order does.
+        // With shuffle on the playlist order means nothing, so don't guess.
+        if self.detect_active_player() != Some("Music") {
+            return Ok(None);
         }
+
+        let script = r#"
+            tell application "Music"
+                if player state is stopped or shuffle enabled then
+                    return "NONE"
+                end if
+                set pl to current playlist
+                set idx to index of current track
+                if idx < (count of tracks of pl) then
+                    set t to track (idx + 1) of pl
+                    return name of t & "|||" & artist of t & "|||" & album of t & "|||" & ((duration of t) * 1000)
+                end if
+                return "NONE"
+            end tell
+        "#;
+
+        let output = match Self::run_script(script) {
+            Ok(output) => output,
+            Err(_) => return Ok(None),
+        };
+        let parts: Vec<&str> = output.split("|||").collect();
+        if parts.len() < 4 {
+            return Ok(None);
+        }
+
+        Ok(Some(TrackInfo {
+            name: parts[0].to_string(),
+            artist: parts[1].to_string(),
+            album: parts[2].to_string(),
+            duration_ms: parts[3].replace(',', ".").parse::<f64>().unwrap_or(0.0) as u64,
+            position_ms: 0,
+            state: PlayerState::Stopped,
+            artwork_url: None,
+            source: "Music".to_string(),
+            id: None,
+            bpm: None,
+            volume: None,
+            context: None,
+            rating: None,
+            play_count: None,
+            last_played: None,
+            favorite: None,
+        }))
+    }
+
+    fn list_players(&self) -> Result<Vec<PlayerSummary>> {
+        let mut players = Vec::new();
+        for app in Self::APPS {
+            // Only talk to running apps, `tell` would launch the others
+            if !Self::is_app_running(app) {
+                continue;
+            }
+            let script = format!(r#"
+                tell application "{}"
+                    set tState to player state as string
+                    set tName to ""
+                    set tArtist to ""
+                    try
+                        set tName to name of current track
+                        set tArtist to artist of current track
+                    end try
+                    return tState & "|||" & tName & "|||" & tArtist
+                end tell
+            "#, app);
+            let output = Self::run_script(&script).unwrap_or_default();
+            let parts: Vec<&str> = output.split("|||").collect();
+            let state = match parts[0] {
+                "playing" => PlayerState::Playing,
+                "paused" => PlayerState::Paused,
+                _ => PlayerState::Stopped,
+            };
+            let field = |i: usize| parts.get(i).map(|s| s.to_string()).filter(|s| !s.is_empty());
+            players.push(PlayerSummary { name: app.to_string(), state, title: field(1), artist: field(2) });
+        }
+        Ok(players)
     }
 
     fn play_pause(&self) -> Result<()> {
@@ -187,32 +586,358 @@ impl PlayerTrait for MacOsPlayer {
         Ok(())
     }
 
-    fn volume_up(&self) -> Result<()> {
-        if let Some(app) = self.detect_active_player() {
-             Self::run_script(&format!("tell application \"{}\" to set sound volume to (sound volume + 10)", app))?;
+    fn play_track(&self, id: &str) -> Result<()> {
+        // Only Spotify takes a track URI; Music has no id in TrackInfo
+        if !id.starts_with("spotify:") || self.detect_active_player() != Some("Spotify") {
+            anyhow::bail!("Can't select tracks by id in this player");
         }
+        Self::run_script(&format!("tell application \"Spotify\" to play track \"{}\"", id))?;
         Ok(())
     }
 
-    fn volume_down(&self) -> Result<()> {
-        if let Some(app) = self.detect_active_player() {
-            Self::run_script(&format!("tell application \"{}\" to set sound volume to (sound volume - 10)", app))?;
+    fn set_rating(&self, stars: u8) -> Result<()> {
+        if self.detect_active_player() != Some("Music") {
+            anyhow::bail!("Only Music has ratings");
+        }
+        Self::run_script(&format!("tell application \"Music\" to set rating of current track to {}", stars.min(5) as u32 * 20))?;
+        Ok(())
+    }
+
+    fn reveal(&self) -> Result<()> {
+        match self.detect_active_player() {
+            // Selects it in the library/playlist, where the Get Info window is a ⌘I away
+            Some("Music") => {
+                Self::run_script(r#"
+                    tell application "Music"
+                        reveal current track
+                        activate
+                    end tell
+                "#)?;
+            },
+            Some(app) => { Self::run_script(&format!("tell application \"{}\" to activate", app))?; },
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn toggle_favorite(&self) -> Result<bool> {
+        if self.detect_active_player() != Some("Music") {
+            anyhow::bail!("Only Music can love tracks over AppleScript");
+        }
+        let loved = Self::run_script(r#"
+            tell application "Music"
+                set loved of current track to not (loved of current track)
+                return loved of current track
+            end tell
+        "#)?;
+        Ok(loved.trim() == "true")
+    }
+
+    fn get_shuffle(&self) -> Result<bool> {
+        let shuffle = match self.detect_active_player() {
+            Some("Spotify") => Self::run_script("tell application \"Spotify\" to return shuffling")?,
+            Some(app) => Self::run_script(&format!("tell application \"{}\" to return shuffle enabled", app))?,
+            None => anyhow::bail!("No player running"),
+        };
+        Ok(shuffle == "true")
+    }
+
+    fn set_shuffle(&self, on: bool) -> Result<()> {
+        match self.detect_active_player() {
+            Some("Spotify") => { Self::run_script(&format!("tell application \"Spotify\" to set shuffling to {}", on))?; },
+            Some(app) => { Self::run_script(&format!("tell application \"{}\" to set shuffle enabled to {}", app, on))?; },
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn get_repeat(&self) -> Result<Repeat> {
+        match self.detect_active_player() {
+            // Spotify only says whether it repeats, which is the whole list
+            Some("Spotify") => {
+                let repeating = Self::run_script("tell application \"Spotify\" to return repeating")?;
+                Ok(if repeating == "true" { Repeat::Context } else { Repeat::Off })
+            },
+            Some(app) => {
+                let repeat = Self::run_script(&format!("tell application \"{}\" to return song repeat as string", app))?;
+                Ok(match repeat.as_str() {
+                    "all" => Repeat::Context,
+                    "one" => Repeat::Track,
+                    _ => Repeat::Off,
+                })
+            },
+            None => anyhow::bail!("No player running"),
+        }
+    }
+
+    fn set_repeat(&self, repeat: Repeat) -> Result<()> {
+        match (self.detect_active_player(), repeat) {
+            (Some("Spotify"), Repeat::Track) => anyhow::bail!("Spotify can't repeat one track over AppleScript"),
+            (Some("Spotify"), _) => {
+                Self::run_script(&format!("tell application \"Spotify\" to set repeating to {}", repeat == Repeat::Context))?;
+            },
+            (Some(app), _) => {
+                let mode = match repeat {
+                    Repeat::Off => "off",
+                    Repeat::Context => "all",
+                    Repeat::Track => "one",
+                };
+                Self::run_script(&format!("tell application \"{}\" to set song repeat to {}", app, mode))?;
+            },
+            (None, _) => {}
         }
         Ok(())
     }
+
+    fn volume_up(&self, step: u8) -> Result<Option<u8>> {
+        self.step_volume(step as i32)
+    }
+
+    fn volume_down(&self, step: u8) -> Result<Option<u8>> {
+        self.step_volume(-(step as i32))
+    }
 }
 
-// --- Dummy Implementation (Linux/Windows Placeholder) ---
-#[cfg(not(target_os = "macos"))]
+// --- Windows Implementation 🪟 ---
+// Media sessions come from the WinRT GlobalSystemMediaTransportControls
+// (SMTC), which Spotify, browsers and most players publish to. PowerShell
+// talks to WinRT, the way osascript does on macOS.
+
+#[cfg(target_os = "windows")]
+pub struct WindowsPlayer;
+
+#[cfg(target_os = "windows")]
+impl WindowsPlayer {
+    /// Loads the WinRT types, an `Await` for their async calls, and picks
+    /// `$session`: the player chosen in the selector strip while it has a
+    /// session, else the one Windows considers current
+    const PRELUDE: &'static str = r#"
+        [Console]::OutputEncoding = [Text.Encoding]::UTF8
+        Add-Type -AssemblyName System.Runtime.WindowsRuntime
+        $asTask = [System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object {
+            $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1'
+        } | Select-Object -First 1
+        function Await($op, [Type]$type) {
+            $task = $asTask.MakeGenericMethod($type).Invoke($null, @($op))
+            $task.Wait(-1) | Out-Null
+            $task.Result
+        }
+        function AppName($s) {
+            $id = $s.SourceAppUserModelId
+            if ($id -match 'Spotify') { 'Spotify' } else { ($id -split '!')[-1] -replace '\.exe$', '' }
+        }
+        $Manager = [Windows.Media.Control.GlobalSystemMediaTransportControlsSessionManager, Windows.Media.Control, ContentType = WindowsRuntime]
+        $Properties = [Windows.Media.Control.GlobalSystemMediaTransportControlsSessionMediaProperties, Windows.Media.Control, ContentType = WindowsRuntime]
+        $manager = Await ($Manager::RequestAsync()) $Manager
+        $sessions = @($manager.GetSessions())
+        $session = $sessions | Where-Object { (AppName $_) -eq $preferred } | Select-Object -First 1
+        if (-not $session) { $session = $manager.GetCurrentSession() }
+    "#;
+
+    /// Run a PowerShell script after the prelude. It goes in as
+    /// -EncodedCommand, so no quoting survives to bite.
+    fn run_script(body: &str) -> Result<String> {
+        let preferred = preferred_player().unwrap_or_default().replace('\'', "''");
+        let script = format!("$preferred = '{}'\n{}\n{}", preferred, Self::PRELUDE, body);
+        let utf16: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-EncodedCommand", &crate::base64::encode(&utf16)])
+            .output()
+            .context("Failed to run PowerShell")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("PowerShell error: {}", stderr.trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Run a control call on the picked session, e.g. `TrySkipNextAsync()`
+    fn control(call: &str) -> Result<()> {
+        Self::run_script(&format!("if ($session) {{ Await ($session.{}) ([bool]) | Out-Null }}", call))?;
+        Ok(())
+    }
+
+    fn state(status: &str) -> PlayerState {
+        match status {
+            "Playing" => PlayerState::Playing,
+            "Paused" => PlayerState::Paused,
+            _ => PlayerState::Stopped,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl PlayerTrait for WindowsPlayer {
+    fn get_current_track(&self) -> Result<Option<TrackInfo>> {
+        let script = r#"
+            if (-not $session) { return 'NONE' }
+            $props = Await ($session.TryGetMediaPropertiesAsync()) $Properties
+            $timeline = $session.GetTimelineProperties()
+            $status = $session.GetPlaybackInfo().PlaybackStatus
+            # The timeline is a snapshot from whenever the player last reported it
+            $position = $timeline.Position.TotalMilliseconds
+            if ($status -eq 'Playing') { $position += ([DateTimeOffset]::Now - $timeline.LastUpdatedTime).TotalMilliseconds }
+            @($props.Title, $props.Artist, $props.AlbumTitle, [int64]$timeline.EndTime.TotalMilliseconds, [int64]$position, $status, (AppName $session)) -join '|||'
+        "#;
+        let output = Self::run_script(script).map_err(|e| PlayerError::Errored("Media session".to_string(), e.to_string()))?;
+        let parts: Vec<&str> = output.split("|||").collect();
+        if parts.len() < 7 || parts[0].is_empty() {
+            return Ok(None);
+        }
+        let state = Self::state(parts[5]);
+        if state == PlayerState::Stopped {
+            return Ok(None);
+        }
+        let duration_ms: u64 = parts[3].parse().unwrap_or(0);
+        let position_ms: u64 = parts[4].parse::<i64>().unwrap_or(0).max(0) as u64;
+
+        Ok(Some(TrackInfo {
+            name: parts[0].to_string(),
+            artist: parts[1].to_string(),
+            album: parts[2].to_string(),
+            duration_ms,
+            position_ms: if duration_ms > 0 { position_ms.min(duration_ms) } else { position_ms },
+            state,
+            // SMTC hands out a thumbnail stream, not a URL: the artwork lookup takes over
+            artwork_url: None,
+            source: parts[6].to_string(),
+            id: None,
+            bpm: None,
+            volume: None,
+            context: None,
+            rating: None,
+            play_count: None,
+            last_played: None,
+            favorite: None,
+        }))
+    }
+
+    fn get_next_track(&self) -> Result<Option<TrackInfo>> {
+        // SMTC doesn't expose queues
+        Ok(None)
+    }
+
+    fn list_players(&self) -> Result<Vec<PlayerSummary>> {
+        let script = r#"
+            foreach ($s in $sessions) {
+                $props = Await ($s.TryGetMediaPropertiesAsync()) $Properties
+                @((AppName $s), $s.GetPlaybackInfo().PlaybackStatus, $props.Title, $props.Artist) -join '|||'
+            }
+        "#;
+        let output = Self::run_script(script)?;
+        Ok(output.lines()
+            .map(|line| {
+                let parts: Vec<&str> = line.split("|||").collect();
+                let field = |i: usize| parts.get(i).map(|s| s.to_string()).filter(|s| !s.is_empty());
+                PlayerSummary {
+                    name: parts[0].to_string(),
+                    state: Self::state(parts.get(1).copied().unwrap_or_default()),
+                    title: field(2),
+                    artist: field(3),
+                }
+            })
+            .collect())
+    }
+
+    fn play_pause(&self) -> Result<()> {
+        Self::control("TryTogglePlayPauseAsync()")
+    }
+
+    fn next(&self) -> Result<()> {
+        Self::control("TrySkipNextAsync()")
+    }
+
+    fn prev(&self) -> Result<()> {
+        Self::control("TrySkipPreviousAsync()")
+    }
+
+    fn seek(&self, position_secs: f64) -> Result<()> {
+        // Positions are in 100ns ticks
+        Self::control(&format!("TryChangePlaybackPositionAsync({})", (position_secs * 10_000_000.0) as i64))
+    }
+
+    fn play_track(&self, _id: &str) -> Result<()> {
+        anyhow::bail!("Can't select tracks by id in this player")
+    }
+
+    fn set_rating(&self, _stars: u8) -> Result<()> {
+        anyhow::bail!("Media sessions have no ratings")
+    }
+
+    fn reveal(&self) -> Result<()> {
+        anyhow::bail!("Can't bring the player forward from here")
+    }
+
+    fn toggle_favorite(&self) -> Result<bool> {
+        anyhow::bail!("Media sessions have no likes")
+    }
+
+    fn get_shuffle(&self) -> Result<bool> {
+        let shuffle = Self::run_script("if ($session) { $session.GetPlaybackInfo().IsShuffleActive }")?;
+        match shuffle.as_str() {
+            "True" => Ok(true),
+            "False" => Ok(false),
+            _ => anyhow::bail!("This player doesn't shuffle"),
+        }
+    }
+
+    fn set_shuffle(&self, on: bool) -> Result<()> {
+        Self::control(&format!("TryChangeShuffleActiveAsync(${})", on))
+    }
+
+    fn get_repeat(&self) -> Result<Repeat> {
+        let repeat = Self::run_script("if ($session) { $session.GetPlaybackInfo().AutoRepeatMode }")?;
+        match repeat.as_str() {
+            "None" => Ok(Repeat::Off),
+            "Track" => Ok(Repeat::Track),
+            "List" => Ok(Repeat::Context),
+            _ => anyhow::bail!("This player doesn't repeat"),
+        }
+    }
+
+    fn set_repeat(&self, repeat: Repeat) -> Result<()> {
+        // MediaPlaybackAutoRepeatMode: None, Track, List
+        let mode = match repeat {
+            Repeat::Off => 0,
+            Repeat::Track => 1,
+            Repeat::Context => 2,
+        };
+        Self::control(&format!("TryChangeAutoRepeatModeAsync({})", mode))
+    }
+
+    // SMTC has no volume: step the system volume with the media keys instead
+    // Each press moves it 2%
+    fn volume_up(&self, step: u8) -> Result<Option<u8>> {
+        Self::run_script(&format!("$shell = New-Object -ComObject WScript.Shell; 1..{} | ForEach-Object {{ $shell.SendKeys([char]175) }}", (step / 2).max(1)))?;
+        Ok(None)
+    }
+
+    fn volume_down(&self, step: u8) -> Result<Option<u8>> {
+        Self::run_script(&format!("$shell = New-Object -ComObject WScript.Shell; 1..{} | ForEach-Object {{ $shell.SendKeys([char]174) }}", (step / 2).max(1)))?;
+        Ok(None)
+    }
+}
+
+// --- Dummy Implementation (Linux Placeholder, and what `--replay` runs against) ---
 pub struct DummyPlayer;
 
-#[cfg(not(target_os = "macos"))]
 impl PlayerTrait for DummyPlayer {
     fn get_current_track(&self) -> Result<Option<TrackInfo>> { Ok(None) }
+    fn get_next_track(&self) -> Result<Option<TrackInfo>> { Ok(None) }
+    fn list_players(&self) -> Result<Vec<PlayerSummary>> { Ok(Vec::new()) }
     fn play_pause(&self) -> Result<()> { Ok(()) }
     fn next(&self) -> Result<()> { Ok(()) }
     fn prev(&self) -> Result<()> { Ok(()) }
     fn seek(&self, _pos: f64) -> Result<()> { Ok(()) }
-    fn volume_up(&self) -> Result<()> { Ok(()) }
-    fn volume_down(&self) -> Result<()> { Ok(()) }
+    fn play_track(&self, _id: &str) -> Result<()> { Ok(()) }
+    fn set_rating(&self, _stars: u8) -> Result<()> { Ok(()) }
+    fn reveal(&self) -> Result<()> { Ok(()) }
+    fn toggle_favorite(&self) -> Result<bool> { Ok(false) }
+    fn get_shuffle(&self) -> Result<bool> { Ok(false) }
+    fn set_shuffle(&self, _on: bool) -> Result<()> { Ok(()) }
+    fn get_repeat(&self) -> Result<Repeat> { Ok(Repeat::Off) }
+    fn set_repeat(&self, _repeat: Repeat) -> Result<()> { Ok(()) }
+    fn volume_up(&self, _step: u8) -> Result<Option<u8>> { Ok(None) }
+    fn volume_down(&self, _step: u8) -> Result<Option<u8>> { Ok(None) }
 }
@@ -0,0 +1,296 @@
+use anyhow::{Context, Result};
+use mlua::{Function, HookTriggers, Lua, RegistryKey, Table};
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::player::{PlayerState, TrackInfo};
+
+// Plugin thread 🧩
+// Every plugin lives in one Lua state on a thread of its own, and the UI only
+// ever sends it jobs. Nothing on the event loop waits for Lua, except `on_key`
+// for a moment, and every hook call gets a time budget: a plugin that loops
+// or blocks is stopped with an error instead of freezing Vyom.
+
+/// Jobs for the plugin thread
+static JOBS: OnceLock<mpsc::Sender<Job>> = OnceLock::new();
+/// File names of the loaded plugins, for `on_track_change`'s `skip`
+static NAMES: OnceLock<Vec<String>> = OnceLock::new();
+static HAS_LYRICS_PROVIDER: AtomicBool = AtomicBool::new(false);
+/// What `custom_widget` last returned, and whether a refresh is already queued
+static WIDGET: Mutex<Option<String>> = Mutex::new(None);
+static WIDGET_QUEUED: AtomicBool = AtomicBool::new(false);
+
+/// Top-level code of a plugin file
+const LOAD_BUDGET: Duration = Duration::from_secs(1);
+/// `on_track_change`: nothing waits for it, but it shouldn't hold up the others
+const EVENT_BUDGET: Duration = Duration::from_secs(1);
+/// `custom_widget`, asked again every tick
+const WIDGET_BUDGET: Duration = Duration::from_millis(100);
+/// `on_key`: the UI waits this long for the answer. A key still queued after it
+/// goes to the built-in binding and never reaches plugins.
+const KEY_BUDGET: Duration = Duration::from_millis(50);
+/// `custom_lyrics_provider` may wait on the network
+const LYRICS_BUDGET: Duration = Duration::from_secs(15);
+
+enum Job {
+    /// The track, and plugins to leave out this time
+    TrackChange(TrackInfo, Vec<String>),
+    Key(String, Instant, mpsc::Sender<bool>),
+    Widget,
+    Lyrics(TrackInfo, mpsc::Sender<Option<(String, String)>>),
+}
+
+/// One `~/.config/vyom/plugins/*.lua` file. The script returns a table of hooks:
+///
+/// ```lua
+/// return {
+///   on_track_change = function(track) end,      -- track.name, .artist, .album, ...
+///   on_key = function(key) return false end,    -- true swallows the key
+///   custom_widget = function() return "..." end, -- one line in the music card
+///   custom_lyrics_provider = function(track) return nil end, -- LRC or plain text
+/// }
+/// ```
+struct Plugin {
+    name: String,
+    hooks: RegistryKey,
+}
+
+struct PluginHost {
+    lua: Lua,
+    plugins: Vec<Plugin>,
+    /// (plugin, hook) pairs that ran out of time once and aren't called again
+    stopped: RefCell<Vec<(String, &'static str)>>,
+}
+
+impl PluginHost {
+    fn new() -> Result<Self> {
+        let lua = Lua::new();
+
+        // `vyom.*` lets plugins drive the player (e.g. from on_key)
+        let api = lua.create_table()?;
+        api.set("play_pause", lua.create_function(|_, ()| {
+            let _ = crate::player::get_player().play_pause();
+            Ok(())
+        })?)?;
+        api.set("next", lua.create_function(|_, ()| {
+            let _ = crate::player::get_player().next();
+            Ok(())
+        })?)?;
+        api.set("prev", lua.create_function(|_, ()| {
+            let _ = crate::player::get_player().prev();
+            Ok(())
+        })?)?;
+        // For plugins that want to hold back on their own
+        api.set("focus_active", lua.create_function(|_, ()| Ok(crate::focus::is_active()))?)?;
+        lua.globals().set("vyom", api)?;
+
+        Ok(Self { lua, plugins: Vec::new(), stopped: RefCell::new(Vec::new()) })
+    }
+
+    fn load_file(&mut self, path: &PathBuf) -> Result<()> {
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("plugin").to_string();
+        let source = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let hooks: Table = self.limited(LOAD_BUDGET, || self.lua.load(&source).set_name(name.as_str()).eval())
+            .with_context(|| format!("{} must return a table of hooks", name))?;
+        let hooks = self.lua.create_registry_value(hooks)?;
+        self.plugins.push(Plugin { name, hooks });
+        Ok(())
+    }
+
+    /// Run `f`, stopping whatever Lua it runs once `budget` has passed
+    fn limited<T>(&self, budget: Duration, f: impl FnOnce() -> mlua::Result<T>) -> mlua::Result<T> {
+        let deadline = Instant::now() + budget;
+        self.lua.set_hook(HookTriggers::new().every_nth_instruction(1000), move |_, _| {
+            if Instant::now() > deadline {
+                return Err(mlua::Error::RuntimeError(format!("took longer than {:?}", budget)));
+            }
+            Ok(())
+        });
+        let result = f();
+        self.lua.remove_hook();
+        result
+    }
+
+    /// Call one plugin's hook within `budget`. A hook that runs out of time is
+    /// left out from then on, so it can't keep the others waiting every time.
+    fn call<T>(&self, plugin: &str, hook: &'static str, budget: Duration, f: impl FnOnce() -> mlua::Result<T>) -> Option<T> {
+        let started = Instant::now();
+        match self.limited(budget, f) {
+            Ok(value) => Some(value),
+            Err(_) if started.elapsed() > budget => {
+                self.stopped.borrow_mut().push((plugin.to_string(), hook));
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Every plugin's `hook` function, in load order (but the stopped ones)
+    fn hooks<'lua>(&'lua self, hook: &str) -> Vec<(&'lua str, Function<'lua>)> {
+        let stopped = self.stopped.borrow();
+        self.plugins.iter()
+            .filter(|p| !stopped.iter().any(|(name, h)| *name == p.name && *h == hook))
+            .filter_map(|p| {
+                let table: Table = self.lua.registry_value(&p.hooks).ok()?;
+                let func: Function = table.get(hook).ok()?;
+                Some((p.name.as_str(), func))
+            })
+            .collect()
+    }
+
+    fn track_table(&self, track: &TrackInfo) -> mlua::Result<Table<'_>> {
+        let t = self.lua.create_table()?;
+        t.set("name", track.name.as_str())?;
+        t.set("artist", track.artist.as_str())?;
+        t.set("album", track.album.as_str())?;
+        t.set("source", track.source.as_str())?;
+        t.set("duration_ms", track.duration_ms)?;
+        t.set("position_ms", track.position_ms)?;
+        t.set("state", match track.state {
+            PlayerState::Playing => "playing",
+            PlayerState::Paused => "paused",
+            PlayerState::Stopped => "stopped",
+        })?;
+        Ok(t)
+    }
+}
+
+fn plugins_dir() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".config").join("vyom").join("plugins"))
+}
+
+/// Start the plugin thread and load every plugin on it.
+/// Returns one message per plugin that failed.
+pub fn load() -> Vec<String> {
+    let (jobs, inbox) = mpsc::channel();
+    let (loaded, errors) = mpsc::channel();
+    let spawned = std::thread::Builder::new().name("plugins".to_string()).spawn(move || {
+        let mut host = match PluginHost::new() {
+            Ok(host) => host,
+            Err(e) => {
+                let _ = loaded.send(vec![format!("Lua init failed: {}", e)]);
+                return;
+            }
+        };
+        let mut failed = Vec::new();
+        if let Some(entries) = plugins_dir().and_then(|dir| fs::read_dir(dir).ok()) {
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "lua"))
+                .collect();
+            paths.sort();
+            for path in paths {
+                if let Err(e) = host.load_file(&path) {
+                    failed.push(format!("{:#}", e));
+                }
+            }
+        }
+        let _ = NAMES.set(host.plugins.iter().map(|p| p.name.clone()).collect());
+        HAS_LYRICS_PROVIDER.store(!host.hooks("custom_lyrics_provider").is_empty(), Ordering::Relaxed);
+        let _ = loaded.send(failed);
+        if host.plugins.is_empty() {
+            return;
+        }
+        for job in inbox {
+            host.run(job);
+        }
+    });
+    if let Err(e) = spawned {
+        return vec![format!("Couldn't start the plugin thread: {}", e)];
+    }
+    let errors = errors.recv().unwrap_or_default();
+    if NAMES.get().is_some_and(|names| !names.is_empty()) {
+        let _ = JOBS.set(jobs);
+    }
+    errors
+}
+
+impl PluginHost {
+    fn run(&self, job: Job) {
+        match job {
+            Job::TrackChange(track, skip) => {
+                for (name, func) in self.hooks("on_track_change") {
+                    if skip.iter().any(|s| s == name) {
+                        continue;
+                    }
+                    self.call(name, "on_track_change", EVENT_BUDGET, || func.call::<_, ()>(self.track_table(&track)?));
+                }
+            }
+            Job::Key(key, queued_at, reply) => {
+                // The UI has stopped waiting: the built-in binding already had it
+                if queued_at.elapsed() > KEY_BUDGET {
+                    return;
+                }
+                let handled = self.hooks("on_key").into_iter().any(|(name, func)| {
+                    self.call(name, "on_key", KEY_BUDGET, || func.call::<_, Option<bool>>(key.as_str())).flatten().unwrap_or(false)
+                });
+                let _ = reply.send(handled);
+            }
+            Job::Widget => {
+                WIDGET_QUEUED.store(false, Ordering::Relaxed);
+                let parts: Vec<String> = self.hooks("custom_widget").into_iter()
+                    .filter_map(|(name, func)| self.call(name, "custom_widget", WIDGET_BUDGET, || func.call::<_, Option<String>>(())).flatten())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                *WIDGET.lock().unwrap_or_else(|e| e.into_inner()) = Some(parts.join("  ·  ")).filter(|s| !s.is_empty());
+            }
+            Job::Lyrics(track, reply) => {
+                let found = self.hooks("custom_lyrics_provider").into_iter().find_map(|(name, func)| {
+                    let lyrics: Option<String> = self.call(name, "custom_lyrics_provider", LYRICS_BUDGET, || func.call(self.track_table(&track)?))?;
+                    lyrics.filter(|l| !l.trim().is_empty()).map(|l| (name.to_string(), l))
+                });
+                let _ = reply.send(found);
+            }
+        }
+    }
+}
+
+fn send(job: Job) -> bool {
+    JOBS.get().is_some_and(|jobs| jobs.send(job).is_ok())
+}
+
+/// `skip` names plugins to leave out this time
+pub fn on_track_change(track: &TrackInfo, skip: impl Fn(&str) -> bool) {
+    let Some(names) = NAMES.get() else { return };
+    let skip = names.iter().filter(|name| skip(name)).cloned().collect();
+    send(Job::TrackChange(track.clone(), skip));
+}
+
+/// True when a plugin handled the key and the built-in binding should be skipped.
+/// Waits at most a little over [`KEY_BUDGET`]: a busy plugin thread (say, a lyrics
+/// provider on the network) doesn't hold keys up.
+pub fn on_key(key: &str) -> bool {
+    let (reply, answer) = mpsc::channel();
+    if !send(Job::Key(key.to_string(), Instant::now(), reply)) {
+        return false;
+    }
+    answer.recv_timeout(KEY_BUDGET * 2).unwrap_or(false)
+}
+
+/// Text from every `custom_widget` hook, joined on one line: the last answer,
+/// while a fresh one is asked for in the background
+pub fn custom_widget() -> Option<String> {
+    if JOBS.get().is_some() && !WIDGET_QUEUED.swap(true, Ordering::Relaxed) && !send(Job::Widget) {
+        WIDGET_QUEUED.store(false, Ordering::Relaxed);
+    }
+    WIDGET.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn has_lyrics_provider() -> bool {
+    HAS_LYRICS_PROVIDER.load(Ordering::Relaxed)
+}
+
+/// First non-empty lyrics a plugin returns, with the plugin's name. Blocks until
+/// the plugin thread gets to it.
+pub fn fetch_lyrics(track: &TrackInfo) -> Option<(String, String)> {
+    let (reply, answer) = mpsc::channel();
+    if !send(Job::Lyrics(track.clone(), reply)) {
+        return None;
+    }
+    answer.recv().ok().flatten()
+}
@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::player::TrackInfo;
+use crate::spotify_api;
+
+// Podcast show notes 🎙️
+// Episodes have no lyrics, so the lyrics panel shows the episode description
+// instead: Spotify's own when the sp_dc cookie is set, else the show's RSS feed
+// found through the iTunes podcast directory. Notes that list timestamps
+// ("(12:34) Topic", "1:02:03 - Topic") give the episode its chapters.
+
+const ITUNES_SEARCH_URL: &str = "https://itunes.apple.com/search";
+
+#[derive(Debug, Deserialize)]
+struct ItunesResponse {
+    results: Vec<ItunesPodcast>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItunesPodcast {
+    #[serde(rename = "feedUrl")]
+    feed_url: Option<String>,
+}
+
+/// A chapter of a podcast episode
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub start_ms: u64,
+    pub title: String,
+}
+
+/// Spotify reports podcast episodes with an episode URI
+pub fn is_episode(track: &TrackInfo) -> bool {
+    track.id.as_deref().is_some_and(|id| id.starts_with("spotify:episode:"))
+}
+
+/// The episode's show notes as display lines, None when no source has them
+pub async fn fetch_show_notes(client: &Client, sp_dc: Option<&str>, track: &TrackInfo) -> Result<Option<Vec<String>>> {
+    if let (Some(sp_dc), Some(uri)) = (sp_dc, track.id.as_deref()) {
+        if let Ok(Some(description)) = spotify_api::fetch_episode_description(client, sp_dc, uri).await {
+            return Ok(Some(to_lines(&description)));
+        }
+    }
+
+    // Spotify names the show as the album
+    let notes = fetch_rss_description(client, &track.album, &track.name).await?;
+    Ok(notes.map(|html| to_lines(&strip_html(&html))))
+}
+
+/// Find the show's feed and the item titled like the episode
+async fn fetch_rss_description(client: &Client, show: &str, episode: &str) -> Result<Option<String>> {
+    let search: ItunesResponse = client.get(ITUNES_SEARCH_URL)
+        .query(&[("media", "podcast"), ("term", show), ("limit", "1")])
+        .send().await?
+        .error_for_status()?
+        .json().await?;
+    let Some(feed_url) = search.results.into_iter().find_map(|p| p.feed_url) else { return Ok(None) };
+
+    let feed = client.get(&feed_url).send().await?
+        .error_for_status()?
+        .text().await
+        .with_context(|| format!("Failed to read {}", feed_url))?;
+
+    let wanted = episode.trim().to_lowercase();
+    Ok(feed.split("<item").skip(1).find_map(|item| {
+        let title = tag_text(item, "title")?;
+        if strip_html(&title).trim().to_lowercase() != wanted {
+            return None;
+        }
+        // Longest first: content:encoded has the full notes, description often a teaser
+        ["content:encoded", "description", "itunes:summary"].iter()
+            .find_map(|tag| tag_text(item, tag).filter(|t| !t.trim().is_empty()))
+    }))
+}
+
+/// Inner text of the first `<tag>` in `xml`, unwrapping CDATA
+fn tag_text(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}", tag);
+    let start = xml.find(&open)?;
+    let body_start = start + xml[start..].find('>')? + 1;
+    let body_len = xml[body_start..].find(&format!("</{}>", tag))?;
+    let body = xml[body_start..body_start + body_len].trim();
+    let body = body.strip_prefix("<![CDATA[")
+        .and_then(|b| b.strip_suffix("]]>"))
+        .map(str::to_string)
+        .unwrap_or_else(|| decode_entities(body));
+    Some(body)
+}
+
+/// Show notes are HTML: keep paragraph and line breaks, drop every other tag
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else { break };
+        let tag = rest[start + 1..start + end].trim_start_matches('/').to_lowercase();
+        let name = tag.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or_default();
+        if matches!(name, "p" | "br" | "li" | "div") {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    decode_entities(&text)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Trimmed lines with runs of blank lines collapsed to one
+fn to_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Chapters from lines that start with a timestamp, like "(12:34) Topic",
+/// "[1:02:03] Topic" or "12:34 - Topic". A single one, or timestamps out of
+/// order (quotes, not a table of contents), give none.
+pub fn chapters(notes: &[String]) -> Vec<Chapter> {
+    let chapters: Vec<Chapter> = notes.iter().filter_map(|line| {
+        let line = line.trim_start_matches(['(', '[']);
+        let end = line.find(|c: char| !c.is_ascii_digit() && c != ':').unwrap_or(line.len());
+        let start_ms = timestamp_ms(&line[..end])?;
+        let title = line[end..].trim_start_matches([')', ']']).trim_start_matches([' ', '-', '–', '—', ':', '|', '·']).trim();
+        (!title.is_empty()).then(|| Chapter { start_ms, title: title.to_string() })
+    }).collect();
+    let ordered = chapters.windows(2).all(|pair| pair[0].start_ms < pair[1].start_ms);
+    if chapters.len() < 2 || !ordered {
+        return Vec::new();
+    }
+    chapters
+}
+
+/// "12:34" / "1:02:03" in ms
+fn timestamp_ms(text: &str) -> Option<u64> {
+    let parts: Vec<&str> = text.split(':').collect();
+    if !(2..=3).contains(&parts.len()) || parts[1..].iter().any(|p| p.len() != 2) {
+        return None;
+    }
+    parts.iter().try_fold(0u64, |secs, part| Some(secs * 60 + part.parse::<u64>().ok()?)).map(|secs| secs * 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn chapter(start_ms: u64, title: &str) -> Chapter {
+        Chapter { start_ms, title: title.into() }
+    }
+
+    #[test]
+    fn reads_the_timestamp_styles() {
+        let found = chapters(&notes(&[
+            "In this episode:",
+            "(00:00) Intro",
+            "[12:34] The interview",
+            "45:06 - Listener questions",
+            "1:02:03 – Wrap-up",
+            "Thanks for listening!",
+        ]));
+        assert_eq!(found, [
+            chapter(0, "Intro"),
+            chapter(754_000, "The interview"),
+            chapter(2_706_000, "Listener questions"),
+            chapter(3_723_000, "Wrap-up"),
+        ]);
+    }
+
+    #[test]
+    fn one_timestamp_is_not_chapters() {
+        assert!(chapters(&notes(&["Recorded live", "12:00 Intro"])).is_empty());
+    }
+
+    #[test]
+    fn out_of_order_is_not_chapters() {
+        assert!(chapters(&notes(&["10:00 Quote one", "02:00 Quote two"])).is_empty());
+    }
+
+    #[test]
+    fn skips_malformed_timestamps() {
+        let found = chapters(&notes(&["0:00 Start", "1:2 Broken", "12:345 Broken", "5:00", "10:00 End"]));
+        assert_eq!(found, [chapter(0, "Start"), chapter(600_000, "End")]);
+    }
+}
@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use crate::config::{MusicDuring, PomodoroConfig};
+
+// Pomodoro timer 🍅
+// Work and break intervals that drive playback: when one ends, the player is
+// paused or resumed so music only plays during work (or only during breaks).
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Work,
+    Break,
+    LongBreak,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pomodoro {
+    config: PomodoroConfig,
+    phase: Phase,
+    started_at: Instant,
+    /// Work intervals finished so far (every `long_break_every`th earns a long break)
+    completed: u32,
+}
+
+impl Pomodoro {
+    /// Begin with a work interval
+    pub fn start(config: &PomodoroConfig) -> Self {
+        Self { config: config.clone(), phase: Phase::Work, started_at: Instant::now(), completed: 0 }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn length(&self, phase: Phase) -> Duration {
+        let mins = match phase {
+            Phase::Work => self.config.work_mins,
+            Phase::Break => self.config.break_mins,
+            Phase::LongBreak => self.config.long_break_mins,
+        };
+        Duration::from_secs(mins.max(1) * 60)
+    }
+
+    /// Time left in the current interval
+    pub fn remaining(&self) -> Duration {
+        self.length(self.phase).saturating_sub(self.started_at.elapsed())
+    }
+
+    /// Move to the next interval once this one is over, returning the new phase
+    pub fn advance(&mut self) -> Option<Phase> {
+        if !self.remaining().is_zero() {
+            return None;
+        }
+        self.phase = match self.phase {
+            Phase::Work => {
+                self.completed += 1;
+                let every = self.config.long_break_every;
+                if every > 0 && self.completed.is_multiple_of(every) { Phase::LongBreak } else { Phase::Break }
+            }
+            Phase::Break | Phase::LongBreak => Phase::Work,
+        };
+        self.started_at = Instant::now();
+        Some(self.phase)
+    }
+
+    /// Whether music should be playing during `phase`
+    pub fn wants_music(&self, phase: Phase) -> bool {
+        (phase == Phase::Work) == (self.config.music_during == MusicDuring::Work)
+    }
+}
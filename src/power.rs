@@ -0,0 +1,127 @@
+#[cfg(target_os = "macos")]
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::time::Duration;
+
+use crate::config::{PollingConfig, PowerProfile};
+
+// Power profiles 🔋
+// One setting for how hard Vyom works: animation tick, player polling,
+// artwork size and decorative animations. The background tasks read the
+// active profile on every loop, so switching takes effect right away.
+// `auto` (the default) follows the power source: balanced when plugged in,
+// battery when not. Rates pinned under `[polling]` win over every profile.
+
+static ACTIVE: AtomicU8 = AtomicU8::new(PowerProfile::Balanced as u8);
+static AUTO: AtomicBool = AtomicBool::new(false);
+/// `[polling]` rates in ms, 0 = the profile's
+static TICK_MS: AtomicU64 = AtomicU64::new(0);
+static PLAYER_MS: AtomicU64 = AtomicU64::new(0);
+static PLAYERS_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Make `profile` the active one; `auto` checks the power source now
+pub fn set(profile: PowerProfile) {
+    AUTO.store(profile == PowerProfile::Auto, Ordering::Relaxed);
+    let profile = match profile {
+        PowerProfile::Auto if on_battery() => PowerProfile::Battery,
+        PowerProfile::Auto => PowerProfile::Balanced,
+        p => p,
+    };
+    ACTIVE.store(profile as u8, Ordering::Relaxed);
+}
+
+/// Pin the rates set in `[polling]`
+pub fn set_polling(config: &PollingConfig) {
+    TICK_MS.store(config.tick_ms.unwrap_or(0), Ordering::Relaxed);
+    PLAYER_MS.store(config.player_ms.unwrap_or(0), Ordering::Relaxed);
+    PLAYERS_MS.store(config.players_ms.unwrap_or(0), Ordering::Relaxed);
+}
+
+fn pinned(rate: &AtomicU64) -> Option<Duration> {
+    Some(rate.load(Ordering::Relaxed)).filter(|&ms| ms > 0).map(Duration::from_millis)
+}
+
+/// Still following the power source (not pinned by config or `b`)
+pub fn is_auto() -> bool {
+    AUTO.load(Ordering::Relaxed)
+}
+
+/// The profile in effect, never `auto`
+pub fn current() -> PowerProfile {
+    match ACTIVE.load(Ordering::Relaxed) {
+        p if p == PowerProfile::Performance as u8 => PowerProfile::Performance,
+        p if p == PowerProfile::Battery as u8 => PowerProfile::Battery,
+        _ => PowerProfile::Balanced,
+    }
+}
+
+impl PowerProfile {
+    /// Redraw/animation tick
+    pub fn tick_interval(self) -> Duration {
+        pinned(&TICK_MS).unwrap_or(Duration::from_millis(match self {
+            PowerProfile::Performance => 33,
+            PowerProfile::Auto | PowerProfile::Balanced => 50,
+            PowerProfile::Battery => 200,
+        }))
+    }
+
+    /// How often the player is asked what's playing
+    pub fn poll_interval(self) -> Duration {
+        pinned(&PLAYER_MS).unwrap_or(Duration::from_millis(match self {
+            PowerProfile::Auto | PowerProfile::Performance | PowerProfile::Balanced => 250,
+            PowerProfile::Battery => 1000,
+        }))
+    }
+
+    /// How often the list of running players is refreshed
+    pub fn players_interval(self) -> Duration {
+        pinned(&PLAYERS_MS).unwrap_or(Duration::from_secs(match self {
+            PowerProfile::Auto | PowerProfile::Performance | PowerProfile::Balanced => 1,
+            PowerProfile::Battery => 5,
+        }))
+    }
+
+    /// Fades, slides and crossfades are skipped
+    pub fn reduced_motion(self) -> bool {
+        self == PowerProfile::Battery
+    }
+
+    /// Artwork is downloaded and scaled at 300px at most
+    pub fn small_artwork(self) -> bool {
+        self == PowerProfile::Battery
+    }
+
+    /// `b` cycles through the profiles
+    pub fn next(self) -> Self {
+        match self {
+            PowerProfile::Performance => PowerProfile::Balanced,
+            PowerProfile::Auto | PowerProfile::Balanced => PowerProfile::Battery,
+            PowerProfile::Battery => PowerProfile::Performance,
+        }
+    }
+}
+
+// --- macOS: power source from IOPS via `pmset` 🍎 ---
+
+/// Running off the battery (unplugged laptop)
+#[cfg(target_os = "macos")]
+pub fn on_battery() -> bool {
+    // "Now drawing from 'Battery Power'" or "'AC Power'"
+    Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).contains("'Battery Power'"))
+        .unwrap_or(false)
+}
+
+// --- Linux: sysfs power supplies 🐧 ---
+
+#[cfg(not(target_os = "macos"))]
+pub fn on_battery() -> bool {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else { return false };
+    entries.flatten().any(|e| {
+        let path = e.path();
+        let read = |name: &str| std::fs::read_to_string(path.join(name)).unwrap_or_default();
+        read("type").trim() == "Battery" && read("status").trim() == "Discharging"
+    })
+}
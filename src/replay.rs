@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::app::LyricsState;
+use crate::player::TrackInfo;
+use crate::AppEvent;
+
+// Record and replay 🎞️
+// `--record session.json` writes the track updates, ticks and lyrics a real
+// session went through; `--replay session.json` feeds them back through the
+// app on the same schedule, with no player behind it, then quits and prints
+// how long the frames took. UI changes can be checked against the same
+// session every time, and profiled without Spotify running.
+//
+// A replay runs offline with HOME pointed at a fresh temporary directory, so
+// it doesn't write history, scrobbles or caches, load plugins or find a
+// running session to attach to. The config is still the real one.
+
+/// One recorded event, `at_ms` after the session started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub event: Recorded,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Recorded {
+    Track { track: Option<TrackInfo> },
+    Tick,
+    Lyrics { id: String, lyrics: LyricsState },
+}
+
+impl Recorded {
+    fn into_event(self) -> AppEvent {
+        match self {
+            Recorded::Track { track } => AppEvent::TrackUpdate(track),
+            Recorded::Tick => AppEvent::Tick,
+            Recorded::Lyrics { id, lyrics } => AppEvent::LyricsUpdate(id, lyrics),
+        }
+    }
+}
+
+pub fn load(path: &str) -> Result<Vec<Entry>> {
+    let content = fs::read_to_string(path).with_context(|| format!("Can't read {}", path))?;
+    let mut entries: Vec<Entry> = serde_json::from_str(&content).with_context(|| format!("{} isn't a recorded session", path))?;
+    entries.sort_by_key(|entry| entry.at_ms);
+    Ok(entries)
+}
+
+/// Point HOME at a new empty directory for the rest of the process
+pub fn sandbox() -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("vyom-replay-{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    std::env::set_var("HOME", &dir);
+    Ok(dir)
+}
+
+/// Send the entries on their schedule, then `ReplayFinished`
+pub fn spawn(entries: Vec<Entry>, tx: mpsc::Sender<AppEvent>) {
+    tokio::spawn(async move {
+        let started = tokio::time::Instant::now();
+        for entry in entries {
+            tokio::time::sleep_until(started + Duration::from_millis(entry.at_ms)).await;
+            if tx.send(entry.event.into_event()).await.is_err() {
+                return;
+            }
+        }
+        let _ = tx.send(AppEvent::ReplayFinished).await;
+    });
+}
+
+/// Draw times over a replay
+#[derive(Debug, Default)]
+pub struct FrameTimes {
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl FrameTimes {
+    pub fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    /// One line for after the terminal is back
+    pub fn summary(&self) -> String {
+        let average = self.total.checked_div(self.count as u32).unwrap_or_default();
+        format!(
+            "Replayed: {} frames, {:.2} ms average, {:.2} ms slowest",
+            self.count,
+            average.as_secs_f64() * 1000.0,
+            self.max.as_secs_f64() * 1000.0,
+        )
+    }
+}
+
+/// Collects what `--record` saves
+pub struct Recorder {
+    path: PathBuf,
+    started: Instant,
+    entries: Vec<Entry>,
+}
+
+impl Recorder {
+    pub fn new(path: &Path) -> Self {
+        Self { path: path.to_path_buf(), started: Instant::now(), entries: Vec::new() }
+    }
+
+    pub fn record(&mut self, event: &AppEvent) {
+        let event = match event {
+            AppEvent::TrackUpdate(track) => Recorded::Track { track: track.clone() },
+            AppEvent::Tick => Recorded::Tick,
+            AppEvent::LyricsUpdate(id, lyrics) => Recorded::Lyrics { id: id.clone(), lyrics: lyrics.clone() },
+            _ => return,
+        };
+        self.entries.push(Entry { at_ms: self.started.elapsed().as_millis() as u64, event });
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let json = serde_json::to_string(&self.entries)?;
+        fs::write(&self.path, json).with_context(|| format!("Can't write {}", self.path.display()))
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::{Context, Result};
+use image::{Rgb, RgbImage};
+use ratatui::style::Color;
+
+use crate::config::expand_home;
+use crate::history::{self, Listen, Summary};
+use crate::theme;
+
+// Weekly report 🎁
+// `vyom report --week` sums up the last seven days of the listening history,
+// Wrapped-style, as text, markdown or JSON, and can draw the daily minutes as
+// a PNG card for sharing.
+
+const DAYS: u64 = 7;
+const TOP: usize = 5;
+
+/// Minutes listened per local day, oldest first, with each day's midnight
+fn per_day(listens: &[Listen]) -> Vec<(u64, u64)> {
+    (0..DAYS).rev().map(|back| {
+        let (start, end) = (history::local_midnight(back), history::local_midnight(back.saturating_sub(1)));
+        let end = if back == 0 { u64::MAX } else { end };
+        let ms: u64 = listens.iter()
+            .filter(|l| l.played_at >= start && l.played_at < end)
+            .map(|l| l.listened_ms)
+            .sum();
+        (start, ms / 60_000)
+    }).collect()
+}
+
+pub fn run_cli(args: &[String]) -> Result<()> {
+    if !args.iter().any(|a| a == "--week") {
+        anyhow::bail!("Usage: vyom report --week [--format text|markdown|json] [--png card.png]");
+    }
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let format = flag("--format").map(String::as_str).unwrap_or("text");
+
+    let since = history::local_midnight(DAYS - 1);
+    let listens = history::load_since(since);
+    let summary = Summary::of(&listens);
+    let days = per_day(&listens);
+    let period = format!("{} – {}", history::fmt_date(since), history::fmt_date(history::unix_now()));
+
+    match format {
+        "text" => print!("{}", text(&summary, &days, &period)),
+        "markdown" | "md" => print!("{}", markdown(&summary, &days, &period)),
+        "json" => println!("{}", serde_json::to_string_pretty(&json(&summary, &days, since))?),
+        other => anyhow::bail!("Unknown format `{}` (text, markdown or json)", other),
+    }
+
+    if let Some(path) = flag("--png") {
+        let path = expand_home(path);
+        draw_card(&days).save(&path).with_context(|| format!("Failed to write {}", path.display()))?;
+        eprintln!("Card saved to {}", path.display());
+    }
+    Ok(())
+}
+
+fn plays(n: usize) -> String {
+    if n == 1 { "1 play".to_string() } else { format!("{} plays", n) }
+}
+
+fn busiest(days: &[(u64, u64)]) -> Option<(u64, u64)> {
+    days.iter().copied().filter(|(_, mins)| *mins > 0).max_by_key(|(_, mins)| *mins)
+}
+
+fn text(summary: &Summary, days: &[(u64, u64)], period: &str) -> String {
+    let mut out = format!("Your week in Vyom ({})\n\n", period);
+    out += &format!("  {} listened · {} · {} tracks\n", history::fmt_duration(summary.listened_ms), plays(summary.listens), summary.distinct_tracks);
+    if let Some((day, mins)) = busiest(days) {
+        out += &format!("  Busiest day: {} ({} min)\n", history::fmt_date(day), mins);
+    }
+    out += "\n  Top artists\n";
+    for (i, (artist, plays)) in summary.top_artists.iter().take(TOP).enumerate() {
+        out += &format!("   {}. {} ({})\n", i + 1, artist, self::plays(*plays));
+    }
+    out += "\n  Top tracks\n";
+    for (i, (title, artist, plays)) in summary.top_tracks.iter().take(TOP).enumerate() {
+        out += &format!("   {}. {} · {} ({})\n", i + 1, title, artist, self::plays(*plays));
+    }
+    let max = days.iter().map(|(_, m)| *m).max().unwrap_or(0).max(1);
+    out += "\n  Minutes per day\n";
+    for (day, mins) in days {
+        let bar = "█".repeat((mins * 30 / max) as usize);
+        out += &format!("   {} {:>4} {}\n", history::fmt_date(*day), mins, bar);
+    }
+    out
+}
+
+fn markdown(summary: &Summary, days: &[(u64, u64)], period: &str) -> String {
+    let mut out = format!("## Your week in Vyom\n\n*{}*\n\n", period);
+    out += &format!("**{}** listened · **{}** plays · **{}** tracks\n\n", history::fmt_duration(summary.listened_ms), summary.listens, summary.distinct_tracks);
+    if let Some((day, mins)) = busiest(days) {
+        out += &format!("Busiest day: {} ({} min)\n\n", history::fmt_date(day), mins);
+    }
+    out += "### Top artists\n\n";
+    for (i, (artist, plays)) in summary.top_artists.iter().take(TOP).enumerate() {
+        out += &format!("{}. **{}** ({})\n", i + 1, artist, self::plays(*plays));
+    }
+    out += "\n### Top tracks\n\n";
+    for (i, (title, artist, plays)) in summary.top_tracks.iter().take(TOP).enumerate() {
+        out += &format!("{}. **{}** — {} ({})\n", i + 1, title, artist, self::plays(*plays));
+    }
+    out += "\n| Day | Minutes |\n|---|---|\n";
+    for (day, mins) in days {
+        out += &format!("| {} | {} |\n", history::fmt_date(*day), mins);
+    }
+    out
+}
+
+fn json(summary: &Summary, days: &[(u64, u64)], since: u64) -> serde_json::Value {
+    serde_json::json!({
+        "since": history::fmt_date(since),
+        "listened_ms": summary.listened_ms,
+        "plays": summary.listens,
+        "tracks": summary.distinct_tracks,
+        "top_artists": summary.top_artists.iter().take(TOP)
+            .map(|(artist, plays)| serde_json::json!({ "artist": artist, "plays": plays }))
+            .collect::<Vec<_>>(),
+        "top_tracks": summary.top_tracks.iter().take(TOP)
+            .map(|(title, artist, plays)| serde_json::json!({ "title": title, "artist": artist, "plays": plays }))
+            .collect::<Vec<_>>(),
+        "minutes_per_day": days.iter()
+            .map(|(day, mins)| serde_json::json!({ "date": history::fmt_date(*day), "minutes": mins }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+// --- PNG card: the week's minutes as bars in the current theme's colors ---
+// (no font rasterizer in the dependencies, so the numbers stay in the text report)
+
+fn rgb(color: Color, fallback: [u8; 3]) -> Rgb<u8> {
+    match color {
+        Color::Rgb(r, g, b) => Rgb([r, g, b]),
+        _ => Rgb(fallback),
+    }
+}
+
+fn draw_card(days: &[(u64, u64)]) -> RgbImage {
+    const WIDTH: u32 = 720;
+    const HEIGHT: u32 = 360;
+    const MARGIN: u32 = 40;
+    let theme = theme::load_current_theme();
+    let background = rgb(theme.base, [30, 30, 46]);
+    let (from, to) = (rgb(theme.green, [166, 227, 161]), rgb(theme.magenta, [203, 166, 247]));
+    let track = rgb(theme.surface, [49, 50, 68]);
+
+    let mut img = RgbImage::from_pixel(WIDTH, HEIGHT, background);
+    let max = days.iter().map(|(_, m)| *m).max().unwrap_or(0).max(1);
+    let slot = (WIDTH - 2 * MARGIN) / days.len().max(1) as u32;
+    let bar_width = slot * 2 / 3;
+    let full_height = HEIGHT - 2 * MARGIN;
+
+    for (i, (_, mins)) in days.iter().enumerate() {
+        let x0 = MARGIN + i as u32 * slot + (slot - bar_width) / 2;
+        let height = (*mins * full_height as u64 / max) as u32;
+        for y in MARGIN..HEIGHT - MARGIN {
+            let filled = y >= HEIGHT - MARGIN - height;
+            // Bars fade from green at the bottom to magenta at the top
+            let t = (HEIGHT - MARGIN - y) as f32 / full_height as f32;
+            let color = if filled {
+                Rgb(std::array::from_fn(|c| (from[c] as f32 + (to[c] as f32 - from[c] as f32) * t) as u8))
+            } else {
+                track
+            };
+            for x in x0..x0 + bar_width {
+                img.put_pixel(x, y, color);
+            }
+        }
+    }
+    img
+}
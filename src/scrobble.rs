@@ -0,0 +1,381 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::config::Config;
+use crate::history::unix_now;
+use crate::lastfm;
+use crate::listenbrainz;
+use crate::player::{PlayerState, TrackInfo};
+
+// Scrobbling 📡
+// Plays go to Last.fm and ListenBrainz the way every scrobbler sends them: "now
+// playing" once a track has been on for a few seconds, then a scrobble when it
+// has played for half its length or four minutes, whichever comes first.
+// Tracks under 30 seconds never count. Scrobbles that can't go out (offline,
+// service down) wait in ~/.cache/vyom/scrobbles.json for the next one that does.
+
+/// Shorter tracks aren't scrobbled at all
+const MIN_TRACK_MS: u64 = 30_000;
+/// A track counts after half its length, or this much for long ones
+const MAX_WAIT_MS: u64 = 4 * 60 * 1000;
+/// Skipping straight past a track doesn't announce it
+const NOW_PLAYING_AFTER_MS: u64 = 5_000;
+/// Last.fm takes at most 50 scrobbles a request
+const BATCH: usize = 50;
+
+/// One submission at a time reads and writes the queue file
+static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+/// Scrobbles waiting in the queue (for whichever service has more), for the header
+static QUEUED: AtomicUsize = AtomicUsize::new(0);
+
+/// A play as the services want it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scrobble {
+    pub artist: String,
+    pub title: String,
+    pub album: String,
+    pub duration_secs: u64,
+    /// Unix seconds when the track started
+    pub started_at: u64,
+    /// Spotify or Music
+    pub player: String,
+}
+
+/// What `Scrobbler::update` wants sent
+#[derive(Debug, Clone)]
+pub enum Submission {
+    NowPlaying(Scrobble),
+    Scrobble(Scrobble),
+}
+
+/// Scrobbles still to go out, per service (one may be down while the other isn't)
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Queue {
+    #[serde(default)]
+    lastfm: Vec<Scrobble>,
+    #[serde(default)]
+    listenbrainz: Vec<Scrobble>,
+}
+
+fn queue_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache").join("vyom").join("scrobbles.json"))
+}
+
+/// Load the queue, change it, save it, under the lock
+fn edit_queue<T>(edit: impl FnOnce(&mut Queue) -> T) -> T {
+    let _lock = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = queue_path();
+    let mut queue: Queue = path.as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let result = edit(&mut queue);
+    QUEUED.store(queue.lastfm.len().max(queue.listenbrainz.len()), Ordering::Relaxed);
+    if let Some(path) = path {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(&queue) {
+            let _ = fs::write(path, json);
+        }
+    }
+    result
+}
+
+/// Scrobbles still waiting to go out, as of the last look at the queue
+pub fn queued() -> usize {
+    QUEUED.load(Ordering::Relaxed)
+}
+
+/// Count what earlier sessions left in the queue
+pub fn count_queued() {
+    edit_queue(|_| ());
+}
+
+/// The track on right now, how long it has really played and what went out for it
+#[derive(Debug)]
+struct Playing {
+    scrobble: Scrobble,
+    duration_ms: u64,
+    position_ms: u64,
+    listened_ms: u64,
+    last_seen: Instant,
+    announced: bool,
+    scrobbled: bool,
+}
+
+impl Playing {
+    fn new(track: &TrackInfo) -> Self {
+        Self {
+            scrobble: Scrobble {
+                artist: track.artist.clone(),
+                title: track.name.clone(),
+                album: track.album.clone(),
+                duration_secs: track.duration_ms / 1000,
+                started_at: unix_now(),
+                player: track.source.clone(),
+            },
+            duration_ms: track.duration_ms,
+            position_ms: track.position_ms,
+            listened_ms: 0,
+            last_seen: Instant::now(),
+            announced: false,
+            scrobbled: false,
+        }
+    }
+
+    fn is_track(&self, track: &TrackInfo) -> bool {
+        self.scrobble.title == track.name && self.scrobble.artist == track.artist
+    }
+
+    /// Play time it takes to count; streams without a length wait the full four minutes
+    fn threshold_ms(&self) -> u64 {
+        if self.duration_ms == 0 { MAX_WAIT_MS } else { (self.duration_ms / 2).min(MAX_WAIT_MS) }
+    }
+}
+
+/// Follows the TrackUpdates and says when to send what
+#[derive(Debug, Default)]
+pub struct Scrobbler {
+    playing: Option<Playing>,
+}
+
+impl Scrobbler {
+    pub fn update(&mut self, track: &TrackInfo) -> Option<Submission> {
+        // Back at the start after it was scrobbled: on repeat, a new play
+        let replayed = self.playing.as_ref()
+            .is_some_and(|p| p.scrobbled && track.position_ms < NOW_PLAYING_AFTER_MS && p.position_ms > p.threshold_ms());
+        let playing = match &mut self.playing {
+            Some(playing) if playing.is_track(track) && !replayed => playing,
+            _ => self.playing.insert(Playing::new(track)),
+        };
+        if track.state == PlayerState::Playing {
+            playing.listened_ms += playing.last_seen.elapsed().as_millis() as u64;
+        }
+        playing.last_seen = Instant::now();
+        playing.position_ms = track.position_ms;
+
+        if playing.duration_ms > 0 && playing.duration_ms < MIN_TRACK_MS {
+            return None;
+        }
+        if !playing.scrobbled && playing.listened_ms >= playing.threshold_ms() {
+            playing.scrobbled = true;
+            return Some(Submission::Scrobble(playing.scrobble.clone()));
+        }
+        if !playing.announced && playing.listened_ms >= NOW_PLAYING_AFTER_MS {
+            playing.announced = true;
+            return Some(Submission::NowPlaying(playing.scrobble.clone()));
+        }
+        None
+    }
+}
+
+/// The accounts to scrobble to
+#[derive(Debug, Clone)]
+pub struct Services {
+    lastfm: Option<lastfm::Account>,
+    listenbrainz: Option<String>,
+}
+
+impl Services {
+    /// None when neither account is set up (or both have `scrobble = false`)
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let services = Self {
+            lastfm: lastfm::Account::from_config(&config.lastfm).filter(|_| config.lastfm.scrobble),
+            listenbrainz: config.listenbrainz.token.clone().filter(|_| config.listenbrainz.scrobble),
+        };
+        (services.lastfm.is_some() || services.listenbrainz.is_some()).then_some(services)
+    }
+}
+
+/// Send a submission. Scrobbles join the queue first and the whole queue goes
+/// out; with `offline` they only join it.
+pub async fn submit(client: &Client, services: &Services, submission: Submission, offline: bool) {
+    match submission {
+        Submission::NowPlaying(scrobble) if !offline => {
+            // Only good while it plays: nothing to keep if it fails
+            if let Some(account) = &services.lastfm {
+                let _ = lastfm::now_playing(client, account, &scrobble).await;
+            }
+            if let Some(token) = &services.listenbrainz {
+                let _ = listenbrainz::submit_listens(client, token, "playing_now", &[scrobble]).await;
+            }
+        },
+        Submission::NowPlaying(_) => {},
+        Submission::Scrobble(scrobble) => {
+            edit_queue(|queue| {
+                if services.lastfm.is_some() {
+                    queue.lastfm.push(scrobble.clone());
+                }
+                if services.listenbrainz.is_some() {
+                    queue.listenbrainz.push(scrobble);
+                }
+            });
+            if !offline {
+                flush(client, services).await;
+            }
+        },
+    }
+}
+
+/// Unsent scrobbles go back in front of any queued while they were out
+fn put_back(queued: &mut Vec<Scrobble>, unsent: &[Scrobble]) {
+    queued.splice(0..0, unsent.iter().cloned());
+}
+
+/// Send everything queued, oldest first. What doesn't make it stays queued.
+pub async fn flush(client: &Client, services: &Services) {
+    if let Some(account) = &services.lastfm {
+        let pending = edit_queue(|queue| std::mem::take(&mut queue.lastfm));
+        let mut sent = 0;
+        for batch in pending.chunks(BATCH) {
+            if lastfm::scrobble(client, account, batch).await.is_err() {
+                break;
+            }
+            sent += batch.len();
+        }
+        edit_queue(|queue| put_back(&mut queue.lastfm, &pending[sent..]));
+    }
+    if let Some(token) = &services.listenbrainz {
+        let pending = edit_queue(|queue| std::mem::take(&mut queue.listenbrainz));
+        let mut sent = 0;
+        for batch in pending.chunks(BATCH) {
+            let listen_type = if batch.len() == 1 { "single" } else { "import" };
+            if listenbrainz::submit_listens(client, token, listen_type, batch).await.is_err() {
+                break;
+            }
+            sent += batch.len();
+        }
+        edit_queue(|queue| put_back(&mut queue.listenbrainz, &pending[sent..]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn track(name: &str, duration_ms: u64, position_ms: u64) -> TrackInfo {
+        TrackInfo {
+            name: name.into(),
+            artist: "Artist".into(),
+            album: "Album".into(),
+            artwork_url: None,
+            duration_ms,
+            position_ms,
+            state: PlayerState::Playing,
+            source: "Music".into(),
+            id: None,
+            bpm: None,
+            volume: None,
+            context: None,
+            rating: None,
+            play_count: None,
+            last_played: None,
+            favorite: None,
+        }
+    }
+
+    /// Pretend the last update was `ms` ago
+    fn wait(scrobbler: &mut Scrobbler, ms: u64) {
+        let playing = scrobbler.playing.as_mut().unwrap();
+        playing.last_seen = Instant::now() - Duration::from_millis(ms);
+    }
+
+    fn scrobble(artist: &str, title: &str) -> Scrobble {
+        Scrobble {
+            artist: artist.into(),
+            title: title.into(),
+            album: String::new(),
+            duration_secs: 0,
+            started_at: 0,
+            player: String::new(),
+        }
+    }
+
+    #[test]
+    fn announces_then_scrobbles_at_half() {
+        let mut scrobbler = Scrobbler::default();
+        let song = track("Song", 200_000, 0);
+        assert!(scrobbler.update(&song).is_none());
+        wait(&mut scrobbler, 6_000);
+        assert!(matches!(scrobbler.update(&song), Some(Submission::NowPlaying(_))));
+        wait(&mut scrobbler, 90_000);
+        assert!(scrobbler.update(&song).is_none());
+        wait(&mut scrobbler, 5_000);
+        assert!(matches!(scrobbler.update(&song), Some(Submission::Scrobble(_))));
+        wait(&mut scrobbler, 50_000);
+        assert!(scrobbler.update(&song).is_none());
+    }
+
+    #[test]
+    fn long_tracks_count_after_four_minutes() {
+        let mut scrobbler = Scrobbler::default();
+        let song = track("Long", 20 * 60 * 1000, 0);
+        scrobbler.update(&song);
+        wait(&mut scrobbler, 6_000);
+        scrobbler.update(&song);
+        wait(&mut scrobbler, MAX_WAIT_MS - 6_000);
+        assert!(matches!(scrobbler.update(&song), Some(Submission::Scrobble(_))));
+    }
+
+    #[test]
+    fn paused_time_does_not_count() {
+        let mut scrobbler = Scrobbler::default();
+        let mut song = track("Song", 200_000, 0);
+        song.state = PlayerState::Paused;
+        scrobbler.update(&song);
+        wait(&mut scrobbler, 150_000);
+        assert!(scrobbler.update(&song).is_none());
+    }
+
+    #[test]
+    fn short_tracks_never_count() {
+        let mut scrobbler = Scrobbler::default();
+        let jingle = track("Jingle", 20_000, 0);
+        scrobbler.update(&jingle);
+        wait(&mut scrobbler, 20_000);
+        assert!(scrobbler.update(&jingle).is_none());
+    }
+
+    #[test]
+    fn repeat_is_a_new_play() {
+        let mut scrobbler = Scrobbler::default();
+        scrobbler.update(&track("Song", 200_000, 0));
+        wait(&mut scrobbler, 110_000);
+        assert!(matches!(scrobbler.update(&track("Song", 200_000, 110_000)), Some(Submission::Scrobble(_))));
+        // Back at the start: counted again from zero
+        wait(&mut scrobbler, 90_000);
+        assert!(scrobbler.update(&track("Song", 200_000, 1_000)).is_none());
+        wait(&mut scrobbler, 6_000);
+        assert!(matches!(scrobbler.update(&track("Song", 200_000, 7_000)), Some(Submission::NowPlaying(_))));
+        wait(&mut scrobbler, 100_000);
+        assert!(matches!(scrobbler.update(&track("Song", 200_000, 107_000)), Some(Submission::Scrobble(_))));
+    }
+
+    #[test]
+    fn seeking_back_before_the_scrobble_is_the_same_play() {
+        let mut scrobbler = Scrobbler::default();
+        scrobbler.update(&track("Song", 200_000, 0));
+        wait(&mut scrobbler, 60_000);
+        scrobbler.update(&track("Song", 200_000, 60_000));
+        wait(&mut scrobbler, 1_000);
+        scrobbler.update(&track("Song", 200_000, 1_000));
+        wait(&mut scrobbler, 40_000);
+        assert!(matches!(scrobbler.update(&track("Song", 200_000, 41_000)), Some(Submission::Scrobble(_))));
+    }
+
+    #[test]
+    fn unsent_go_back_in_front() {
+        let mut queued = vec![scrobble("C", "3")];
+        put_back(&mut queued, &[scrobble("A", "1"), scrobble("B", "2")]);
+        let titles: Vec<_> = queued.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["1", "2", "3"]);
+    }
+}
@@ -0,0 +1,457 @@
+#[cfg(not(unix))]
+use anyhow::Result;
+use ratatui::backend::{Backend, ClearType, CrosstermBackend, WindowSize};
+use ratatui::buffer::Cell;
+use ratatui::layout::{Position, Size};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// Detach/attach 🔌
+// `d` hands the terminal back while this process keeps polling, fetching and
+// caching. `vyom attach` (from any terminal) connects over a Unix socket,
+// forwards its input events and size, and prints the frames we draw.
+// `vyom daemon` starts out that way, with no terminal of its own. Several
+// clients can be attached at once (tmux panes, say): they all get the same
+// frames, drawn at the smallest one's size like tmux does.
+
+fn socket_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache").join("vyom").join("attach.sock"))
+}
+
+/// A connected `vyom attach` client
+pub trait Client: Write + Send {
+    /// Hang up so the client restores its terminal and exits
+    fn hang_up(&self);
+}
+
+/// An attached `vyom attach` and its terminal size
+struct Remote {
+    id: u64,
+    client: Box<dyn Client>,
+    size: Size,
+}
+
+/// Where frames go
+enum Target {
+    Local(io::Stdout),
+    /// Never empty: the last client leaving makes it Detached
+    Remote(Vec<Remote>),
+    /// Detached with nobody attached: frames are dropped
+    Detached,
+}
+
+/// Writer shared by the crossterm backend and `SessionBackend`, which swaps its target
+#[derive(Clone)]
+pub struct Output(Arc<Mutex<Target>>);
+
+impl Output {
+    fn target(&self) -> MutexGuard<'_, Target> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut target = self.target();
+        match &mut *target {
+            Target::Local(out) => out.write(buf),
+            Target::Remote(remotes) => {
+                // A client that went away is dropped, not an app error
+                remotes.retain_mut(|remote| remote.client.write_all(buf).is_ok());
+                if remotes.is_empty() {
+                    *target = Target::Detached;
+                }
+                Ok(buf.len())
+            }
+            Target::Detached => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut target = self.target();
+        match &mut *target {
+            Target::Local(out) => out.flush(),
+            Target::Remote(remotes) => {
+                remotes.retain_mut(|remote| remote.client.flush().is_ok());
+                if remotes.is_empty() {
+                    *target = Target::Detached;
+                }
+                Ok(())
+            }
+            Target::Detached => Ok(()),
+        }
+    }
+}
+
+/// Crossterm backend that can move between the local terminal and attached
+/// clients. Once detached, the size comes from the clients (or the last one
+/// seen), never from the terminal we left.
+pub struct SessionBackend {
+    inner: CrosstermBackend<Output>,
+    output: Output,
+    size_override: Option<Size>,
+}
+
+impl SessionBackend {
+    pub fn new(stdout: io::Stdout) -> Self {
+        let output = Output(Arc::new(Mutex::new(Target::Local(stdout))));
+        Self { inner: CrosstermBackend::new(output.clone()), output, size_override: None }
+    }
+
+    /// No terminal at all (`vyom daemon`): drawing waits for a client
+    pub fn detached() -> Self {
+        let output = Output(Arc::new(Mutex::new(Target::Detached)));
+        Self { inner: CrosstermBackend::new(output.clone()), output, size_override: Some(Size::new(80, 24)) }
+    }
+
+    /// Size to draw at when it isn't the local terminal's
+    fn fixed_size(&self) -> Option<Size> {
+        match &*self.output.target() {
+            Target::Remote(remotes) => smallest(remotes),
+            _ => self.size_override,
+        }
+    }
+
+    pub fn is_local(&self) -> bool {
+        matches!(*self.output.target(), Target::Local(_))
+    }
+
+    /// Nothing to draw to (skip rendering)
+    pub fn is_detached(&self) -> bool {
+        matches!(*self.output.target(), Target::Detached)
+    }
+
+    /// Drop the local terminal or every attached client
+    pub fn detach(&mut self) {
+        self.size_override = self.size().ok();
+        let mut target = self.output.target();
+        if let Target::Remote(remotes) = &*target {
+            for remote in remotes {
+                remote.client.hang_up();
+            }
+        }
+        *target = Target::Detached;
+    }
+
+    /// Hang up on one attached client; the others stay
+    pub fn detach_client(&mut self, id: u64) {
+        let mut target = self.output.target();
+        let Target::Remote(remotes) = &mut *target else { return };
+        remotes.retain(|remote| {
+            if remote.id == id {
+                remote.client.hang_up();
+            }
+            remote.id != id
+        });
+        if remotes.is_empty() {
+            *target = Target::Detached;
+        }
+    }
+
+    /// Draw to `client` as well (instead of the local terminal, if that's where we were)
+    pub fn attach(&mut self, id: u64, client: Box<dyn Client>, size: Size) {
+        if !matches!(*self.output.target(), Target::Remote(_)) {
+            self.detach();
+            *self.output.target() = Target::Remote(Vec::new());
+        }
+        let mut target = self.output.target();
+        if let Target::Remote(remotes) = &mut *target {
+            remotes.push(Remote { id, client, size });
+        }
+    }
+
+    /// An attached client was resized
+    pub fn set_remote_size(&mut self, id: u64, size: Size) {
+        let mut target = self.output.target();
+        if let Target::Remote(remotes) = &mut *target {
+            if let Some(remote) = remotes.iter_mut().find(|remote| remote.id == id) {
+                remote.size = size;
+            }
+        }
+    }
+}
+
+/// Frames have to fit every client: the narrowest width and shortest height
+fn smallest(remotes: &[Remote]) -> Option<Size> {
+    let width = remotes.iter().map(|remote| remote.size.width).min()?;
+    let height = remotes.iter().map(|remote| remote.size.height).min()?;
+    Some(Size::new(width, height))
+}
+
+/// Raw escape sequences (leaving the alternate screen on exit) go wherever frames do
+impl Write for SessionBackend {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(&mut self.inner)
+    }
+}
+
+impl Backend for SessionBackend {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        self.inner.draw(content)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.inner.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.inner.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        self.inner.get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+        self.inner.set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.inner.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+        self.inner.clear_region(clear_type)
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        match self.fixed_size() {
+            Some(size) => Ok(size),
+            None => self.inner.size(),
+        }
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        match self.fixed_size() {
+            Some(size) => Ok(WindowSize { columns_rows: size, pixels: Size::default() }),
+            None => self.inner.window_size(),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Backend::flush(&mut self.inner)
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use anyhow::{Context, Result};
+    use crossterm::event::Event;
+    use std::io::{self, BufRead, BufReader, Read, Write};
+    use std::net::Shutdown;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::signal::unix::{signal, SignalKind};
+    use tokio::sync::mpsc;
+
+    use super::{socket_path, Client};
+    use crate::AppEvent;
+
+    impl Client for UnixStream {
+        fn hang_up(&self) {
+            let _ = self.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// This process bound the socket (and removes it on the way out)
+    static OWNED: AtomicBool = AtomicBool::new(false);
+    /// Tells the attached clients apart
+    static NEXT_CLIENT: AtomicU64 = AtomicU64::new(1);
+    /// How often a client whose session went away looks for it again
+    const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// A session (detached or `vyom daemon`) answers on the socket
+    pub fn is_running() -> bool {
+        socket_path().is_some_and(|path| UnixStream::connect(path).is_ok())
+    }
+
+    /// Accept `vyom attach` clients in the background. A client sends its
+    /// size first, then one JSON crossterm event per line.
+    pub fn listen(tx: mpsc::Sender<AppEvent>) -> Result<()> {
+        let path = socket_path().context("HOME is not set")?;
+        // One session per user: a second one would take the socket from the first
+        if is_running() {
+            anyhow::bail!("Another Vyom session is running (`vyom attach` shows it)");
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Left over from a session that didn't exit cleanly
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        OWNED.store(true, Ordering::Relaxed);
+
+        // Closing the terminal we left must not take us down with it
+        let mut hangup = signal(SignalKind::hangup())?;
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {}
+        });
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                std::thread::spawn(move || serve_client(stream, tx));
+            }
+        });
+        Ok(())
+    }
+
+    fn serve_client(stream: UnixStream, tx: mpsc::Sender<AppEvent>) {
+        let Ok(reader) = stream.try_clone() else { return };
+        let mut lines = BufReader::new(reader).lines();
+
+        // Connections that say nothing are `is_running` checks
+        let Some(Ok(first)) = lines.next() else { return };
+        let Ok(Event::Resize(width, height)) = serde_json::from_str(&first) else { return };
+        let id = NEXT_CLIENT.fetch_add(1, Ordering::Relaxed);
+        if tx.blocking_send(AppEvent::Attach(id, Box::new(stream), width, height)).is_err() {
+            return;
+        }
+
+        for line in lines.map_while(Result::ok) {
+            if let Ok(event) = serde_json::from_str::<Event>(&line) {
+                if tx.blocking_send(AppEvent::RemoteInput(id, event)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// `vyom attach`: show a detached session in this terminal until it
+    /// detaches us (`d`) or quits. When the session itself goes away (a
+    /// restarted `vyom daemon`), wait for it to come back and carry on;
+    /// q, Esc or Ctrl+C stop waiting.
+    pub fn attach() -> Result<()> {
+        use crossterm::{
+            cursor::MoveTo,
+            event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyModifiers},
+            execute,
+            terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+        };
+
+        let path = socket_path().context("HOME is not set")?;
+        let mut stream = UnixStream::connect(&path)
+            .context("No detached Vyom session (press d in a running Vyom to detach it, or start `vyom daemon`)")?;
+
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+
+        // Our keys, clicks and resizes go to the session; with no session they can only stop the wait
+        let writer: Arc<Mutex<Option<UnixStream>>> = Arc::new(Mutex::new(None));
+        let give_up = Arc::new(AtomicBool::new(false));
+        {
+            let (writer, give_up) = (writer.clone(), give_up.clone());
+            std::thread::spawn(move || {
+                while let Ok(event) = crossterm::event::read() {
+                    let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+                    match writer.as_mut() {
+                        Some(stream) => {
+                            let Ok(line) = serde_json::to_string(&event) else { continue };
+                            if writeln!(stream, "{}", line).is_err() {
+                                *writer = None;
+                            }
+                        }
+                        None => {
+                            if let Event::Key(key) = event {
+                                let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                                if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                                    give_up.store(true, Ordering::Relaxed);
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        }
+
+        let result = 'session: loop {
+            let (width, height) = crossterm::terminal::size()?;
+            let mut hello = stream.try_clone()?;
+            writeln!(hello, "{}", serde_json::to_string(&Event::Resize(width, height))?)?;
+            *writer.lock().unwrap_or_else(|e| e.into_inner()) = Some(hello);
+
+            // Its frames come back; stdout is line-buffered, so flush every chunk
+            let mut buf = [0u8; 16 * 1024];
+            let pumped = loop {
+                match stream.read(&mut buf) {
+                    Ok(0) => break Ok(()),
+                    Ok(n) => {
+                        if let Err(e) = stdout.write_all(&buf[..n]).and_then(|_| stdout.flush()) {
+                            break Err(e);
+                        }
+                    }
+                    Err(e) => break Err(e),
+                }
+            };
+            *writer.lock().unwrap_or_else(|e| e.into_inner()) = None;
+
+            // Hung up on with the session still there: detached (or q'd) on purpose.
+            // A session that crashed can still take connections while it's torn down.
+            std::thread::sleep(Duration::from_millis(200));
+            if pumped.is_err() || is_running() {
+                break pumped;
+            }
+            execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+            print!("Vyom went away, waiting for it to come back... (q to stop)");
+            stdout.flush()?;
+            loop {
+                std::thread::sleep(RECONNECT_INTERVAL);
+                if give_up.load(Ordering::Relaxed) {
+                    break 'session Ok(());
+                }
+                if let Ok(reconnected) = UnixStream::connect(&path) {
+                    stream = reconnected;
+                    execute!(stdout, Clear(ClearType::All))?;
+                    continue 'session;
+                }
+            }
+        };
+
+        disable_raw_mode()?;
+        execute!(stdout, LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show)?;
+        Ok(result?)
+    }
+
+    pub fn cleanup() {
+        if !OWNED.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(path) = socket_path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(unix)]
+pub use unix::{attach, cleanup, is_running, listen};
+
+#[cfg(not(unix))]
+pub fn listen(_tx: tokio::sync::mpsc::Sender<crate::AppEvent>) -> Result<()> {
+    anyhow::bail!("Detaching needs Unix sockets")
+}
+
+#[cfg(not(unix))]
+pub fn attach() -> Result<()> {
+    anyhow::bail!("Detaching needs Unix sockets")
+}
+
+#[cfg(not(unix))]
+pub fn cleanup() {}
+
+#[cfg(not(unix))]
+pub fn is_running() -> bool {
+    false
+}
@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::config::expand_home;
+use crate::player::TrackInfo;
+
+// Now-playing snapshot 📝
+// `e` turns what's playing into a small markdown note (track, album, where in
+// the song, the line being sung) for journals and "what I was listening to"
+// notes: appended to a file when one is configured, else copied.
+
+/// Where the snapshot ended up, for the toast
+pub enum Destination {
+    Clipboard,
+    File(String),
+}
+
+/// The note itself, e.g.
+///
+/// ```text
+/// - **Song** — Artist · *Album* · 1:23 / 3:45 · 2026-10-16 21:04
+///   > the line being sung
+/// ```
+pub fn markdown(track: &TrackInfo, position_ms: u64, lyric: Option<&str>) -> String {
+    let time = |ms: u64| format!("{}:{:02}", ms / 60000, (ms % 60000) / 1000);
+    let mut note = format!(
+        "- **{}** — {} · *{}* · {} / {}",
+        track.name, track.artist, track.album, time(position_ms), time(track.duration_ms)
+    );
+    if let Some(now) = local_time() {
+        note.push_str(&format!(" · {}", now));
+    }
+    note.push('\n');
+    if let Some(line) = lyric.map(str::trim).filter(|l| !l.is_empty()) {
+        note.push_str(&format!("  > {}\n", line));
+    }
+    note
+}
+
+/// Append to `file` when set, otherwise copy to the clipboard
+pub fn export(note: &str, file: Option<&str>) -> Result<Destination> {
+    match file {
+        Some(file) => {
+            let path = expand_home(file);
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut out = OpenOptions::new().create(true).append(true).open(&path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            out.write_all(note.as_bytes())?;
+            Ok(Destination::File(file.to_string()))
+        }
+        None => {
+            copy(note)?;
+            Ok(Destination::Clipboard)
+        }
+    }
+}
+
+/// "2026-10-16 21:04" from `date`, so no date crate is needed
+fn local_time() -> Option<String> {
+    let output = Command::new("date").arg("+%Y-%m-%d %H:%M").output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// --- Clipboard: pbcopy on macOS, wl-copy or xclip on Linux ---
+
+fn copy(text: &str) -> Result<()> {
+    let tools: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else {
+        &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"])]
+    };
+    for (tool, args) in tools {
+        let Ok(mut child) = Command::new(tool)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(stdin) = child.stdin.as_mut() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        // Drop stdin so the tool sees EOF
+        drop(child.stdin.take());
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    anyhow::bail!("No clipboard tool found (pbcopy, wl-copy or xclip)")
+}
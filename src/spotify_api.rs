@@ -0,0 +1,550 @@
+use anyhow::{Context, Result};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::SpotifyConfig;
+use crate::hash;
+use crate::keychain;
+use crate::lyrics::spotify::access_token;
+use crate::output;
+use crate::player::{PlayerState, Repeat, TrackInfo};
+
+const PLAYER_URL: &str = "https://api.spotify.com/v1/me/player";
+const AUDIO_ANALYSIS_URL: &str = "https://api.spotify.com/v1/audio-analysis";
+const EPISODES_URL: &str = "https://api.spotify.com/v1/episodes";
+const SAVED_TRACKS_URL: &str = "https://api.spotify.com/v1/me/tracks";
+const AUTHORIZE_URL: &str = "https://accounts.spotify.com/authorize";
+const OAUTH_TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+/// What the login asks for: the library, and reading and driving playback
+const SCOPES: &str = "user-library-read user-library-modify user-read-playback-state user-modify-playback-state";
+
+/// Where the current track plays from (playlist, album, artist radio, ...)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaybackContext {
+    /// "playlist", "album", "artist", "show" or "collection"
+    pub kind: String,
+    pub name: String,
+    /// `spotify:playlist:...`, opened by the context key
+    pub uri: String,
+}
+
+/// What the Web API says about the player, refreshed on every track change
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerStatus {
+    pub context: Option<PlaybackContext>,
+    /// Spotify's private session: nothing is shared, so neither should we
+    pub private_session: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlayerResponse {
+    context: Option<ContextRef>,
+    device: Option<Device>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Device {
+    #[serde(default)]
+    is_private_session: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContextRef {
+    #[serde(rename = "type")]
+    kind: String,
+    uri: String,
+    href: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Named {
+    name: String,
+}
+
+/// A stretch of the track from Spotify's audio analysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Clearly louder than the rest of the track, the best hint of a chorus
+    /// the analysis gives (sections carry no labels)
+    pub chorus: bool,
+}
+
+/// What the audio analysis tells us about the playing track
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    pub sections: Vec<Section>,
+    /// Integrated loudness of the whole track in dB (around -14 is typical)
+    pub loudness_db: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnalysisResponse {
+    #[serde(default)]
+    sections: Vec<RawSection>,
+    track: Option<AnalysisTrack>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnalysisTrack {
+    loudness: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Episode {
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSection {
+    start: f64,
+    duration: f64,
+    loudness: f64,
+}
+
+/// Ask the Web API what the user is playing from and whether it's a private
+/// session. Uses the same web player token as Spotify lyrics, so it needs the
+/// sp_dc cookie.
+pub async fn fetch_status(client: &Client, sp_dc: &str) -> Result<PlayerStatus> {
+    let token = access_token(client, sp_dc).await?;
+    let resp = client.get(PLAYER_URL).bearer_auth(&token).send().await?;
+
+    // 204: nothing playing on any device
+    if resp.status() == StatusCode::NO_CONTENT {
+        return Ok(PlayerStatus::default());
+    }
+    let player: PlayerResponse = resp.error_for_status()?.json().await?;
+    let private_session = player.device.is_some_and(|d| d.is_private_session);
+    let context = match player.context {
+        Some(context) => fetch_context_name(client, &token, context).await?,
+        None => None,
+    };
+    Ok(PlayerStatus { context, private_session })
+}
+
+async fn fetch_context_name(client: &Client, token: &str, context: ContextRef) -> Result<Option<PlaybackContext>> {
+    // Liked Songs has no API object to name it
+    let name = match (&context.href, context.kind.as_str()) {
+        (_, "collection") => "Liked Songs".to_string(),
+        (Some(href), _) => {
+            let named: Named = client.get(href)
+                .query(&[("fields", "name")])
+                .bearer_auth(token)
+                .send().await?
+                .error_for_status()?
+                .json().await?;
+            named.name
+        }
+        (None, _) => return Ok(None),
+    };
+
+    Ok(Some(PlaybackContext { kind: context.kind, name, uri: context.uri }))
+}
+
+/// Sections of the playing track, for styling chorus lyrics, and its
+/// loudness. Local files and podcasts have no analysis.
+pub async fn fetch_analysis(client: &Client, sp_dc: &str, track_uri: &str) -> Result<Analysis> {
+    let Some(track_id) = track_uri.strip_prefix("spotify:track:") else { return Ok(Analysis::default()) };
+
+    let token = access_token(client, sp_dc).await?;
+    let analysis: AnalysisResponse = client.get(format!("{}/{}", AUDIO_ANALYSIS_URL, track_id))
+        .bearer_auth(&token)
+        .send().await?
+        .error_for_status()?
+        .json().await?;
+    let loudness_db = analysis.track.map(|t| t.loudness as f32);
+    if analysis.sections.is_empty() {
+        return Ok(Analysis { sections: Vec::new(), loudness_db });
+    }
+
+    // Loudness is in dB (negative); a chorus stands out by a decibel or more
+    let mean = analysis.sections.iter().map(|s| s.loudness).sum::<f64>() / analysis.sections.len() as f64;
+    let sections = analysis.sections.into_iter()
+        .map(|s| Section {
+            start_ms: (s.start * 1000.0) as u64,
+            end_ms: ((s.start + s.duration) * 1000.0) as u64,
+            chorus: s.loudness > mean + 1.0,
+        })
+        .collect();
+    Ok(Analysis { sections, loudness_db })
+}
+
+/// Show notes (plain text) of a `spotify:episode:<id>`
+pub async fn fetch_episode_description(client: &Client, sp_dc: &str, episode_uri: &str) -> Result<Option<String>> {
+    let Some(episode_id) = episode_uri.strip_prefix("spotify:episode:") else { return Ok(None) };
+
+    let token = access_token(client, sp_dc).await?;
+    let episode: Episode = client.get(format!("{}/{}", EPISODES_URL, episode_id))
+        .query(&[("market", "from_token")])
+        .bearer_auth(&token)
+        .send().await?
+        .error_for_status()?
+        .json().await?;
+    Ok(Some(episode.description).filter(|d| !d.trim().is_empty()))
+}
+
+/// Whether the desktop client has "Normalize volume" on, from its prefs file.
+/// The setting is only written once it's been changed, so no entry means on;
+/// None when there's no Spotify client here.
+pub fn normalization_enabled() -> Option<bool> {
+    let prefs = if cfg!(target_os = "windows") {
+        std::path::PathBuf::from(std::env::var("APPDATA").ok()?).join("Spotify").join("prefs")
+    } else if cfg!(target_os = "macos") {
+        std::path::PathBuf::from(std::env::var("HOME").ok()?).join("Library/Application Support/Spotify/prefs")
+    } else {
+        std::path::PathBuf::from(std::env::var("HOME").ok()?).join(".config/spotify/prefs")
+    };
+    let content = std::fs::read_to_string(prefs).ok()?;
+    Some(!content.lines().any(|line| line.trim() == "audio.normalize_v2=false"))
+}
+
+// Web API login 🔑
+// The sp_dc token above can read but not change anything, and AppleScript
+// can't save tracks, shuffle reliably, read the queue or move playback to
+// another device. `vyom auth spotify` logs in through the user's own app
+// (Authorization Code with PKCE: a browser consent and a redirect to a
+// one-shot local listener, no client secret) and keeps the refresh token in
+// the keychain. `SpotifyWebPlayer` then does those things next to the local
+// player.
+
+/// Access token from the login, and when it runs out
+static WEB_TOKEN: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+/// Spotify may rotate the refresh token on every refresh; the newest wins over the config's
+static REFRESH_TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Deserialize)]
+struct OAuthToken {
+    access_token: String,
+    expires_in: u64,
+    refresh_token: Option<String>,
+}
+
+fn repeat_from_api(state: &str) -> Repeat {
+    match state {
+        "context" => Repeat::Context,
+        "track" => Repeat::Track,
+        _ => Repeat::Off,
+    }
+}
+
+fn repeat_to_api(repeat: Repeat) -> &'static str {
+    match repeat {
+        Repeat::Off => "off",
+        Repeat::Context => "context",
+        Repeat::Track => "track",
+    }
+}
+
+fn redirect_uri(config: &SpotifyConfig) -> String {
+    format!("http://127.0.0.1:{}/callback", config.redirect_port)
+}
+
+/// PKCE code verifier: 64 characters from the unreserved set, from the OS's
+/// random source
+fn code_verifier() -> Result<String> {
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
+    let mut bytes = [0u8; 64];
+    getrandom::getrandom(&mut bytes).map_err(|e| anyhow::anyhow!("No randomness from the OS: {}", e))?;
+    // A byte mod 66 leans a little towards the first characters; 64 of them
+    // still carry well over the 256 bits RFC 7636 asks for
+    Ok(bytes.iter().map(|b| CHARS[*b as usize % CHARS.len()] as char).collect())
+}
+
+async fn request_token(client: &Client, form: &[(&str, &str)]) -> Result<OAuthToken> {
+    let resp = client.post(OAUTH_TOKEN_URL).form(form).send().await?;
+    if !resp.status().is_success() {
+        let body: Value = resp.json().await.unwrap_or_default();
+        let reason = body.get("error_description").or(body.get("error")).and_then(Value::as_str).unwrap_or("unknown error");
+        anyhow::bail!("Spotify refused the login: {}", reason);
+    }
+    Ok(resp.json().await?)
+}
+
+/// Wait for the browser to come back to the redirect URI, and take the code
+/// from it. Anything else hitting the port (a favicon request) is ignored.
+fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String> {
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let mut request_line = String::new();
+        BufReader::new(&stream).read_line(&mut request_line)?;
+        // "GET /callback?code=...&state=... HTTP/1.1"
+        let Some(path) = request_line.split_whitespace().nth(1).filter(|p| p.starts_with("/callback")) else {
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            continue;
+        };
+        let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", path))?;
+        let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned());
+        let (message, result) = match (param("code"), param("error")) {
+            _ if param("state").as_deref() != Some(state) => ("This login wasn't started by Vyom.", Err(anyhow::anyhow!("Login state didn't match, try again"))),
+            (Some(code), _) => ("Vyom is logged in to Spotify, you can close this tab.", Ok(code)),
+            (None, error) => ("Vyom wasn't allowed in.", Err(anyhow::anyhow!("Spotify login failed: {}", error.unwrap_or_default()))),
+        };
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            message.len(),
+            message
+        );
+        return result;
+    }
+    anyhow::bail!("The login listener closed")
+}
+
+/// `vyom auth spotify`: approve Vyom in the browser, then keep the refresh token
+pub async fn login(client: &Client, config: &SpotifyConfig) -> Result<()> {
+    let Some(client_id) = config.client_id.as_deref() else {
+        anyhow::bail!(
+            "Set [spotify] client_id first: create an app at https://developer.spotify.com/dashboard with {} as a redirect URI",
+            redirect_uri(config)
+        );
+    };
+    let redirect = redirect_uri(config);
+    let listener = TcpListener::bind(("127.0.0.1", config.redirect_port))
+        .with_context(|| format!("Port {} is taken; pick another with [spotify] redirect_port", config.redirect_port))?;
+
+    let verifier = code_verifier()?;
+    let challenge = crate::base64::encode_url(&hash::sha256(verifier.as_bytes()));
+    let state = code_verifier()?[..16].to_string();
+    let url = reqwest::Url::parse_with_params(AUTHORIZE_URL, &[
+        ("client_id", client_id),
+        ("response_type", "code"),
+        ("redirect_uri", &redirect),
+        ("code_challenge_method", "S256"),
+        ("code_challenge", &challenge),
+        ("state", &state),
+        ("scope", SCOPES),
+    ])?;
+    let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+    let _ = Command::new(opener).arg(url.as_str()).stdout(Stdio::null()).stderr(Stdio::null()).status();
+    println!("Allow Vyom on Spotify (opened in your browser):\n  {}", url);
+    let code = wait_for_code(&listener, &state)?;
+
+    let token = request_token(client, &[
+        ("grant_type", "authorization_code"),
+        ("code", &code),
+        ("redirect_uri", &redirect),
+        ("client_id", client_id),
+        ("code_verifier", &verifier),
+    ]).await?;
+    let refresh_token = token.refresh_token.context("Spotify returned no refresh token")?;
+    keychain::set("spotify_refresh_token", &refresh_token)?;
+    println!("Logged in to Spotify.");
+    Ok(())
+}
+
+/// Saves, shuffle, repeat, the queue and Connect devices through the Web API,
+/// for the Spotify app the local player drives. Cheap to clone into tasks.
+#[derive(Debug, Clone)]
+pub struct SpotifyWebPlayer {
+    client: Client,
+    client_id: String,
+    refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Modes {
+    shuffle_state: bool,
+    repeat_state: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Queue {
+    #[serde(default)]
+    queue: Vec<QueueItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueueItem {
+    uri: String,
+    name: String,
+    duration_ms: u64,
+    #[serde(default)]
+    artists: Vec<Named>,
+    album: Option<QueueAlbum>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueueAlbum {
+    name: String,
+    #[serde(default)]
+    images: Vec<Image>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Image {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Devices {
+    #[serde(default)]
+    devices: Vec<ConnectDevice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConnectDevice {
+    /// Restricted devices have none and can't be controlled
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    is_active: bool,
+}
+
+impl SpotifyWebPlayer {
+    /// None until there's a `client_id` and `vyom auth spotify` has been run
+    pub fn from_config(client: &Client, config: &SpotifyConfig) -> Option<Self> {
+        Some(Self {
+            client: client.clone(),
+            client_id: config.client_id.clone()?,
+            refresh_token: config.refresh_token.clone()?,
+        })
+    }
+
+    /// The cached access token, refreshed a minute before it runs out
+    async fn token(&self) -> Result<String> {
+        if let Ok(cached) = WEB_TOKEN.lock() {
+            if let Some((token, expires)) = cached.as_ref() {
+                if Instant::now() < *expires {
+                    return Ok(token.clone());
+                }
+            }
+        }
+        let refresh_token = REFRESH_TOKEN.lock().ok()
+            .and_then(|latest| latest.clone())
+            .unwrap_or_else(|| self.refresh_token.clone());
+        let token = request_token(&self.client, &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh_token),
+            ("client_id", &self.client_id),
+        ]).await?;
+        if let Some(rotated) = token.refresh_token.filter(|t| *t != refresh_token) {
+            let _ = keychain::set("spotify_refresh_token", &rotated);
+            if let Ok(mut latest) = REFRESH_TOKEN.lock() {
+                *latest = Some(rotated);
+            }
+        }
+        let expires = Instant::now() + Duration::from_secs(token.expires_in.saturating_sub(60));
+        if let Ok(mut cached) = WEB_TOKEN.lock() {
+            *cached = Some((token.access_token.clone(), expires));
+        }
+        Ok(token.access_token)
+    }
+
+    async fn request(&self, method: Method, url: &str) -> Result<RequestBuilder> {
+        Ok(self.client.request(method, url).bearer_auth(self.token().await?))
+    }
+
+    /// Whether `track_uri` is in Liked Songs
+    pub async fn is_saved(&self, track_uri: &str) -> Result<bool> {
+        let Some(track_id) = track_uri.strip_prefix("spotify:track:") else { return Ok(false) };
+        let saved: Vec<bool> = self.request(Method::GET, &format!("{}/contains", SAVED_TRACKS_URL)).await?
+            .query(&[("ids", track_id)])
+            .send().await?
+            .error_for_status()?
+            .json().await?;
+        Ok(saved.first().copied().unwrap_or(false))
+    }
+
+    /// Add `track_uri` to Liked Songs, or take it out
+    pub async fn set_saved(&self, track_uri: &str, saved: bool) -> Result<()> {
+        let Some(track_id) = track_uri.strip_prefix("spotify:track:") else { return Ok(()) };
+        let method = if saved { Method::PUT } else { Method::DELETE };
+        self.request(method, SAVED_TRACKS_URL).await?
+            .query(&[("ids", track_id)])
+            .header("Content-Length", "0")
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Shuffle and repeat as they are now
+    pub async fn modes(&self) -> Result<(bool, Repeat)> {
+        let resp = self.request(Method::GET, PLAYER_URL).await?.send().await?;
+        if resp.status() == StatusCode::NO_CONTENT {
+            anyhow::bail!("Spotify isn't playing anywhere");
+        }
+        let modes: Modes = resp.error_for_status()?.json().await?;
+        Ok((modes.shuffle_state, repeat_from_api(&modes.repeat_state)))
+    }
+
+    pub async fn set_shuffle(&self, on: bool) -> Result<()> {
+        self.request(Method::PUT, &format!("{}/shuffle", PLAYER_URL)).await?
+            .query(&[("state", on.to_string())])
+            .header("Content-Length", "0")
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn set_repeat(&self, repeat: Repeat) -> Result<()> {
+        self.request(Method::PUT, &format!("{}/repeat", PLAYER_URL)).await?
+            .query(&[("state", repeat_to_api(repeat))])
+            .header("Content-Length", "0")
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Tracks up next, in order (episodes and local files left out)
+    pub async fn queue(&self) -> Result<Vec<TrackInfo>> {
+        let queue: Queue = self.request(Method::GET, &format!("{}/queue", PLAYER_URL)).await?
+            .send().await?
+            .error_for_status()?
+            .json().await?;
+        Ok(queue.queue.into_iter()
+            .filter(|item| item.uri.starts_with("spotify:track:"))
+            .map(|item| {
+                let (album, artwork_url) = match item.album {
+                    Some(album) => (album.name, album.images.into_iter().next().map(|i| i.url)),
+                    None => (String::new(), None),
+                };
+                TrackInfo {
+                    name: item.name,
+                    artist: item.artists.into_iter().map(|a| a.name).collect::<Vec<_>>().join(", "),
+                    album,
+                    artwork_url,
+                    duration_ms: item.duration_ms,
+                    position_ms: 0,
+                    state: PlayerState::Stopped,
+                    source: "Spotify".to_string(),
+                    id: Some(item.uri),
+                    bpm: None,
+                    volume: None,
+                    context: None,
+                    rating: None,
+                    play_count: None,
+                    last_played: None,
+                    favorite: None,
+                }
+            })
+            .collect())
+    }
+
+    /// Spotify Connect devices that can take over playback
+    pub async fn devices(&self) -> Result<Vec<output::Device>> {
+        let devices: Devices = self.request(Method::GET, &format!("{}/devices", PLAYER_URL)).await?
+            .send().await?
+            .error_for_status()?
+            .json().await?;
+        Ok(devices.devices.into_iter()
+            .filter_map(|d| Some(output::Device { id: d.id?, name: d.name, connect: true, active: d.is_active }))
+            .collect())
+    }
+
+    /// Move playback to a Connect device, carrying on where it was
+    pub async fn transfer(&self, device_id: &str) -> Result<()> {
+        self.request(Method::PUT, PLAYER_URL).await?
+            .json(&serde_json::json!({ "device_ids": [device_id], "play": true }))
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::app::App;
+use crate::player;
+
+/// What the last session left behind, restored on the next launch 💾
+/// Only things changed at runtime live here; everything else is config.toml.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedState {
+    /// A/V/C/F/I toggles (None: leave the config value alone)
+    pub show_artwork: Option<bool>,
+    pub show_visualizer: Option<bool>,
+    pub show_controls: Option<bool>,
+    pub show_footer: Option<bool>,
+    pub info_icons: Option<bool>,
+    /// Player picked in the selector strip
+    pub preferred_player: Option<String>,
+}
+
+fn state_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache").join("vyom").join("state.json"))
+}
+
+pub fn load() -> SavedState {
+    state_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+impl SavedState {
+    pub fn apply(self, app: &mut App) {
+        let ui = &mut app.config.ui;
+        ui.show_artwork = self.show_artwork.unwrap_or(ui.show_artwork);
+        ui.show_visualizer = self.show_visualizer.unwrap_or(ui.show_visualizer);
+        ui.show_controls = self.show_controls.unwrap_or(ui.show_controls);
+        ui.show_footer = self.show_footer.unwrap_or(ui.show_footer);
+        ui.info_icons = self.info_icons.unwrap_or(ui.info_icons);
+        if self.preferred_player.is_some() {
+            player::set_preferred_player(self.preferred_player);
+        }
+    }
+}
+
+/// Write the runtime state on exit. Toggles still matching `config` are left
+/// out so later config.toml edits keep working.
+pub fn save(app: &App, config: &crate::config::Config) {
+    let (ui, default) = (&app.config.ui, &config.ui);
+    let changed = |now: bool, configured: bool| (now != configured).then_some(now);
+    let state = SavedState {
+        show_artwork: changed(ui.show_artwork, default.show_artwork),
+        show_visualizer: changed(ui.show_visualizer, default.show_visualizer),
+        show_controls: changed(ui.show_controls, default.show_controls),
+        show_footer: changed(ui.show_footer, default.show_footer),
+        info_icons: changed(ui.info_icons, default.info_icons),
+        preferred_player: player::preferred_player(),
+    };
+
+    let Some(path) = state_path() else { return };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&state) {
+        let _ = fs::write(path, json);
+    }
+}
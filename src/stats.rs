@@ -0,0 +1,111 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+// Self-monitoring 🩺
+// Numbers for the F12 debug overlay: how long frames and player polls take,
+// how busy the event loop is (and how much of its backlog it could skip), and
+// what the process and its caches weigh. Timings and counters are always
+// recorded (atomic stores); the rest is sampled once a second, and only while
+// the overlay is open.
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static FRAME_US: AtomicU64 = AtomicU64::new(0);
+static POLL_US: AtomicU64 = AtomicU64::new(0);
+static EVENTS: AtomicU64 = AtomicU64::new(0);
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+/// Caches under ~/.cache/vyom shown in the overlay
+const CACHE_DIRS: [&str; 3] = ["lyrics", "artwork", "itunes"];
+
+/// One second's worth of the slower numbers
+#[derive(Debug, Clone, Default)]
+pub struct Sample {
+    pub events_per_sec: u64,
+    pub cpu_percent: Option<f32>,
+    pub rss_kb: Option<u64>,
+    /// (cache dir, files, bytes)
+    pub disk_caches: Vec<(&'static str, usize, u64)>,
+    pub tasks: usize,
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn record_frame(elapsed: Duration) {
+    FRAME_US.store(elapsed.as_micros() as u64, Ordering::Relaxed);
+}
+
+pub fn record_poll(elapsed: Duration) {
+    POLL_US.store(elapsed.as_micros() as u64, Ordering::Relaxed);
+}
+
+pub fn record_events(count: u64) {
+    EVENTS.fetch_add(count, Ordering::Relaxed);
+}
+
+/// Events thrown away because the queue was full or a newer one replaced them
+pub fn record_dropped(count: u64) {
+    DROPPED.fetch_add(count, Ordering::Relaxed);
+}
+
+/// Dropped events since startup
+pub fn dropped() -> u64 {
+    DROPPED.load(Ordering::Relaxed)
+}
+
+/// Last frame's draw time
+pub fn frame_time() -> Duration {
+    Duration::from_micros(FRAME_US.load(Ordering::Relaxed))
+}
+
+/// How long the last "what's playing?" query took
+pub fn poll_latency() -> Duration {
+    Duration::from_micros(POLL_US.load(Ordering::Relaxed))
+}
+
+/// Take the slow numbers; call once a second, off the async runtime (runs `ps`)
+pub fn sample(tasks: usize) -> Sample {
+    let (cpu_percent, rss_kb) = process_usage().unzip();
+    Sample {
+        events_per_sec: EVENTS.swap(0, Ordering::Relaxed),
+        cpu_percent,
+        rss_kb,
+        disk_caches: disk_caches(),
+        tasks,
+    }
+}
+
+/// CPU % and resident memory of this process; `ps` is the same on macOS and Linux
+fn process_usage() -> Option<(f32, u64)> {
+    let output = Command::new("ps")
+        .args(["-o", "%cpu=,rss=", "-p", &std::process::id().to_string()])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut fields = text.split_whitespace();
+    let cpu = fields.next()?.replace(',', ".").parse().ok()?;
+    let rss = fields.next()?.parse().ok()?;
+    Some((cpu, rss))
+}
+
+fn disk_caches() -> Vec<(&'static str, usize, u64)> {
+    let Ok(home) = std::env::var("HOME") else { return Vec::new() };
+    let root = Path::new(&home).join(".cache").join("vyom");
+    CACHE_DIRS.iter().map(|&name| {
+        let (files, bytes) = std::fs::read_dir(root.join(name))
+            .map(|entries| entries.flatten()
+                .filter_map(|e| e.metadata().ok())
+                .filter(|m| m.is_file())
+                .fold((0, 0), |(n, total), m| (n + 1, total + m.len())))
+            .unwrap_or((0, 0));
+        (name, files, bytes)
+    }).collect()
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::glyphs;
+use crate::player::{self, PlayerState, TrackInfo};
+use crate::ui::fmt_ms;
+
+// Status line 📟
+// `vyom status` asks the player once and prints the track without starting
+// the TUI, for waybar, polybar, sketchybar or tmux. `--format` takes the
+// footer's placeholders, `--json` prints an object waybar's custom modules
+// read as-is (text, tooltip, class, percentage) with the track fields
+// alongside, and `--follow` keeps printing whenever the output changes.
+
+pub const DEFAULT_FORMAT: &str = "{state} {artist} – {title}";
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn run_cli(args: &[String], config: &Config) -> Result<()> {
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("Usage: vyom status [--json] [--format TEMPLATE] [--follow]");
+        println!("Placeholders: {{title}} {{artist}} {{album}} {{source}} {{position}} {{duration}} {{volume}} {{state}} {{progress}}");
+        return Ok(());
+    }
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+    let format = flag("--format").map(String::as_str).unwrap_or(DEFAULT_FORMAT);
+    let json = args.iter().any(|a| a == "--json");
+    let follow = args.iter().any(|a| a == "--follow");
+
+    let player = player::get_player();
+    let mut last = None;
+    loop {
+        // A player that can't be read shows as nothing playing: bars want a line, not an error
+        let track = player.get_current_track().ok().flatten();
+        let text = render(format, track.as_ref(), config);
+        let line = if json { to_json(text, track.as_ref()).to_string() } else { text };
+        if last.as_ref() != Some(&line) {
+            println!("{}", line);
+        }
+        if !follow {
+            return Ok(());
+        }
+        last = Some(line);
+        std::thread::sleep(FOLLOW_INTERVAL);
+    }
+}
+
+fn state_name(state: &PlayerState) -> &'static str {
+    match state {
+        PlayerState::Playing => "playing",
+        PlayerState::Paused => "paused",
+        PlayerState::Stopped => "stopped",
+    }
+}
+
+fn progress(track: &TrackInfo) -> u64 {
+    (track.position_ms * 100).checked_div(track.duration_ms).unwrap_or(0).min(100)
+}
+
+/// Expand the placeholders; nothing playing prints an empty line. Unknown ones stay as written.
+pub fn render(format: &str, track: Option<&TrackInfo>, config: &Config) -> String {
+    let Some(track) = track else { return String::new() };
+    let mut out = String::new();
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            rest = &rest[start..];
+            break;
+        };
+        let value = match &rest[start + 1..end] {
+            "title" => track.name.clone(),
+            "artist" => track.artist.clone(),
+            "album" => track.album.clone(),
+            "source" => track.source.clone(),
+            "position" => fmt_ms(track.position_ms),
+            "duration" => fmt_ms(track.duration_ms),
+            "volume" => track.volume.map(|v| format!("{}%", v)).unwrap_or_else(|| "--".to_string()),
+            "state" => glyphs::get(config.ui.glyphs).state(&track.state).to_string(),
+            "progress" => progress(track).to_string(),
+            _ => rest[start..=end].to_string(),
+        };
+        out.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn to_json(text: String, track: Option<&TrackInfo>) -> Value {
+    let Some(track) = track else {
+        return serde_json::json!({ "text": text, "tooltip": "", "class": "stopped", "percentage": 0, "state": "stopped" });
+    };
+    serde_json::json!({
+        "text": text,
+        "tooltip": format!("{}\n{}\n{}", track.name, track.artist, track.album),
+        "class": state_name(&track.state),
+        "percentage": progress(track),
+        "state": state_name(&track.state),
+        "title": track.name,
+        "artist": track.artist,
+        "album": track.album,
+        "source": track.source,
+        "position_ms": track.position_ms,
+        "duration_ms": track.duration_ms,
+        "volume": track.volume,
+        "artwork_url": track.artwork_url,
+        "id": track.id,
+    })
+}
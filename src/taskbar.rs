@@ -0,0 +1,97 @@
+use crate::app::App;
+use crate::artwork;
+use crate::base64;
+use crate::player::PlayerState;
+use crate::ui::fmt_ms;
+
+// Terminal progress 📶
+// Terminals that show progress in their tab or the taskbar get the track's:
+// Windows Terminal, ConEmu, WezTerm and Ghostty read OSC 9;4 (green while
+// playing, yellow when paused), iTerm2 shows the track and time as a badge
+// in the corner of the pane. It stays up with the pane scrolled away or the
+// tab in the background. Elsewhere OSC 9 can mean a desktop notification, so
+// other terminals get nothing.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    /// `ESC ] 9 ; 4 ; state ; percent BEL`
+    Progress,
+    /// iTerm2's `SetBadgeFormat`
+    Badge,
+}
+
+pub struct TerminalProgress {
+    kind: Kind,
+    is_tmux: bool,
+    /// Last escape written, to only write again when it changes
+    last: Option<String>,
+}
+
+impl TerminalProgress {
+    /// None when this terminal shows neither
+    pub fn detect(is_tmux: bool) -> Option<Self> {
+        let env = |name: &str| std::env::var(name).unwrap_or_default();
+        let program = env("TERM_PROGRAM");
+        // Inside tmux TERM_PROGRAM is tmux's; the others survive it
+        let kind = if program == "iTerm.app" || env("LC_TERMINAL") == "iTerm2" {
+            Kind::Badge
+        } else if program == "WezTerm" || program == "ghostty" || std::env::var("WT_SESSION").is_ok() || std::env::var("ConEmuPID").is_ok() {
+            Kind::Progress
+        } else {
+            return None;
+        };
+        Some(Self { kind, is_tmux, last: None })
+    }
+
+    /// The escape for what's playing now, if it changed since the last one
+    pub fn update(&mut self, app: &App) -> Option<String> {
+        let track = app.track.as_ref()
+            .filter(|track| app.config.ui.terminal_progress && track.state != PlayerState::Stopped);
+        let escape = match (self.kind, track) {
+            (Kind::Progress, Some(track)) => {
+                let state = if track.state == PlayerState::Playing { 1 } else { 4 };
+                format!("\x1b]9;4;{};{}\x07", state, (app.clock.progress() * 100.0).round() as u8)
+            }
+            (Kind::Badge, Some(track)) => {
+                let icon = if track.state == PlayerState::Playing { "▶" } else { "⏸" };
+                // Badges interpolate `\(...)`, titles shouldn't
+                let text = format!(
+                    "{} {}\n{} / {}",
+                    icon,
+                    track.name.replace('\\', "\\\\"),
+                    fmt_ms(app.clock.position_ms()),
+                    fmt_ms(app.clock.duration_ms()),
+                );
+                badge(&text)
+            }
+            (_, None) => return self.clear(),
+        };
+        self.write(escape)
+    }
+
+    /// Take the progress down (nothing playing, or Vyom is leaving the terminal)
+    pub fn clear(&mut self) -> Option<String> {
+        let escape = match self.kind {
+            Kind::Progress => "\x1b]9;4;0;0\x07".to_string(),
+            Kind::Badge => badge(""),
+        };
+        // Nothing to take down before anything was shown
+        if self.last.is_none() {
+            self.last = Some(escape);
+            return None;
+        }
+        self.write(escape)
+    }
+
+    fn write(&mut self, escape: String) -> Option<String> {
+        if self.last.as_ref() == Some(&escape) {
+            return None;
+        }
+        self.last = Some(escape.clone());
+        Some(artwork::passthrough(&escape, self.is_tmux))
+    }
+}
+
+fn badge(text: &str) -> String {
+    format!("\x1b]1337;SetBadgeFormat={}\x07", base64::encode(text.as_bytes()))
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+
+const SEARCH_URL: &str = "https://api.getsongbpm.com/search/";
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    // The API answers {"search": {"error": "..."}} when nothing matches
+    #[serde(default)]
+    search: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct Song {
+    tempo: Option<String>,
+}
+
+/// Look up a track's tempo on GetSongBPM.
+pub async fn fetch_bpm(client: &Client, api_key: &str, artist: &str, title: &str) -> Result<Option<f32>> {
+    let lookup = format!("song:{} artist:{}", title, artist);
+    let params = [("api_key", api_key), ("type", "both"), ("lookup", lookup.as_str())];
+
+    let data: SearchResponse = client.get(SEARCH_URL).query(&params).send().await?.json().await?;
+    let songs: Vec<Song> = serde_json::from_value(data.search).unwrap_or_default();
+
+    Ok(songs.into_iter()
+        .filter_map(|s| s.tempo?.parse::<f32>().ok())
+        .find(|bpm| *bpm > 0.0))
+}
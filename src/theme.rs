@@ -17,8 +17,8 @@ pub struct Theme {
 
 }
 
-impl Theme {
-    pub fn default() -> Self {
+impl Default for Theme {
+    fn default() -> Self {
         Self {
             base: Color::Rgb(30, 30, 46),
             surface: Color::Rgb(49, 50, 68),
@@ -59,3 +59,8 @@ pub fn load_current_theme() -> Theme {
     }
     Theme::default()
 }
+
+/// The `NO_COLOR` convention (https://no-color.org): set and not empty
+pub fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
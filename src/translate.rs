@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{TranslationConfig, TranslationService};
+
+// Lyrics translation 🌍
+// `t` sends the lyrics to DeepL or a LibreTranslate instance and shows each
+// line's translation under it, like the ones NetEase/QQ ship. Repeated lines
+// (choruses) go out once, and the result is kept per track and language in
+// ~/.cache/vyom/translations, so a song is only translated the first time.
+
+/// Texts per request; DeepL takes at most 50
+const BATCH: usize = 50;
+
+/// Whether `[translation]` has enough to send a request
+pub fn configured(config: &TranslationConfig) -> bool {
+    match config.service {
+        TranslationService::Deepl => config.api_key.is_some(),
+        TranslationService::Libretranslate => config.url.is_some(),
+    }
+}
+
+fn get_cache_path(artist: &str, title: &str, target: &str) -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    let filename = format!("{}_{}.{}.json", artist.replace('/', "_"), title.replace('/', "_"), target);
+    Some(Path::new(&home).join(".cache").join("vyom").join("translations").join(filename))
+}
+
+/// `lines` in `target`, one for each (empty ones stay empty). From the cache when
+/// this track was translated before.
+pub async fn translate(
+    client: &Client,
+    config: &TranslationConfig,
+    artist: &str,
+    title: &str,
+    lines: &[String],
+    target: &str,
+) -> Result<Vec<String>> {
+    let cache_path = get_cache_path(artist, title, target);
+    if let Some(cached) = cache_path.as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str::<Vec<String>>(&json).ok())
+        .filter(|cached| cached.len() == lines.len())
+    {
+        return Ok(cached);
+    }
+
+    let mut unique: Vec<&str> = Vec::new();
+    for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if !unique.contains(&line) {
+            unique.push(line);
+        }
+    }
+    let mut translated = Vec::with_capacity(unique.len());
+    for batch in unique.chunks(BATCH) {
+        let texts = match config.service {
+            TranslationService::Deepl => deepl(client, config, batch, target).await?,
+            TranslationService::Libretranslate => libretranslate(client, config, batch, target).await?,
+        };
+        if texts.len() != batch.len() {
+            anyhow::bail!("translation came back with {} lines for {}", texts.len(), batch.len());
+        }
+        translated.extend(texts);
+    }
+
+    let result: Vec<String> = lines.iter()
+        .map(|line| {
+            unique.iter().position(|u| *u == line.trim())
+                .map(|i| translated[i].clone())
+                .unwrap_or_default()
+        })
+        .collect();
+    if let Some(path) = &cache_path {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(&result) {
+            let _ = fs::write(path, json);
+        }
+    }
+    Ok(result)
+}
+
+async fn deepl(client: &Client, config: &TranslationConfig, texts: &[&str], target: &str) -> Result<Vec<String>> {
+    let key = config.api_key.as_deref().context("DeepL needs an api_key")?;
+    // Free-plan keys end in ":fx" and have their own host
+    let base = config.url.clone().unwrap_or_else(|| {
+        if key.ends_with(":fx") { "https://api-free.deepl.com" } else { "https://api.deepl.com" }.to_string()
+    });
+    let data: Value = client.post(format!("{}/v2/translate", base.trim_end_matches('/')))
+        .header("Authorization", format!("DeepL-Auth-Key {}", key))
+        .json(&serde_json::json!({ "text": texts, "target_lang": target.to_uppercase() }))
+        .send().await?
+        .error_for_status()?
+        .json().await?;
+    let translations = data.get("translations").and_then(Value::as_array).context("DeepL returned no translations")?;
+    Ok(translations.iter()
+        .map(|t| t.get("text").and_then(Value::as_str).unwrap_or_default().to_string())
+        .collect())
+}
+
+async fn libretranslate(client: &Client, config: &TranslationConfig, texts: &[&str], target: &str) -> Result<Vec<String>> {
+    let base = config.url.as_deref().context("LibreTranslate needs a url")?;
+    let mut body = serde_json::json!({ "q": texts, "source": "auto", "target": target, "format": "text" });
+    if let Some(key) = &config.api_key {
+        body["api_key"] = Value::from(key.as_str());
+    }
+    let data: Value = client.post(format!("{}/translate", base.trim_end_matches('/')))
+        .json(&body)
+        .send().await?
+        .error_for_status()?
+        .json().await?;
+    // A list in, a list out
+    let translations = data.get("translatedText").and_then(Value::as_array).context("LibreTranslate returned no translations")?;
+    Ok(translations.iter()
+        .map(|t| t.as_str().unwrap_or_default().to_string())
+        .collect())
+}
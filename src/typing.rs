@@ -0,0 +1,74 @@
+use std::time::Instant;
+
+/// Typing practice against the live lyrics ⌨️
+/// The target is always the line being sung; when the song moves on, whatever
+/// was typed for the old line is scored and the input starts over.
+#[derive(Debug, Clone)]
+pub struct TypingPractice {
+    /// Lyric line currently being typed
+    pub line_idx: usize,
+    pub typed: String,
+    started: Instant,
+    /// Totals over finished lines
+    correct: usize,
+    attempted: usize,
+}
+
+impl TypingPractice {
+    pub fn new(line_idx: usize) -> Self {
+        Self { line_idx, typed: String::new(), started: Instant::now(), correct: 0, attempted: 0 }
+    }
+
+    /// The sung line changed: bank the score for `old_target` and move on
+    pub fn advance(&mut self, line_idx: usize, old_target: &str) {
+        self.correct += count_correct(&self.typed, old_target);
+        self.attempted += self.typed.chars().count();
+        self.typed.clear();
+        self.line_idx = line_idx;
+    }
+
+    /// Correct characters and characters typed so far, including the current line
+    fn totals(&self, target: &str) -> (usize, usize) {
+        (
+            self.correct + count_correct(&self.typed, target),
+            self.attempted + self.typed.chars().count(),
+        )
+    }
+
+    /// Share of typed characters that matched, 0-100
+    pub fn accuracy(&self, target: &str) -> u32 {
+        match self.totals(target) {
+            (_, 0) => 100,
+            (correct, attempted) => (correct * 100 / attempted) as u32,
+        }
+    }
+
+    /// Words per minute, counting five correct characters as a word
+    pub fn wpm(&self, target: &str) -> u32 {
+        let minutes = self.started.elapsed().as_secs_f32() / 60.0;
+        if minutes < 1.0 / 60.0 {
+            return 0;
+        }
+        let (correct, _) = self.totals(target);
+        (correct as f32 / 5.0 / minutes) as u32
+    }
+}
+
+/// Position-by-position match, ignoring case
+fn count_correct(typed: &str, target: &str) -> usize {
+    typed.chars()
+        .zip(target.chars())
+        .filter(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+        .count()
+}
+
+/// Per-character verdicts for drawing: Some(true) right, Some(false) wrong, None not typed yet
+pub fn marks(typed: &str, target: &str) -> Vec<(char, Option<bool>)> {
+    let mut typed = typed.chars();
+    target.chars()
+        .map(|t| match typed.next() {
+            Some(c) => (t, Some(c.to_lowercase().eq(t.to_lowercase()))),
+            None => (t, None),
+        })
+        .collect()
+}
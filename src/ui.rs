@@ -1,74 +1,177 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Alignment, Rect},
+    layout::{Constraint, Direction, Layout, Alignment, Margin, Rect},
+    buffer::Buffer,
     style::{Color, Style, Modifier},
     text::{Span, Line, Text},
-    widgets::{block::Title, Block, Paragraph, Borders, BorderType},
+    widgets::{block::Title, Block, Clear, Padding, Paragraph, Borders, BorderType, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
     Frame,
 };
-use crate::app::{App, ArtworkState, LyricsState};
-use crate::player::PlayerState;
+use crate::artwork::Rendered;
+use crate::app::{current_line_idx, App, ArtworkState, Focus, FooterAction, LyricsState, OutputPicker, QueuePane};
+use crate::config::{CardLayout, PowerProfile};
+use crate::glyphs::{self, Glyphs};
+use crate::history::{self, Summary};
+use crate::player::{PlayerError, PlayerState, Repeat};
+use crate::lyrics::{LyricLine, LyricWord};
+use crate::podcast;
+use crate::scrobble;
+use crate::pomodoro::Phase;
+use crate::power;
+use crate::stats;
+use crate::theme::Theme;
+use crate::typing;
 
 
 
+const ARTWORK_FADE_MS: f32 = 300.0;
+// Each info line fades in over this, starting INFO_STAGGER_MS after the one above
+const INFO_FADE_MS: f32 = 250.0;
+const INFO_STAGGER_MS: f32 = 80.0;
+// The lyric highlight hands over to the next line during this last stretch
+const LYRIC_CROSSFADE_MS: f32 = 500.0;
+// How long a toast ("skipped intro") stays on the card border
+const TOAST_MS: u128 = 3000;
+
+/// Linear blend from `from` to `to`, t in 0..=1
+fn blend(from: (u8, u8, u8), to: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// `blend` for theme colors; named/indexed colors just switch halfway
+fn mix(from: Color, to: Color, t: f32) -> Color {
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let (r, g, b) = blend((r1, g1, b1), (r2, g2, b2), t);
+            Color::Rgb(r, g, b)
+        }
+        _ if t < 0.5 => from,
+        _ => to,
+    }
+}
+
+// Monochrome 🔲
+// With `[ui] monochrome` or `NO_COLOR` the finished frame loses its colors,
+// whatever drew them. How bright a color was against the theme decides what
+// stands in for it: text stays as it is, faded colors turn dim, text in the
+// background color disappears and bright backgrounds (labels, badges) become
+// reverse video. The current lyric keeps its bold, ● and underline, the
+// progress bar its blocks, and half-block covers are thresholded to shapes.
+
+/// Perceived brightness, 0..=255; None for the terminal's own colors
+fn luminance(color: Color) -> Option<f32> {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::DarkGray => (85, 85, 85),
+        Color::Gray => (170, 170, 170),
+        Color::Reset | Color::Indexed(_) => return None,
+        _ => (255, 255, 255),
+    };
+    Some(0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32)
+}
+
+fn monochrome(buf: &mut Buffer, theme: &Theme) {
+    let (base, text) = (luminance(theme.base).unwrap_or(0.0), luminance(theme.text).unwrap_or(255.0));
+    // 0 at the background, 1 at the text color (also for light themes)
+    let level = |color: Color| luminance(color).map_or(1.0, |l| (l - base) / (text - base).abs().max(1.0) * (text - base).signum());
+
+    // Cover pixels are lit when brighter than the cover's average
+    let pixels: Vec<f32> = buf.content.iter()
+        .filter(|cell| cell.symbol() == "▀")
+        .filter_map(|cell| Some(luminance(cell.fg)? + luminance(cell.bg)?))
+        .collect();
+    let average = pixels.iter().sum::<f32>() / pixels.len().max(1) as f32 / 2.0;
+
+    for cell in buf.content.iter_mut().filter(|cell| !cell.skip) {
+        if let (Some(top), Some(bottom), "▀") = (luminance(cell.fg), luminance(cell.bg), cell.symbol()) {
+            cell.set_symbol(match (top > average, bottom > average) {
+                (true, true) => "█",
+                (true, false) => "▀",
+                (false, true) => "▄",
+                (false, false) => " ",
+            });
+        } else if level(cell.bg) >= 0.5 && cell.bg != Color::Reset {
+            cell.modifier.insert(Modifier::REVERSED);
+        } else if level(cell.fg) < 0.05 {
+            cell.set_symbol(" ");
+        } else if level(cell.fg) < 0.5 {
+            cell.modifier.insert(Modifier::DIM);
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
 pub fn ui(f: &mut Frame, app: &mut App) {
-    let theme = &app.theme;
+    // Owned copy: helpers below need `app` mutably to register hitboxes
+    let theme = &app.theme.clone();
+    let glyphs = glyphs::get(app.config.ui.glyphs);
     let area = f.area();
 
     // Responsive Logic 🧠
-    // 1. Footer needs 1 line at the bottom always.
+    // 1. Footer needs 1 line at the bottom always. The optional header takes
+    //    1 line at the top, unless the terminal is too short to spare it.
+    let show_header = app.config.ui.header && area.height >= 12;
     let root_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
+            Constraint::Length(if show_header { 1 } else { 0 }), // Header
             Constraint::Min(0),    // Body
-            Constraint::Length(1), // Footer
+            Constraint::Length(if app.config.ui.show_footer { 1 } else { 0 }), // Footer
         ])
         .split(area);
 
-    let body_area = root_layout[0];
-    let footer_area = root_layout[1];
+    let body_area = root_layout[1];
+    let footer_area = root_layout[2];
+    if show_header {
+        draw_header(f, app, root_layout[0]);
+    }
 
     // 2. Decide Layout Direction
-    // - Horizontal: If width >= 100 && user wants lyrics.
+    // - Horizontal: If wide enough && user wants lyrics.
     // - Vertical: Standard.
-    // - Compressed: If Vertical AND height < 40 (Hide Lyrics).
+    // - Compressed: If Vertical AND too short (Hide Lyrics).
+    // Thresholds come from `[layout]`.
     let width = area.width;
     let height = area.height;
     
     // Thresholds
     // Only enable horizontal split if NOT in Tmux (as per user request) AND wide enough.
-    let wide_mode = !app.is_tmux && width >= 90;
+    let layout = &app.config.layout;
+    let wide_mode = !app.is_tmux && width >= layout.side_by_side_min_width;
     
     // Logic:
     // If we want lyrics:
     //    If wide -> Horizontal Split.
     //    If narrow -> Vertical Split.
-    //       If too short (height < 40) -> Hide Lyrics (Compressed).
+    //       If too short -> Hide Lyrics (Compressed).
     // If we don't want lyrics -> Music Card only.
 
     let show_lyrics = app.app_show_lyrics;
     
     let (music_area, lyrics_area, _is_horizontal) = if show_lyrics {
         if wide_mode {
-             // Unified Horizontal Mode: Music Dominant (65%)
+             // Unified Horizontal Mode: Music Dominant (65% by default)
              let chunks = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([
-                    Constraint::Percentage(65), // Bigger Music
+                    Constraint::Percentage(layout.side_by_side_card_percent), // Bigger Music
                     Constraint::Min(10),        // Lyrics
                 ])
                 .split(body_area);
              (chunks[0], Some(chunks[1]), true)
         } else {
             // Vertical Mode
-            if height < 40 {
+            if height < layout.stacked_min_height {
                 // Too short for stack -> Hide Lyrics
                 (body_area, None, false)
             } else {
-                // Stack Mode: Music Top (36), Lyrics Bottom
+                // Stack Mode: Music Top (36 rows by default), Lyrics Bottom
                 let chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Length(36),
+                        Constraint::Length(layout.stacked_card_rows),
                         Constraint::Min(0),
                     ])
                     .split(body_area);
@@ -80,180 +183,200 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         (body_area, None, false)
     };
 
+    // Queue pane (`Q`): the bottom of the lyrics column, or of the card when the
+    // lyrics are hidden, sized to what's queued. Left out when it would crowd them.
+    let queue_rows = app.queue.as_ref().map(|queue| match &queue.tracks {
+        Some(Ok(tracks)) => tracks.len().clamp(1, 8) as u16 + 2,
+        _ => 3,
+    });
+    let split_off = |area: Rect, rows: u16| {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(rows)])
+            .split(area);
+        (chunks[0], chunks[1])
+    };
+    let (music_area, lyrics_area, queue_area) = match (queue_rows, lyrics_area) {
+        (Some(rows), Some(lyrics)) if lyrics.height >= rows + 6 => {
+            let (lyrics, queue) = split_off(lyrics, rows);
+            (music_area, Some(lyrics), Some(queue))
+        }
+        (Some(rows), None) if music_area.height >= rows + 10 => {
+            let (music, queue) = split_off(music_area, rows);
+            (music, None, Some(queue))
+        }
+        _ => (music_area, lyrics_area, None),
+    };
+
+    app.lyrics_pane = lyrics_area.is_some();
+    app.queue_pane = queue_area.is_some();
+    // Once Tab has been used, the pane the arrows act on gets a heavier border
+    let focus = app.show_focus.then(|| app.focused());
+    let border_type = |focused: bool| if focused { BorderType::Thick } else { BorderType::Rounded };
+    if let (Some(area), Some(queue)) = (queue_area, &app.queue) {
+        draw_queue(f, app, queue, area, border_type(focus == Some(Focus::Queue)));
+    }
+
     // --- MUSIC CARD ---
     let music_title = Title::from(Line::from(vec![
         Span::styled(" Vyom ", Style::default().fg(theme.base).bg(theme.blue).add_modifier(Modifier::BOLD))
     ]));
 
-    let music_block = Block::default()
+    let mut music_block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
+        .border_type(border_type(focus == Some(Focus::Card)))
         .title(music_title)
         .title_alignment(Alignment::Center)
         .border_style(Style::default().fg(theme.blue)) 
         .style(Style::default().bg(Color::Reset));
     
+    // Selector strip: only worth showing when there's a choice to make
+    if app.players.len() > 1 {
+        let active = app.track.as_ref().map(|t| t.source.as_str());
+        let mut spans = vec![Span::raw(" ")];
+        for (i, p) in app.players.iter().enumerate() {
+            let icon = glyphs.state(&p.state);
+            let style = if Some(p.name.as_str()) == active {
+                Style::default().fg(theme.base).bg(theme.blue).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.overlay)
+            };
+            spans.push(Span::styled(format!(" {} {} {} ", i + 1, p.name, icon), style));
+            spans.push(Span::raw(" "));
+        }
+        music_block = music_block.title_bottom(Line::from(spans).centered());
+    }
+
+    // Private session, battery saver and failing fetches: flag them here unless the header says so
+    if app.private_session && !show_header {
+        let private = Span::styled(format!(" {} ", glyphs.private), Style::default().fg(theme.overlay));
+        music_block = music_block.title_top(Line::from(private).left_aligned());
+    }
+    if power::current() == PowerProfile::Battery && !show_header {
+        let battery = Span::styled(format!(" {} ", glyphs.battery), Style::default().fg(theme.overlay));
+        music_block = music_block.title_top(Line::from(battery).left_aligned());
+    }
+    if let Some((message, shown_at)) = &app.toast {
+        if shown_at.elapsed().as_millis() < TOAST_MS {
+            let toast = Span::styled(format!(" {} ", message), Style::default().fg(theme.yellow).add_modifier(Modifier::BOLD));
+            music_block = music_block.title_bottom(Line::from(toast).left_aligned());
+        }
+    }
+    if let Some(timer) = &app.pomodoro {
+        let (label, color) = match timer.phase() {
+            Phase::Work => ("work", theme.red),
+            Phase::Break | Phase::LongBreak => ("break", theme.green),
+        };
+        let secs = timer.remaining().as_secs();
+        let text = format!(" {} {} {:02}:{:02} ", glyphs.pomodoro, label, secs / 60, secs % 60);
+        music_block = music_block.title_bottom(Line::from(Span::styled(text, Style::default().fg(color))).right_aligned());
+    }
+    if let Some(loudness) = loudness_span(app).filter(|_| !show_header) {
+        music_block = music_block.title_top(Line::from(vec![Span::raw(" "), loudness, Span::raw(" ")]).right_aligned());
+    }
+    if app.network_down && !show_header {
+        let warning = Span::styled(format!(" {} ", glyphs.warning), Style::default().fg(theme.yellow).add_modifier(Modifier::DIM));
+        music_block = music_block.title_top(Line::from(warning).right_aligned());
+    }
+
     let inner_music_area = music_block.inner(music_area);
     f.render_widget(music_block, music_area);
 
     // Inner Music Layout
-    let m_height = inner_music_area.height;
-    let is_cramped = m_height < 30; 
-
-    let music_constraints = if is_cramped {
-         vec![
-            Constraint::Min(10),    // 0: Artwork (Shrinkable)
-            Constraint::Length(4),  // 1: Info 
-            Constraint::Length(1),  // 2: Gauge
-            Constraint::Length(1),  // 3: Time
-            Constraint::Length(1),  // 4: Controls
-         ]
-    } else {
-        // Normal
-         vec![
-            Constraint::Min(20),    // 0: Artwork (Takes available space!)
-            Constraint::Length(4),  // 1: Info 
-            Constraint::Length(1),  // 2: Gauge
-            Constraint::Length(1),  // 3: Time
-            Constraint::Length(1),  // 4: Spacer
-            Constraint::Length(1),  // 5: Controls
-            Constraint::Length(1),  // 6: Bottom Padding
-        ]
-    };
+    let card = card_layout(app, inner_music_area);
+    // Hidden or not drawn this frame: nothing left to click
+    app.prev_btn = Rect::default();
+    app.play_btn = Rect::default();
+    app.next_btn = Rect::default();
+    app.progress_rect = Rect::default();
+    app.visualizer_rect = Rect::default();
+    draw_artwork(f, app, card.artwork.unwrap_or_default());
 
-    let music_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(music_constraints)
-        .split(inner_music_area);
-
-    // 1. Artwork
-    let _art_idx = 0;
-    
-    // Add 2 lines of padding at top of artwork chunk itself to separate from Border Title (Vyom)
-    let artwork_area = if music_chunks.len() > 0 {
-         let area = music_chunks[0];
-         // Only shrink if we have space, else use as is
-         if area.height > 2 {
-             Layout::default()
-                 .direction(Direction::Vertical)
-                 .constraints([
-                     Constraint::Length(1), // Top Padding
-                     Constraint::Min(1),    // Art
-                 ])
-                 .split(area)[1]
-         } else {
-             area
-         }
-    } else {
-        Rect::default()
-    };
-
-    match &app.artwork {
-        ArtworkState::Loaded(raw_image) => {
-            // Calculate available area for artwork in characters
-            let available_width = artwork_area.width as u32;
-            let available_height = artwork_area.height as u32;
-            
-            let target_width = available_width;
-            let target_height = available_height * 2;
-            
-            if target_width > 0 && target_height > 0 {
-                use image::imageops::FilterType;
-                use image::GenericImageView;
-                
-                // Resize preserving aspect ratio (Triangle for quality)
-                let resized = raw_image.resize(target_width, target_height, FilterType::Triangle);
-                
-                // Vertical centering logic
-                let img_height_subpixels = resized.height();
-                let img_rows = (img_height_subpixels + 1) / 2; // integer ceil
-                
-                let total_rows = available_height;
-                let padding_top = total_rows.saturating_sub(img_rows) / 2;
-                
-                let mut lines = Vec::new();
-                
-                // Add top padding
-                for _ in 0..padding_top {
-                    lines.push(Line::default());
-                }
+    // 2. Info
+    if let (Some(track), None) = (&app.track, &app.player_error) {
+        let icon = |glyph: &str| if app.config.ui.info_icons { format!("{} ", glyph) } else { String::new() };
 
-                for y in (0..img_height_subpixels).step_by(2) {
-                    let mut spans = Vec::new();
-                    for x in 0..resized.width() {
-                        let p1 = resized.get_pixel(x, y);
-                        let p2 = if y + 1 < img_height_subpixels {
-                            resized.get_pixel(x, y + 1)
-                        } else {
-                            p1
-                        };
-
-                        let fg = (p1[0], p1[1], p1[2]);
-                        let bg = (p2[0], p2[1], p2[2]);
-                        
-                        spans.push(Span::styled(
-                            "▀",
-                            Style::default()
-                                .fg(Color::Rgb(fg.0, fg.1, fg.2))
-                                .bg(Color::Rgb(bg.0, bg.1, bg.2))
-                        ));
-                    }
-                    lines.push(Line::from(spans));
-                }
-                
-                let artwork_widget = Paragraph::new(lines)
-                    .alignment(Alignment::Center)
-                    .block(Block::default().style(Style::default().bg(Color::Reset)));
-                f.render_widget(artwork_widget, artwork_area);
+        // Track change: lines fade in top to bottom while the block slides up a row
+        let elapsed = match (app.track_changed_at, app.reduced_motion()) {
+            (Some(t), false) => t.elapsed().as_millis() as f32,
+            _ => f32::MAX,
+        };
+        let fade_in = |line: usize, color: Color| match (theme.base, color) {
+            (Color::Rgb(br, bg, bb), Color::Rgb(r, g, b)) => {
+                let t = ((elapsed - line as f32 * INFO_STAGGER_MS) / INFO_FADE_MS).clamp(0.0, 1.0);
+                let (r, g, b) = blend((br, bg, bb), (r, g, b), t);
+                Color::Rgb(r, g, b)
             }
-        },
-        ArtworkState::Loading => {
-            let p = Paragraph::new("\n\n\n\n\n        Loading...".to_string())
-                .alignment(Alignment::Center)
-                .block(Block::default().style(Style::default().fg(theme.yellow).bg(Color::Reset)));
-             f.render_widget(p, artwork_area);
-        },
-        ArtworkState::Failed | ArtworkState::Idle => {
-            let text = "\n\n\n\n\n        ♪\n    No Album\n      Art".to_string();
-            let p = Paragraph::new(text)
-                .alignment(Alignment::Center)
-                .block(Block::default().style(Style::default().fg(theme.overlay).bg(Color::Reset)));
-            f.render_widget(p, artwork_area);
-        }
-    }
+            _ => color,
+        };
 
-    // 2. Info
-    let info_idx = 1;
-    if let Some(track) = &app.track {
-        let info_text = vec![
-            Line::from(Span::styled(
-                format!("🎵 {}", track.name),
-                Style::default().fg(theme.text).add_modifier(Modifier::BOLD)
-            )),
+        let mut title = vec![Span::styled(
+            format!("{}{}", icon(glyphs.title), track.name),
+            Style::default().fg(fade_in(0, theme.text)).add_modifier(Modifier::BOLD)
+        )];
+        if app.is_loved(track) {
+            title.push(Span::styled(format!(" {}", glyphs.loved), Style::default().fg(fade_in(0, theme.red))));
+        }
+        // Music's star rating, set with 0-5
+        if let Some(stars) = track.rating.map(usize::from) {
+            title.push(Span::styled(format!(" {}", glyphs.star.repeat(stars)), Style::default().fg(fade_in(0, theme.yellow))));
+            title.push(Span::styled(glyphs.star_empty.repeat(5 - stars), Style::default().fg(fade_in(0, theme.overlay))));
+        }
+        let mut info_text = vec![
+            Line::from(title),
             Line::from(vec![
-                Span::raw("🎤 "),
-                Span::styled(&track.artist, Style::default().fg(theme.magenta)), 
+                Span::raw(icon(glyphs.artist)),
+                Span::styled(&track.artist, Style::default().fg(fade_in(1, theme.magenta))), 
             ]),
             Line::from(vec![
-                Span::raw("💿 "),
-                Span::styled(&track.album, Style::default().fg(theme.cyan).add_modifier(Modifier::DIM)), 
+                Span::raw(icon(glyphs.album)),
+                Span::styled(&track.album, Style::default().fg(fade_in(2, theme.cyan)).add_modifier(Modifier::DIM)), 
             ]),
         ];
+
+        // Playing-from line (unless the header already shows it), with Music's play count
+        let mut facts = Vec::new();
+        if let (Some(context), false) = (app.context_name(), show_header) {
+            facts.push(format!("{}{}", icon(glyphs.context), context));
+        }
+        if let Some(plays) = track.play_count {
+            let mut text = format!("{} play{}", plays, if plays == 1 { "" } else { "s" });
+            if let Some(at) = track.last_played {
+                text.push_str(&format!(", last {}", history::fmt_ago(at)));
+            }
+            facts.push(text);
+        }
+        if !facts.is_empty() {
+            info_text.push(Line::from(Span::styled(facts.join(" · "), Style::default().fg(fade_in(3, theme.overlay)))));
+        }
         
         let info = Paragraph::new(info_text)
             .alignment(Alignment::Center)
             .wrap(ratatui::widgets::Wrap { trim: true })
             .block(Block::default().style(Style::default().bg(Color::Reset)));
-        f.render_widget(info, music_chunks[info_idx]);
+        if let Some(mut area) = card.info {
+            if elapsed < INFO_FADE_MS / 2.0 && area.height > 1 {
+                area.y += 1;
+                area.height -= 1;
+            }
+            f.render_widget(info, area);
+        }
 
-        // 3. Gauge
-        let gauge_idx = 2;
-        // Check if we have enough chunks. If cramped, we don't have spacers.
-        // We used indices 0..4 for cramped.
-        // music_chunks length check? 
-        
-        // Helper to safely get chunk
-        if gauge_idx < music_chunks.len() {
+        // 3. Visualizer + Gauge
+        if let Some(area) = card.visualizer {
+            draw_visualizer(f, app, area);
+            app.visualizer_rect = area;
+        }
+
+        // Plugin widget sits in the spacer row (no room for it in smaller layouts)
+        if let (Some(text), Some(area)) = (&app.plugin_widget, card.widget) {
+            let widget = Paragraph::new(text.as_str())
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(theme.overlay));
+            f.render_widget(widget, area);
+        }
+
+        if let Some(gauge_row) = card.gauge {
              let gauge_area_rect = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([
@@ -261,28 +384,36 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                     Constraint::Percentage(80), 
                     Constraint::Percentage(10), 
                 ])
-                .split(music_chunks[gauge_idx])[1];
+                .split(gauge_row)[1];
 
-            let ratio = if track.duration_ms > 0 {
-                track.position_ms as f64 / track.duration_ms as f64
-            } else {
-                0.0
-            };
-            
             let width = gauge_area_rect.width as usize;
-            let occupied_width = (width as f64 * ratio.min(1.0).max(0.0)) as usize;
-            let fill_style = Style::default().fg(theme.magenta);
+            let occupied_width = (width as f64 * app.clock.progress()) as usize;
+            let fill_style = |i: usize| Style::default().fg(album_gradient(app, i, width).unwrap_or(theme.magenta));
             let empty_style = Style::default().fg(theme.surface);
             
+            // Chapter boundaries as tick columns (the first chapter starts at 0, no tick)
+            let duration_ms = app.clock.duration_ms();
+            let chapter_cols: Vec<usize> = if duration_ms > 0 {
+                app.chapters.iter()
+                    .filter(|c| c.start_ms > 0)
+                    .map(|c| (c.start_ms as f64 / duration_ms as f64 * width as f64) as usize)
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let tick_style = Style::default().fg(theme.yellow);
+
             let mut bar_spans: Vec<Span> = Vec::with_capacity(width);
             for i in 0..width {
-                 if i < occupied_width {
+                 if chapter_cols.contains(&i) {
+                    bar_spans.push(Span::styled("┃", tick_style));
+                 } else if i < occupied_width {
                     if i >= occupied_width.saturating_sub(1) {
-                        bar_spans.push(Span::styled("▓", fill_style));
+                        bar_spans.push(Span::styled("▓", fill_style(i)));
                     } else if i >= occupied_width.saturating_sub(2) {
-                        bar_spans.push(Span::styled("▒", fill_style));
+                        bar_spans.push(Span::styled("▒", fill_style(i)));
                     } else {
-                        bar_spans.push(Span::styled("█", fill_style));
+                        bar_spans.push(Span::styled("█", fill_style(i)));
                     }
                 } else {
                     bar_spans.push(Span::styled("░", empty_style));
@@ -297,61 +428,46 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         }
 
         // 4. Time
-        let time_idx = 3;
-        if time_idx < music_chunks.len() {
+        if let Some(time_area) = card.time {
+            let (position_ms, duration_ms) = (app.clock.position_ms(), app.clock.duration_ms());
             let time_str = format!(
                 "{:02}:{:02} / {:02}:{:02}",
-                track.position_ms / 60000,
-                (track.position_ms % 60000) / 1000,
-                track.duration_ms / 60000,
-                (track.duration_ms % 60000) / 1000
+                position_ms / 60000,
+                (position_ms % 60000) / 1000,
+                duration_ms / 60000,
+                (duration_ms % 60000) / 1000
             );
+            let time_str = match app.current_chapter() {
+                Some(chapter) => format!("{} · {}", time_str, chapter.title),
+                None => time_str,
+            };
             let time_label = Paragraph::new(time_str)
                 .alignment(Alignment::Center)
                 .style(Style::default().fg(theme.overlay));
-            f.render_widget(time_label, music_chunks[time_idx]);
+            f.render_widget(time_label, time_area);
         }
         
         // 5. Controls
-        // If cramped: index 4. If normal: index 5 (index 4 is spacer)
-        let controls_idx = if is_cramped { 4 } else { 5 };
-        
-        if controls_idx < music_chunks.len() {
-            let play_icon = if track.state == PlayerState::Playing { "⏸" } else { "▶" };
-            let btn_style = Style::default().fg(theme.text).add_modifier(Modifier::BOLD);
-            
-            let prev_str = "   ⏮   ";
-            let next_str = "   ⏭   ";
-            let play_str = format!("   {}   ", play_icon); 
-            
-            let controls_text = Line::from(vec![
-                Span::styled(prev_str, btn_style),
-                Span::raw("   "), 
-                Span::styled(play_str, btn_style),
-                Span::raw("   "), 
-                Span::styled(next_str, btn_style),
-            ]);
-            
-            let controls = Paragraph::new(controls_text)
-                .alignment(Alignment::Center)
-                .block(Block::default().style(Style::default().bg(Color::Reset)));
-            
-            f.render_widget(controls, music_chunks[controls_idx]);
-
-            let area = music_chunks[controls_idx];
-            let mid_x = area.x + area.width / 2;
-            let y = area.y;
-            
-            app.prev_btn = ratatui::layout::Rect::new(mid_x.saturating_sub(13), y, 7, 1);
-            app.play_btn = ratatui::layout::Rect::new(mid_x.saturating_sub(3), y, 7, 1);
-            app.next_btn = ratatui::layout::Rect::new(mid_x + 7, y, 7, 1);
+        if let Some(area) = card.controls {
+            let playing = track.state == PlayerState::Playing;
+            draw_controls(f, app, area, playing, card.compact);
         }
 
     } else {
-        // IDLE STATE
-        let t = Paragraph::new("Music Paused / Not Running")
+        // IDLE STATE (or a running player we couldn't read)
+        let message = match &app.player_error {
+            Some(PlayerError::PermissionDenied(player)) => format!(
+                "Vyom isn't allowed to control {}.\nAllow it in System Settings → Privacy & Security → Automation.",
+                player
+            ),
+            Some(PlayerError::Errored(player, msg)) => format!("{} is running but didn't answer:\n{}", player, msg),
+            None => "Music Paused / Not Running".to_string(),
+        };
+        let color = if app.player_error.is_some() { theme.red } else { theme.text };
+        let t = Paragraph::new(message)
             .alignment(Alignment::Center)
-            .style(Style::default().fg(theme.text));
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(color));
         
         // Just center it in available space
         f.render_widget(t, inner_music_area);
@@ -359,94 +475,197 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     
     // --- LYRICS CARD ---
     if let Some(lyrics_area_rect) = lyrics_area {
+        // Unsynced lyrics are marked with where they came from
+        let lyrics_label = match &app.lyrics {
+            LyricsState::Unsynced(_, source) => format!(" Lyrics · {} (unsynced) ", source),
+            LyricsState::ShowNotes(_) => " Show Notes ".to_string(),
+            _ if app.typing.is_some() => format!(" Lyrics · {} typing practice ", glyphs.typing),
+            _ if app.loop_line.is_some() => format!(" Lyrics · {} looping line ", glyphs.looping),
+            _ if app.scroll_lock => format!(" Lyrics · {} reading ahead ", glyphs.lock),
+            _ => " Lyrics ".to_string(),
+        };
         let lyrics_title = Title::from(Line::from(vec![
-            Span::styled(" Lyrics ", Style::default().fg(theme.base).bg(theme.magenta).add_modifier(Modifier::BOLD))
+            Span::styled(lyrics_label, Style::default().fg(theme.base).bg(theme.magenta).add_modifier(Modifier::BOLD))
         ]));
 
-        let credits_title = Line::from(vec![
-            Span::styled(" ~ by syr3x </3 ", Style::default()
-                .bg(Color::Rgb(235, 111, 146)) // #eb6f92
-                .fg(theme.base) 
-                .add_modifier(Modifier::BOLD | Modifier::ITALIC))
-        ]);
+        // An open search takes over the bottom border
+        let credits_title = if let Some(search) = &app.lyrics_search {
+            let cursor = if search.editing { "▏" } else { "" };
+            let count = if search.matches.is_empty() {
+                " no matches ".to_string()
+            } else {
+                format!(" {}/{} ", search.current + 1, search.matches.len())
+            };
+            Line::from(vec![
+                Span::styled(format!(" /{}{} ", search.query, cursor), Style::default().fg(theme.base).bg(theme.yellow).add_modifier(Modifier::BOLD)),
+                Span::styled(count, Style::default().fg(theme.yellow)),
+            ])
+        } else {
+            Line::from(vec![
+                Span::styled(" ~ by syr3x </3 ", Style::default()
+                    .bg(Color::Rgb(235, 111, 146)) // #eb6f92
+                    .fg(theme.base) 
+                    .add_modifier(Modifier::BOLD | Modifier::ITALIC))
+            ])
+        };
 
-        let lyrics_block = Block::default()
+        let mut lyrics_block = Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
+            .border_type(border_type(matches!(focus, Some(Focus::Lyrics | Focus::Search))))
             .title(lyrics_title)
             .title_alignment(Alignment::Center)
             .title_bottom(credits_title)
             .border_style(Style::default().fg(theme.magenta))
             .style(Style::default().bg(Color::Reset));
         
+        // Language badge: dim when it's the user's own
+        if let Some(code) = app.lyrics_language {
+            let style = if app.lyrics_in_user_language() {
+                Style::default().fg(theme.overlay)
+            } else {
+                Style::default().fg(theme.magenta).add_modifier(Modifier::BOLD)
+            };
+            lyrics_block = lyrics_block.title_top(Line::from(Span::styled(format!(" {} ", code.to_uppercase()), style)).right_aligned());
+        }
+
         let inner_lyrics_area = lyrics_block.inner(lyrics_area_rect);
         f.render_widget(lyrics_block, lyrics_area_rect);
 
         app.lyrics_hitboxes.clear(); 
+        // Synced lyrics fade out 8 lines from the center, so a page is at most 17 lines
+        app.lyrics_page = (inner_lyrics_area.height as usize).clamp(1, 17);
         
         match &app.lyrics {
+            LyricsState::Loaded(lyrics) if app.typing.is_some() => draw_typing(f, app, lyrics, inner_lyrics_area),
             LyricsState::Loaded(lyrics) => {
                 let height = inner_lyrics_area.height as usize;
-                let track_ms = app.track.as_ref().map(|t| t.position_ms).unwrap_or(0);
+                let track_ms = app.clock.position_ms();
                 
-                let current_idx = lyrics.iter()
-                   .position(|l| l.timestamp_ms > track_ms)
-                   .map(|i| if i > 0 { i - 1 } else { 0 })
-                   .unwrap_or(lyrics.len().saturating_sub(1));
+                let current_idx = current_line_idx(lyrics, track_ms);
 
-                // Absolute Centering Logic (Virtual Window)
-                
-                let mut lines = Vec::new();
+                // Karaoke crossfade toward the next line, on the interpolated position
+                let crossfade = match (lyrics.get(current_idx + 1), app.reduced_motion() || app.loop_line.is_some()) {
+                    (Some(next), false) => {
+                        let fade_start = next.timestamp_ms as f32 - LYRIC_CROSSFADE_MS;
+                        ((track_ms as f32 - fade_start) / LYRIC_CROSSFADE_MS).clamp(0.0, 1.0)
+                    }
+                    _ => 0.0,
+                };
+                let active_color = mix(theme.green, theme.text, crossfade);
+
+                // Absolute Centering Logic (Virtual Window): the centered line sits on the
+                // middle row, the ones before it fill upward and the rest downward. Each
+                // line brings what goes under it (progress underline, romanization, translation).
                 let half_height = height / 2;
-                let center_idx = app.lyrics_offset.unwrap_or(current_idx);
-
-                for row in 0..height {
-                     let dist_from_center: isize = (row as isize - half_height as isize).abs();
-                     let target_idx_isize = (center_idx as isize) - (half_height as isize) + (row as isize);
-                     
-                     // Visibility Radius: 8 lines above/below
-                     if dist_from_center <= 8 && target_idx_isize >= 0 && target_idx_isize < lyrics.len() as isize {
-                         let idx = target_idx_isize as usize;
-                         let line = &lyrics[idx];
-                         
-                         let is_active = idx == current_idx;
-                         
-                         // Gradient Logic 🎨
-                         let style = if is_active {
-                            // Center: Active Color
-                            Style::default().add_modifier(Modifier::BOLD).fg(theme.green)
-                         } else {
-                            // Gradient based on distance (1..8)
-                            match dist_from_center {
-                                1..=2 => Style::default().fg(theme.text),                                // Bright
-                                3..=4 => Style::default().fg(theme.text).add_modifier(Modifier::DIM),    // Semi-Bright
-                                5..=6 => Style::default().fg(theme.overlay),                             // Dim
-                                7..=8 => Style::default().fg(theme.surface).add_modifier(Modifier::DIM), // Dark
-                                _ => Style::default().fg(theme.base),
-                            }
-                         };
-
-                        let prefix = if is_active { "● " } else { "  " };
-                        let prefix_span = if is_active {
-                            Span::styled(prefix, Style::default().fg(theme.green))
-                        } else {
-                             Span::styled(prefix, style)
-                        };
-
-                        lines.push(Line::from(vec![
-                            prefix_span,
-                            Span::styled(line.text.clone(), style)
+                let center_idx = app.lyrics_offset.unwrap_or(current_idx).min(lyrics.len().saturating_sub(1));
+                let secondary = !app.translations_hidden && !app.lyrics_in_user_language();
+
+                let block = |idx: usize| -> Vec<Line<'static>> {
+                    let line = &lyrics[idx];
+                    let is_active = idx == current_idx;
+                    let dist_from_center = idx.abs_diff(center_idx);
+
+                    // Gradient Logic 🎨
+                    let style = if is_active {
+                        // Center: Active Color
+                        Style::default().add_modifier(Modifier::BOLD).fg(active_color)
+                    } else if idx == current_idx + 1 && crossfade > 0.0 {
+                        // Incoming line warms up as the active one cools down
+                        Style::default().fg(mix(theme.text, theme.green, crossfade))
+                    } else {
+                        // Gradient based on distance (1..8)
+                        match dist_from_center {
+                            0..=2 => Style::default().fg(theme.text),                                // Bright
+                            3..=4 => Style::default().fg(theme.text).add_modifier(Modifier::DIM),    // Semi-Bright
+                            5..=6 => Style::default().fg(theme.overlay),                             // Dim
+                            7..=8 => Style::default().fg(theme.surface).add_modifier(Modifier::DIM), // Dark
+                            _ => Style::default().fg(theme.base),
+                        }
+                    };
+                    // Choruses stand out from the verses around them
+                    let style = if !is_active && app.in_chorus(line.timestamp_ms) {
+                        style.add_modifier(Modifier::BOLD).remove_modifier(Modifier::DIM)
+                    } else {
+                        style
+                    };
+
+                    let prefix = if is_active { "● " } else { "  " };
+                    let prefix_span = if is_active {
+                        Span::styled(prefix, Style::default().fg(active_color))
+                    } else {
+                        Span::styled(prefix, style)
+                    };
+
+                    // Lines with word timings sweep across the words as they're sung
+                    let line_style = search_style(app, idx, style);
+                    let end_ms = lyrics.get(idx + 1).map(|l| l.timestamp_ms).unwrap_or(app.clock.duration_ms());
+                    let mut spans = vec![prefix_span];
+                    match &line.words {
+                        Some(words) if is_active && line_style == style && !words.is_empty() => {
+                            let unsung = Style::default().add_modifier(Modifier::BOLD).fg(theme.text);
+                            // Without colors the words still to come are the dim ones
+                            let unsung = if app.monochrome() { unsung.add_modifier(Modifier::DIM) } else { unsung };
+                            spans.extend(karaoke_spans(&line.text, words, end_ms, track_ms, style, unsung));
+                        }
+                        _ => spans.push(Span::styled(line.text.clone(), line_style)),
+                    }
+                    let mut block = vec![Line::from(spans)];
+
+                    // Thin underline filling up over the active line's time window
+                    if is_active && app.config.ui.line_progress {
+                        let window = end_ms.saturating_sub(line.timestamp_ms).max(1) as f32;
+                        let progress = (track_ms.saturating_sub(line.timestamp_ms) as f32 / window).clamp(0.0, 1.0);
+                        let width = (Span::raw(line.text.as_str()).width() + Span::raw(prefix).width()).min(inner_lyrics_area.width as usize);
+                        let filled = (width as f32 * progress).round() as usize;
+                        block.push(Line::from(vec![
+                            Span::styled("▔".repeat(filled), Style::default().fg(active_color)),
+                            Span::styled("▔".repeat(width - filled), Style::default().fg(theme.surface)),
                         ]));
-                        
-                        let line_y = inner_lyrics_area.y + row as u16;
-                        let hitbox = Rect::new(inner_lyrics_area.x, line_y, inner_lyrics_area.width, 1);
-                        app.lyrics_hitboxes.push((hitbox, line.timestamp_ms));
-
-                     } else {
-                         // Hidden / Out of bounds
-                         lines.push(Line::from(""));
-                     }
+                    }
+
+                    // Romanization and translation, dim under every line (unless `t` hid them)
+                    if secondary {
+                        let color = if dist_from_center <= 4 { theme.overlay } else { theme.surface };
+                        if let Some(romanization) = &line.romanization {
+                            block.push(Line::from(Span::styled(romanization.clone(), Style::default().fg(color))));
+                        }
+                        if let Some(translation) = &line.translation {
+                            block.push(Line::from(Span::styled(
+                                translation.clone(),
+                                Style::default().fg(color).add_modifier(Modifier::ITALIC),
+                            )));
+                        }
+                    }
+                    block
+                };
+
+                // Visibility Radius: 8 lines above/below
+                let mut above = Vec::new();
+                let mut rows_above = 0;
+                for idx in (center_idx.saturating_sub(8)..center_idx).rev() {
+                    let lines = block(idx);
+                    if rows_above + lines.len() > half_height {
+                        break;
+                    }
+                    rows_above += lines.len();
+                    above.push((idx, lines));
+                }
+                let mut lines = vec![Line::from(""); half_height - rows_above];
+                let mut placed = Vec::new();
+                let below = (center_idx..lyrics.len().min(center_idx + 9)).map(|idx| (idx, block(idx)));
+                for (idx, block) in above.into_iter().rev().chain(below) {
+                    if lines.len() >= height {
+                        break;
+                    }
+                    placed.push((lyrics[idx].timestamp_ms, lines.len() as u16));
+                    lines.extend(block);
                 }
+                for (timestamp_ms, row) in placed {
+                    let hitbox = Rect::new(inner_lyrics_area.x, inner_lyrics_area.y + row, inner_lyrics_area.width, 1);
+                    app.lyrics_hitboxes.push((hitbox, timestamp_ms));
+                }
+                lines.truncate(height);
+                app.lyrics_hitboxes.retain(|(rect, _)| rect.y < inner_lyrics_area.bottom());
                 
                 let lyrics_widget = Paragraph::new(lines)
                     .alignment(Alignment::Center)
@@ -455,8 +674,60 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                     
                 f.render_widget(lyrics_widget, inner_lyrics_area);
             },
+            LyricsState::Unsynced(plain, _) => {
+                // No timings: keep the estimated position centered, no active highlight
+                let height = inner_lyrics_area.height as usize;
+                let half_height = height / 2;
+                let center_idx = app.lyrics_offset
+                    .or_else(|| app.current_lyric_idx())
+                    .unwrap_or(0);
+
+                let lines: Vec<Line> = (0..height).map(|row| {
+                    let idx = center_idx as isize - half_height as isize + row as isize;
+                    let dist_from_center = (row as isize - half_height as isize).abs();
+                    match usize::try_from(idx).ok().and_then(|i| Some((i, plain.get(i)?))) {
+                        Some((i, text)) => {
+                            let style = if dist_from_center <= 4 {
+                                Style::default().fg(theme.text)
+                            } else {
+                                Style::default().fg(theme.overlay)
+                            };
+                            Line::from(Span::styled(text.clone(), search_style(app, i, style)))
+                        }
+                        None => Line::from(""),
+                    }
+                }).collect();
+
+                let lyrics_widget = Paragraph::new(lines)
+                    .alignment(Alignment::Center)
+                    .block(Block::default().style(Style::default().bg(Color::Reset)));
+                f.render_widget(lyrics_widget, inner_lyrics_area);
+            },
+            LyricsState::ShowNotes(notes) => {
+                // Top-aligned prose from the scroll position down
+                let start = app.lyrics_offset.unwrap_or(0).min(notes.len());
+                let lines: Vec<Line> = notes[start..].iter().enumerate()
+                    .map(|(i, text)| Line::from(Span::styled(text.clone(), search_style(app, start + i, Style::default().fg(theme.text)))))
+                    .collect();
+                let notes_widget = Paragraph::new(lines)
+                    .wrap(Wrap { trim: true })
+                    .block(Block::default().padding(Padding::horizontal(1)).style(Style::default().bg(Color::Reset)));
+                f.render_widget(notes_widget, inner_lyrics_area);
+            },
             LyricsState::Loading => {
-                let text = Paragraph::new(Text::styled("\nFetching Lyrics...", Style::default().fg(theme.yellow)))
+                // Spinner over shimmering stand-ins for the lines to come
+                let width = inner_lyrics_area.width.saturating_sub(4);
+                let mut lines = vec![
+                    Line::from(""),
+                    Line::from(Span::styled(format!("{} Fetching lyrics", spinner(app)), Style::default().fg(theme.yellow))),
+                    Line::from(""),
+                ];
+                for (row, share) in [0.6, 0.8, 0.45, 0.7, 0.55, 0.75, 0.4, 0.65].into_iter().enumerate() {
+                    let bar = (width as f32 * share) as u16;
+                    lines.push(Line::from(shimmer(app, bar, row as u16)));
+                    lines.push(Line::from(""));
+                }
+                let text = Paragraph::new(lines)
                     .alignment(Alignment::Center)
                     .block(Block::default().style(Style::default().bg(Color::Reset)));
                 f.render_widget(text, inner_lyrics_area);
@@ -468,58 +739,1212 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 f.render_widget(text, inner_lyrics_area);
             },
             LyricsState::Failed(err) => {
-                 let text = Paragraph::new(Text::styled(format!("\nLyrics Failed: {}", err), Style::default().fg(theme.red)))
+                 let message = if app.network_down {
+                     "\nNo connection, retrying when it's back".to_string()
+                 } else {
+                     format!("\nLyrics Failed: {}", err)
+                 };
+                 let text = Paragraph::new(Text::styled(message, Style::default().fg(theme.red)))
                     .alignment(Alignment::Center)
                      .block(Block::default().style(Style::default().bg(Color::Reset)));
                  f.render_widget(text, inner_lyrics_area);
             },
             LyricsState::Idle | LyricsState::NotFound => {
-                let no_lyrics = Paragraph::new(Text::styled("\nNo Lyrics Found", Style::default().fg(theme.overlay)))
+                let message = if app.track.as_ref().is_some_and(podcast::is_episode) { "\nNo Show Notes Found" } else { "\nNo Lyrics Found" };
+                let no_lyrics = Paragraph::new(Text::styled(message, Style::default().fg(theme.overlay)))
                     .alignment(Alignment::Center)
                      .block(Block::default().style(Style::default().bg(Color::Reset)));
                  f.render_widget(no_lyrics, inner_lyrics_area);
             }
         }
+
+        // Slim scrollbar over the right border: where the view sits in the whole song
+        let total = app.lyrics_len();
+        if total > 1 {
+            let position = app.lyrics_offset.or_else(|| app.current_lyric_idx()).unwrap_or(0);
+            draw_scrollbar(f, &app.theme, lyrics_area_rect, total, position);
+        }
     }
 
     // --- FOOTER ---
-    let desc_style = Style::default().fg(theme.overlay);
+    app.footer_btns.clear();
+    app.volume_rect = Rect::default();
     
-    // Split footer into 2 chunks: Left (Controls) and Right (Volume)
-    let footer_chunks = Layout::default()
+    // A user template replaces the keybinding hints entirely
+    if let Some(template) = &app.config.ui.footer {
+        let footer = Paragraph::new(render_template(template, app))
+            .alignment(Alignment::Center)
+            .block(Block::default().style(Style::default().bg(Color::Reset)));
+        f.render_widget(footer, footer_area);
+    } else {
+        // Split footer into 2 chunks: Left (Controls) and Right (Volume)
+        let footer_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(20),    // Left: Main Controls
+                Constraint::Length(12), // Right: Volume Control
+            ])
+            .split(footer_area);
+
+        let desc_style = Style::default().fg(theme.overlay);
+        let key_style = |color: Color| Style::default().fg(color).add_modifier(Modifier::BOLD);
+        // Hints follow `[keys]`
+        let keymap = app.keymap.clone();
+        let key = |action: &str| keymap.label(action);
+        let left_hints = [
+            (Span::styled(format!(" {} ", key("quit")), key_style(theme.red)), Some(FooterAction::Quit)),
+            (Span::styled("Exit", desc_style), Some(FooterAction::Quit)),
+            (Span::raw("   "), None),
+            (Span::styled(format!(" {} ", key("next")), key_style(theme.blue)), Some(FooterAction::Next)),
+            (Span::styled("Next", desc_style), Some(FooterAction::Next)),
+            (Span::raw("   "), None),
+            (Span::styled(format!(" {} ", key("prev")), key_style(theme.blue)), Some(FooterAction::Prev)),
+            (Span::styled("Prev", desc_style), Some(FooterAction::Prev)),
+            (Span::raw("   "), None),
+            (Span::styled(format!(" {} ", key("play_pause")), key_style(theme.green)), Some(FooterAction::PlayPause)),
+            (Span::styled("Play/Pause", desc_style), Some(FooterAction::PlayPause)),
+        ];
+        draw_hints(f, app, footer_chunks[0], &left_hints);
+
+        // "+" and "-" are separate targets
+        let right_hints = [
+            (Span::styled(format!(" {}", key("volume_up")), key_style(theme.yellow)), Some(FooterAction::VolumeUp)),
+            (Span::styled("/", key_style(theme.yellow)), None),
+            (Span::styled(format!("{} ", key("volume_down")), key_style(theme.yellow)), Some(FooterAction::VolumeDown)),
+            (Span::styled("Vol ", desc_style), None),
+        ];
+        draw_hints(f, app, footer_chunks[1], &right_hints);
+        app.volume_rect = footer_chunks[1];
+    }
+
+    app.party_cards.clear();
+    if app.party_view {
+        draw_party_view(f, app, body_area);
+    } else if let Some((x, y)) = app.hover {
+        draw_seek_tooltip(f, app, x, y);
+    }
+
+    // Lightbox (`z`): the cover alone, as big as the terminal allows
+    if app.lightbox {
+        f.render_widget(Clear, area);
+        draw_artwork(f, app, area);
+    }
+
+    if let Some(recap) = &app.recap {
+        draw_recap(f, app, recap);
+    }
+
+    if let Some(picker) = &app.output_picker {
+        draw_output_picker(f, app, picker);
+    }
+
+    if app.debug_overlay {
+        draw_debug_overlay(f, app);
+    }
+
+    if let Some(position_ms) = app.resume_prompt {
+        draw_resume_prompt(f, app, position_ms);
+    }
+
+    if app.confirm_quit {
+        draw_quit_prompt(f, app);
+    }
+
+    // --- ONBOARDING (on top of everything) ---
+    if let Some(player) = app.permission_dialog_player() {
+        draw_permission_dialog(f, app, &player);
+    }
+
+    if app.monochrome() {
+        monochrome(f.buffer_mut(), theme);
+    }
+
+    // A graphics-protocol cover goes on after the frame, over cells ratatui
+    // leaves alone. It would sit on top of any popup, so those take it down.
+    let popup = app.party_view || app.recap.is_some() || app.output_picker.is_some() || app.debug_overlay
+        || app.resume_prompt.is_some() || app.confirm_quit || app.permission_dialog_player().is_some();
+    if popup {
+        app.artwork_placement = None;
+    }
+    if let Some(placement) = app.artwork_placement.map(|rect| rect.intersection(area)) {
+        for position in placement.positions() {
+            f.buffer_mut()[position].set_skip(true);
+        }
+    }
+}
+
+/// The cover in `area`: half blocks drawn here, or a graphics-protocol image
+/// placed by main after the frame. Asks for a render at this size.
+fn draw_artwork(f: &mut Frame, app: &mut App, artwork_area: Rect) {
+    let theme = &app.theme.clone();
+    // The lines themselves are rendered off-thread (see `artwork::render`)
+    let art_size = (artwork_area.width, artwork_area.height);
+    let has_image = matches!(app.artwork, ArtworkState::Loaded(_));
+    app.artwork_wanted = (has_image && art_size.0 > 0 && art_size.1 > 0).then_some(art_size);
+    app.artwork_placement = None;
+
+    match &app.artwork {
+        ArtworkState::Loaded(_) => {
+            // Until the first render for this cover arrives the pane stays empty;
+            // after a resize the old size shows (clipped) for a frame or two
+            if let Some((_, Rendered::Graphic(graphic))) = &app.artwork_rendered {
+                app.artwork_placement = Some(graphic.area_in(artwork_area));
+            } else if let Some((_, Rendered::Lines(rendered))) = &app.artwork_rendered {
+                // Fade-in: blend from the theme base toward the real pixels
+                let fade = if app.reduced_motion() {
+                    1.0
+                } else {
+                    app.artwork_loaded_at
+                        .map(|t| (t.elapsed().as_millis() as f32 / ARTWORK_FADE_MS).min(1.0))
+                        .unwrap_or(1.0)
+                };
+                let base = match theme.base {
+                    Color::Rgb(..) => theme.base,
+                    _ => Color::Rgb(0, 0, 0),
+                };
+                let lines = if fade < 1.0 {
+                    let fade_span = |span: &Span<'static>| {
+                        let fg = span.style.fg.map(|c| mix(base, c, fade));
+                        let bg = span.style.bg.map(|c| mix(base, c, fade));
+                        Span::styled(span.content.clone(), Style { fg, bg, ..span.style })
+                    };
+                    rendered.iter()
+                        .map(|line| Line::from(line.spans.iter().map(fade_span).collect::<Vec<_>>()))
+                        .collect()
+                } else {
+                    rendered.clone()
+                };
+
+                let artwork_widget = Paragraph::new(lines)
+                    .alignment(Alignment::Center)
+                    .block(Block::default().style(Style::default().bg(Color::Reset)));
+                f.render_widget(artwork_widget, artwork_area);
+            }
+        },
+        ArtworkState::Loading => {
+            // A shimmering square where the cover will go, spinner in the middle
+            let cols = artwork_area.width.min(artwork_area.height * 2);
+            let rows = cols / 2;
+            let square = Rect::new(
+                artwork_area.x + (artwork_area.width - cols) / 2,
+                artwork_area.y + (artwork_area.height - rows) / 2,
+                cols,
+                rows,
+            );
+            let placeholder: Vec<Line> = (0..rows).map(|row| Line::from(shimmer(app, cols, row))).collect();
+            f.render_widget(Paragraph::new(placeholder), square);
+            if rows > 0 {
+                let middle = Rect { y: square.y + rows / 2, height: 1, ..square };
+                let label = Paragraph::new(Span::styled(spinner(app), Style::default().fg(theme.yellow)))
+                    .alignment(Alignment::Center);
+                f.render_widget(label, middle);
+            }
+        },
+        ArtworkState::Failed | ArtworkState::Idle => {
+            let text = "\n\n\n\n\n        ♪\n    No Album\n      Art".to_string();
+            let p = Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .block(Block::default().style(Style::default().fg(theme.overlay).bg(Color::Reset)));
+            f.render_widget(p, artwork_area);
+        }
+    }
+}
+
+/// Current frame of the loading spinner (held still with reduced motion)
+fn spinner(app: &App) -> &'static str {
+    let frames = glyphs::get(app.config.ui.glyphs).spinner;
+    let frame = if app.reduced_motion() { 0 } else { app.ticks as usize / 2 };
+    frames[frame % frames.len()]
+}
+
+/// Placeholder bar `width` cells wide on `row`, with a highlight sweeping
+/// diagonally across it as the ticks go by (flat with reduced motion)
+fn shimmer(app: &App, width: u16, row: u16) -> Vec<Span<'static>> {
+    const PERIOD: i64 = 48;
+    const BAND: f32 = 6.0;
+    let theme = &app.theme;
+    let still = app.reduced_motion();
+    (0..width).map(|col| {
+        let offset = (col as i64 + row as i64 * 2 - app.ticks as i64).rem_euclid(PERIOD);
+        let distance = offset.min(PERIOD - offset) as f32;
+        let glow = if still { 0.0 } else { (1.0 - distance / BAND).max(0.0) * 0.6 };
+        Span::styled(" ", Style::default().bg(mix(theme.surface, theme.overlay, glow)))
+    }).collect()
+}
+
+/// Every running player side by side, over the card and lyrics. Number keys
+/// or a click switch the main view to one of them.
+fn draw_party_view(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = &app.theme.clone();
+    let glyphs = glyphs::get(app.config.ui.glyphs);
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Line::from(Span::styled(" Party View ", Style::default().fg(theme.magenta).add_modifier(Modifier::BOLD))).centered())
+        .title_bottom(Line::from(Span::styled(" 1-9 or click to switch · Esc to close ", Style::default().fg(theme.overlay))).centered())
+        .border_style(Style::default().fg(theme.magenta))
+        .style(Style::default().bg(Color::Reset));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.players.is_empty() {
+        let empty = Paragraph::new(Text::styled("\nNo players running", Style::default().fg(theme.overlay)))
+            .alignment(Alignment::Center);
+        f.render_widget(empty, inner);
+        return;
+    }
+
+    // One card per player, side by side when they fit, else stacked (tmux sidebar)
+    let count = app.players.len() as u16;
+    let cards = if inner.width / count >= 20 {
+        let card_height = inner.height.min(7);
+        let row = Rect::new(inner.x, inner.y + (inner.height - card_height) / 2, inner.width, card_height);
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, count as u32); count as usize])
+            .split(row)
+    } else {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Max(6); count as usize])
+            .split(inner)
+    };
+
+    let active = app.track.as_ref().map(|t| t.source.clone());
+    for (i, (p, card)) in app.players.iter().zip(cards.iter()).enumerate() {
+        let is_active = Some(&p.name) == active.as_ref();
+        let color = if is_active { theme.blue } else { theme.surface };
+        let card_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(Line::from(format!(" {} {} {} ", i + 1, p.name, glyphs.state(&p.state))).centered())
+            .border_style(Style::default().fg(color));
+
+        let text = match &p.title {
+            Some(title) => vec![
+                Line::default(),
+                Line::from(Span::styled(title.clone(), Style::default().fg(theme.text).add_modifier(Modifier::BOLD))),
+                Line::from(Span::styled(p.artist.clone().unwrap_or_default(), Style::default().fg(theme.magenta))),
+            ],
+            None => vec![Line::default(), Line::from(Span::styled("Nothing loaded", Style::default().fg(theme.overlay)))],
+        };
+        f.render_widget(
+            Paragraph::new(text).alignment(Alignment::Center).wrap(Wrap { trim: true }).block(card_block),
+            *card,
+        );
+        app.party_cards.push((*card, p.name.clone()));
+    }
+}
+
+/// Floating timestamp above the pointer showing where a click would seek
+fn draw_seek_tooltip(f: &mut Frame, app: &App, x: u16, y: u16) {
+    let Some(target_ms) = app.seek_target_at(x, y) else { return };
+    let label = format!(" {} ", fmt_ms(target_ms));
+    let width = label.len() as u16;
+    let area = f.area();
+
+    // Above the pointer, or below it on the top row; kept inside the screen
+    let row = if y > area.y { y - 1 } else { y + 1 };
+    let col = x.saturating_sub(width / 2).min(area.right().saturating_sub(width));
+    let rect = Rect::new(col, row, width, 1).intersection(area);
+
+    f.render_widget(Clear, rect);
+    f.render_widget(
+        Paragraph::new(label).style(Style::default().fg(app.theme.base).bg(app.theme.yellow).add_modifier(Modifier::BOLD)),
+        rect,
+    );
+}
+
+/// Right-aligned footer hints, registering a hitbox for every span with an action
+fn draw_hints(f: &mut Frame, app: &mut App, area: Rect, hints: &[(Span<'static>, Option<FooterAction>)]) {
+    let line = Line::from(hints.iter().map(|(span, _)| span.clone()).collect::<Vec<_>>());
+    let mut x = area.x + area.width.saturating_sub(line.width() as u16);
+    f.render_widget(
+        Paragraph::new(line)
+            .alignment(Alignment::Right)
+            .block(Block::default().style(Style::default().bg(Color::Reset))),
+        area,
+    );
+
+    for (span, action) in hints {
+        let width = span.width() as u16;
+        if let Some(action) = action {
+            let hitbox = Rect::new(x, area.y, width, 1).intersection(area);
+            if !hitbox.is_empty() {
+                app.footer_btns.push((hitbox, *action));
+            }
+        }
+        x += width;
+    }
+}
+
+/// Typing practice: the sung line as a target, typed characters marked right/wrong
+fn draw_typing(f: &mut Frame, app: &App, lyrics: &[LyricLine], area: Rect) {
+    let theme = &app.theme;
+    let Some(typing) = &app.typing else { return };
+    let idx = typing.line_idx;
+    let target = lyrics.get(idx).map(|l| l.text.as_str()).unwrap_or_default();
+    let dim = Style::default().fg(theme.overlay).add_modifier(Modifier::DIM);
+
+    let typed_len = typing.typed.chars().count();
+    let mut target_spans: Vec<Span> = typing::marks(&typing.typed, target).into_iter()
+        .enumerate()
+        .map(|(i, (c, mark))| {
+            let style = match mark {
+                Some(true) => Style::default().fg(theme.green).add_modifier(Modifier::BOLD),
+                // Wrong spaces would be invisible without a background
+                Some(false) => Style::default().fg(theme.base).bg(theme.red),
+                None if i == typed_len => Style::default().fg(theme.text).add_modifier(Modifier::REVERSED),
+                None => Style::default().fg(theme.text),
+            };
+            Span::styled(c.to_string(), style)
+        })
+        .collect();
+    // Typed past the end of the line
+    let overflow: String = typing.typed.chars().skip(target.chars().count()).collect();
+    if !overflow.is_empty() {
+        target_spans.push(Span::styled(overflow, Style::default().fg(theme.base).bg(theme.red)));
+    }
+
+    let mut lines = Vec::new();
+    let content_height = 7u16;
+    for _ in 0..area.height.saturating_sub(content_height) / 2 {
+        lines.push(Line::default());
+    }
+    let prev = idx.checked_sub(1).and_then(|i| lyrics.get(i)).map(|l| l.text.clone()).unwrap_or_default();
+    let next = lyrics.get(idx + 1).map(|l| l.text.clone()).unwrap_or_default();
+    lines.push(Line::from(Span::styled(prev, dim)));
+    lines.push(Line::default());
+    lines.push(Line::from(target_spans));
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(next, dim)));
+    lines.push(Line::default());
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  {} WPM", glyphs::get(app.config.ui.glyphs).typing, typing.wpm(target)), Style::default().fg(theme.yellow).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" · {}% accuracy", typing.accuracy(target)), Style::default().fg(theme.cyan)),
+        Span::styled(" · Esc to stop", Style::default().fg(theme.overlay)),
+    ]));
+
+    let widget = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+    f.render_widget(widget, area);
+}
+
+/// The active line split at the singer: words already sung in `sung`, the
+/// one being sung filling up letter by letter over its time, the rest in
+/// `unsung`. A word runs until the next one starts (the last one until `end_ms`).
+fn karaoke_spans(text: &str, words: &[LyricWord], end_ms: u64, track_ms: u64, sung: Style, unsung: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut cursor = 0;
+    for (i, word) in words.iter().enumerate() {
+        // Words are in the text in order; one that isn't (edited text) is skipped
+        let Some(at) = text[cursor..].find(word.text.as_str()).map(|at| cursor + at) else { continue };
+        let gap_style = if track_ms >= word.timestamp_ms { sung } else { unsung };
+        spans.push(Span::styled(text[cursor..at].to_string(), gap_style));
+
+        let word_end = words.get(i + 1).map_or(end_ms, |next| next.timestamp_ms);
+        let window = word.timestamp_ms..word_end.max(word.timestamp_ms + 1);
+        let progress = (track_ms.saturating_sub(window.start) as f32 / (window.end - window.start) as f32).clamp(0.0, 1.0);
+        let chars = word.text.chars().count();
+        let split = word.text.char_indices().nth((chars as f32 * progress).round() as usize).map_or(word.text.len(), |(i, _)| i);
+        spans.push(Span::styled(word.text[..split].to_string(), sung));
+        spans.push(Span::styled(word.text[split..].to_string(), unsung));
+        cursor = at + word.text.len();
+    }
+    let done = words.last().is_some_and(|last| track_ms >= last.timestamp_ms);
+    spans.push(Span::styled(text[cursor..].to_string(), if done { sung } else { unsung }));
+    spans.retain(|span| !span.content.is_empty());
+    spans
+}
+
+/// Search matches are yellow, the one being viewed is also underlined
+fn search_style(app: &App, idx: usize, style: Style) -> Style {
+    let Some(search) = &app.lyrics_search else { return style };
+    match search.matches.iter().position(|&m| m == idx) {
+        Some(n) if n == search.current => style.fg(app.theme.yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        Some(_) => style.fg(app.theme.yellow),
+        None => style,
+    }
+}
+
+/// Thumb-only vertical scrollbar drawn onto a bordered block's right edge,
+/// so the border itself acts as the track.
+fn draw_scrollbar(f: &mut Frame, theme: &Theme, block_area: Rect, total: usize, position: usize) {
+    let area = block_area.inner(Margin { vertical: 1, horizontal: 0 });
+    let mut state = ScrollbarState::new(total).position(position);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_symbol(None)
+        .thumb_symbol("┃")
+        .thumb_style(Style::default().fg(theme.magenta));
+    f.render_stateful_widget(scrollbar, area, &mut state);
+}
+
+/// Where each part of the music card goes (None = not shown in this layout)
+#[derive(Default)]
+struct CardAreas {
+    artwork: Option<Rect>,
+    info: Option<Rect>,
+    visualizer: Option<Rect>,
+    widget: Option<Rect>,
+    gauge: Option<Rect>,
+    time: Option<Rect>,
+    controls: Option<Rect>,
+    /// Tight button spacing (ultra-compact layout)
+    compact: bool,
+}
+
+/// One row of a stacked card layout
+#[derive(Clone, Copy, PartialEq)]
+enum Slot {
+    Artwork,
+    Info,
+    Visualizer,
+    /// Spacer row, doubling as the plugin widget line
+    Widget,
+    Gauge,
+    Time,
+    Controls,
+    Pad,
+}
+
+/// Split `area` top to bottom into `slots` and note where each part landed
+fn stack(area: Rect, slots: &[(Slot, Constraint)]) -> CardAreas {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(slots.iter().map(|(_, c)| *c))
+        .split(area);
+
+    let mut card = CardAreas::default();
+    for ((slot, _), rect) in slots.iter().zip(chunks.iter().copied()) {
+        let target = match slot {
+            Slot::Artwork => &mut card.artwork,
+            Slot::Info => &mut card.info,
+            Slot::Visualizer => &mut card.visualizer,
+            Slot::Widget => &mut card.widget,
+            Slot::Gauge => &mut card.gauge,
+            Slot::Time => &mut card.time,
+            Slot::Controls => &mut card.controls,
+            Slot::Pad => continue,
+        };
+        *target = Some(rect);
+    }
+    card
+}
+
+/// Pick the card layout from the inner height:
+/// - normal: big artwork, visualizer, spacers
+/// - cramped (< `cramped_below_rows`): no visualizer or spacers
+/// - compact (< `compact_below_rows`): thumbnail beside the info, time and
+///   controls merged into one row
+///
+/// Parts hidden in `[ui]` are left out and the rest is centered in the freed space.
+fn card_layout(app: &App, area: Rect) -> CardAreas {
+    let ui = &app.config.ui;
+
+    // Terminal cells are about twice as tall as wide, so 4:1 in cells is a wide card
+    let side = ui.show_artwork && match ui.card_layout {
+        CardLayout::Side => true,
+        CardLayout::Stacked => false,
+        CardLayout::Auto => area.width >= area.height * 4 && area.height >= 8,
+    };
+    if side {
+        return side_layout(app, area);
+    }
+
+    if area.height < ui.compact_below_rows {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),    // 0: Thumbnail | Info
+                Constraint::Length(1), // 1: Gauge
+                Constraint::Length(1), // 2: Time | Controls
+            ])
+            .split(area);
+        // Half-block pixels are square at 2 columns per row
+        let thumb_width = if ui.show_artwork { (rows[0].height * 2).min(area.width / 3) } else { 0 };
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(thumb_width), Constraint::Length(thumb_width.min(1)), Constraint::Min(0)])
+            .split(rows[0]);
+        let controls_width = if ui.show_controls { controls_width(glyphs::get(ui.glyphs), true) } else { 0 };
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(controls_width)])
+            .split(rows[2]);
+        return CardAreas {
+            artwork: ui.show_artwork.then_some(top[0]),
+            info: Some(top[2]),
+            gauge: Some(rows[1]),
+            time: Some(bottom[0]),
+            controls: ui.show_controls.then_some(bottom[1]),
+            compact: true,
+            ..Default::default()
+        };
+    }
+
+    let cramped = area.height < ui.cramped_below_rows;
+    let mut slots = Vec::new();
+    if ui.show_artwork {
+        // Takes all the space the rest doesn't need (shrinkable when cramped)
+        slots.push((Slot::Artwork, Constraint::Min(if cramped { 10 } else { 20 })));
+    } else {
+        slots.push((Slot::Pad, Constraint::Fill(1)));
+    }
+    slots.push((Slot::Info, Constraint::Length(4)));
+    if !cramped && ui.show_visualizer {
+        slots.push((Slot::Visualizer, Constraint::Length(2)));
+    }
+    slots.push((Slot::Gauge, Constraint::Length(1)));
+    slots.push((Slot::Time, Constraint::Length(1)));
+    if !cramped {
+        slots.push((Slot::Widget, Constraint::Length(1)));
+    }
+    if ui.show_controls {
+        slots.push((Slot::Controls, Constraint::Length(1)));
+    }
+    if !ui.show_artwork {
+        slots.push((Slot::Pad, Constraint::Fill(1)));
+    } else if !cramped {
+        slots.push((Slot::Pad, Constraint::Length(1))); // Bottom Padding
+    }
+
+    let mut card = stack(area, &slots);
+    // 1 line of padding at the top of the artwork to separate it from the title (Vyom)
+    card.artwork = card.artwork.map(|art| if art.height > 2 {
+        Rect { y: art.y + 1, height: art.height - 1, ..art }
+    } else {
+        art
+    });
+    card
+}
+
+/// Artwork on the left, info/progress/controls stacked and vertically centered on the right
+fn side_layout(app: &App, area: Rect) -> CardAreas {
+    let ui = &app.config.ui;
+    let art_width = (area.height * 2).min(area.width / 2);
+    let columns = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Min(20),    // Left: Main Controls
-            Constraint::Length(12), // Right: Volume Control
-        ])
-        .split(footer_area);
+        .constraints([Constraint::Length(art_width), Constraint::Length(2), Constraint::Min(0)])
+        .split(area);
 
-    let left_footer_text = Line::from(vec![
-        Span::styled(" q ", Style::default().fg(theme.red).add_modifier(Modifier::BOLD)), 
-        Span::styled("Exit   ", desc_style),
-        
-        Span::styled(" n ", Style::default().fg(theme.blue).add_modifier(Modifier::BOLD)), 
-        Span::styled("Next   ", desc_style),
-        
-        Span::styled(" p ", Style::default().fg(theme.blue).add_modifier(Modifier::BOLD)), 
-        Span::styled("Prev   ", desc_style),
-        
-        Span::styled(" Space ", Style::default().fg(theme.green).add_modifier(Modifier::BOLD)), 
-        Span::styled("Play/Pause", desc_style),
+    let mut slots = vec![
+        (Slot::Pad, Constraint::Fill(1)),
+        (Slot::Info, Constraint::Length(4)),
+    ];
+    // The visualizer only fits when there's height to spare
+    if ui.show_visualizer && area.height >= 12 {
+        slots.push((Slot::Visualizer, Constraint::Length(2)));
+    }
+    slots.push((Slot::Gauge, Constraint::Length(1)));
+    slots.push((Slot::Time, Constraint::Length(1)));
+    if ui.show_controls {
+        slots.push((Slot::Pad, Constraint::Length(1)));
+        slots.push((Slot::Controls, Constraint::Length(1)));
+    }
+    slots.push((Slot::Pad, Constraint::Fill(1)));
+
+    CardAreas {
+        artwork: Some(columns[0]),
+        ..stack(columns[2], &slots)
+    }
+}
+
+/// Button padding and spacing for the ⏮ ⏯ ⏭ row: tight in compact mode
+fn controls_spacing(compact: bool) -> (usize, usize) {
+    if compact { (1, 1) } else { (3, 3) }
+}
+
+/// Columns the transport row needs (ASCII glyphs are wider than the icons)
+fn controls_width(glyphs: &Glyphs, compact: bool) -> u16 {
+    let (pad, gap) = controls_spacing(compact);
+    let width = |icon: &str| Span::raw(icon).width();
+    let icons_width = width(glyphs.prev) + width(glyphs.play).max(width(glyphs.pause)) + width(glyphs.next);
+    (icons_width + pad * 6 + gap * 2) as u16
+}
+
+/// Transport buttons centered in `area`, registering their click hitboxes
+fn draw_controls(f: &mut Frame, app: &mut App, area: Rect, playing: bool, compact: bool) {
+    let theme = &app.theme;
+    let glyphs = glyphs::get(app.config.ui.glyphs);
+    let play_icon = if playing { glyphs.pause } else { glyphs.play };
+    let btn_style = Style::default().fg(theme.text).add_modifier(Modifier::BOLD);
+
+    // Each button is the icon with `pad` spaces either side, `gap` between buttons
+    let (pad, gap) = controls_spacing(compact);
+    let buttons = [glyphs.prev, play_icon, glyphs.next].map(|icon| format!("{0}{1}{0}", " ".repeat(pad), icon));
+    let widths = buttons.clone().map(|b| Span::raw(b).width() as u16);
+    let mut spans = vec![
+        Span::styled(buttons[0].clone(), btn_style),
+        Span::raw(" ".repeat(gap)),
+        Span::styled(buttons[1].clone(), btn_style),
+        Span::raw(" ".repeat(gap)),
+        Span::styled(buttons[2].clone(), btn_style),
+    ];
+    let buttons_width = widths.iter().sum::<u16>() + gap as u16 * 2;
+
+    // Shuffle left of ⏮ and repeat right of ⏭, lit while on. Both slots get the
+    // same width so the buttons stay centered, and they only show where they fit.
+    let shuffle = app.shuffle.map(|on| (glyphs.shuffle, on));
+    let repeat = app.repeat.map(|repeat| {
+        let icon = if repeat == Repeat::Track { glyphs.repeat_one } else { glyphs.repeat };
+        (icon, repeat != Repeat::Off)
+    });
+    let side = [shuffle, repeat].iter().flatten().map(|(icon, _)| Span::raw(*icon).width()).max().unwrap_or(0);
+    let with_sides = buttons_width as usize + (side + gap) * 2;
+    let side = if side > 0 && with_sides <= area.width as usize { side } else { 0 };
+    let mode = |slot: Option<(&'static str, bool)>, right: bool| {
+        let (icon, on) = slot.unwrap_or(("", false));
+        let style = if on { Style::default().fg(theme.green).add_modifier(Modifier::BOLD) } else { Style::default().fg(theme.overlay) };
+        let fill = " ".repeat(side - Span::raw(icon).width());
+        let text = if right { format!("{}{}", icon, fill) } else { format!("{}{}", fill, icon) };
+        Span::styled(text, style)
+    };
+    let side_width = if side > 0 {
+        spans.insert(0, Span::raw(" ".repeat(gap)));
+        spans.insert(0, mode(shuffle, false));
+        spans.push(Span::raw(" ".repeat(gap)));
+        spans.push(mode(repeat, true));
+        (side + gap) as u16
+    } else {
+        0
+    };
+
+    let gap = gap as u16;
+    let total = buttons_width + side_width * 2;
+    let row_x = area.x + area.width.saturating_sub(total) / 2;
+    let row = Rect::new(row_x, area.y, total.min(area.width), 1);
+    f.render_widget(Paragraph::new(Line::from(spans)), row);
+    let x = row_x + side_width;
+
+    app.prev_btn = Rect::new(x, area.y, widths[0], 1);
+    app.play_btn = Rect::new(x + widths[0] + gap, area.y, widths[1], 1);
+    app.next_btn = Rect::new(x + widths[0] + widths[1] + gap * 2, area.y, widths[2], 1);
+}
+
+/// Breadcrumb bar: where the track plays from on the left, status on the right
+fn draw_header(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let sep = Span::styled(" › ", Style::default().fg(theme.overlay));
+
+    let mut left = vec![Span::raw(" ")];
+    match &app.track {
+        Some(track) => {
+            let glyphs = glyphs::get(app.config.ui.glyphs);
+            let icon = if track.source == "Spotify" { glyphs.spotify } else { glyphs.music };
+            left.push(Span::styled(format!("{} {}", icon, track.source), Style::default().fg(theme.blue).add_modifier(Modifier::BOLD)));
+            if app.private_session {
+                left.push(Span::styled(format!(" {} private", glyphs.private), Style::default().fg(theme.overlay).add_modifier(Modifier::ITALIC)));
+            }
+            if power::current() == PowerProfile::Battery {
+                left.push(Span::styled(format!(" {} battery saver", glyphs.battery), Style::default().fg(theme.overlay).add_modifier(Modifier::ITALIC)));
+            }
+            if let Some(loudness) = loudness_span(app) {
+                left.push(Span::raw(" "));
+                left.push(loudness);
+            }
+            if let Some(context) = app.context_name() {
+                left.push(sep.clone());
+                left.push(Span::styled(context.to_string(), Style::default().fg(theme.text)));
+            }
+            left.push(sep);
+            left.push(Span::styled(track.album.clone(), Style::default().fg(theme.overlay)));
+        }
+        None => left.push(Span::styled("No player", Style::default().fg(theme.overlay))),
+    }
+
+    let mut right = Vec::new();
+    if let Some(device) = &app.output_device {
+        right.push(Span::styled(format!("{} {}  ", glyphs::get(app.config.ui.glyphs).output, device), Style::default().fg(theme.overlay)));
+    }
+    // Scrobbler: off (no account), sending, or plays waiting for the network
+    let scrobble = glyphs::get(app.config.ui.glyphs).scrobble;
+    right.push(match (scrobble::Services::from_config(&app.config), scrobble::queued()) {
+        (None, _) => Span::styled(format!("{} off  ", scrobble), Style::default().fg(theme.overlay).add_modifier(Modifier::DIM)),
+        (Some(_), 0) => Span::styled(format!("{} ok  ", scrobble), Style::default().fg(theme.overlay)),
+        (Some(_), queued) => Span::styled(format!("{} {} queued  ", scrobble, queued), Style::default().fg(theme.yellow)),
+    });
+    right.push(if app.network_down {
+        Span::styled(format!("{} no network ", glyphs::get(app.config.ui.glyphs).warning), Style::default().fg(theme.yellow).add_modifier(Modifier::DIM))
+    } else if app.config.offline {
+        Span::styled("○ offline ", Style::default().fg(theme.yellow))
+    } else {
+        Span::styled("● online ", Style::default().fg(theme.green))
+    });
+    let right = Line::from(right);
+    let right_width = right.width() as u16;
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(right_width)])
+        .split(area);
+    f.render_widget(Paragraph::new(Line::from(left)), chunks[0]);
+    f.render_widget(Paragraph::new(right).alignment(Alignment::Right), chunks[1]);
+}
+
+/// Spotify's loudness for the track, and a warning when the client plays it
+/// without normalization (so it won't sit level with other sources)
+fn loudness_span(app: &App) -> Option<Span<'static>> {
+    let theme = &app.theme;
+    let loudness = app.loudness_db.map(|db| format!("{:.1} dB", db));
+    if app.normalization_off {
+        let warning = glyphs::get(app.config.ui.glyphs).warning;
+        let text = match loudness {
+            Some(loudness) => format!("{} {} · normalization off", warning, loudness),
+            None => format!("{} normalization off", warning),
+        };
+        return Some(Span::styled(text, Style::default().fg(theme.yellow)));
+    }
+    Some(Span::styled(loudness?, Style::default().fg(theme.overlay)))
+}
+
+pub fn fmt_ms(ms: u64) -> String {
+    if ms >= 3_600_000 {
+        return format!("{}:{:02}:{:02}", ms / 3_600_000, (ms % 3_600_000) / 60000, (ms % 60000) / 1000);
+    }
+    format!("{:02}:{:02}", ms / 60000, (ms % 60000) / 1000)
+}
+
+/// Expand a footer template. `{title}`, `{artist}`, `{album}`, `{position}`,
+/// `{duration}`, `{volume}`, `{state}` and `{source}` are replaced with track
+/// data. `[red]`...`[/]` colors text with theme colors (`[bold]` also works).
+/// Unknown placeholders and tags are kept as written.
+fn render_template(template: &str, app: &App) -> Line<'static> {
+    let theme = &app.theme;
+    let base = Style::default().fg(theme.overlay);
+    let track = app.track.as_ref();
+
+    let mut spans = Vec::new();
+    let mut style = base;
+    let mut buf = String::new();
+    let mut rest = template;
+
+    while let Some(c) = rest.chars().next() {
+        let close = match c {
+            '{' => rest.find('}'),
+            '[' => rest.find(']'),
+            _ => None,
+        };
+        let Some(end) = close else {
+            buf.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        let name = &rest[1..end];
+
+        if c == '{' {
+            let value = match (name, track) {
+                ("title", Some(t)) => Some(t.name.clone()),
+                ("artist", Some(t)) => Some(t.artist.clone()),
+                ("album", Some(t)) => Some(t.album.clone()),
+                ("source", Some(t)) => Some(t.source.clone()),
+                ("position", Some(_)) => Some(fmt_ms(app.clock.position_ms())),
+                ("duration", Some(_)) => Some(fmt_ms(app.clock.duration_ms())),
+                ("volume", Some(t)) => Some(t.volume.map(|v| format!("{}%", v)).unwrap_or_else(|| "--".to_string())),
+                ("state", Some(t)) => Some(glyphs::get(app.config.ui.glyphs).state(&t.state).to_string()),
+                ("title" | "artist" | "album" | "source" | "position" | "duration" | "volume" | "state", None) => Some(String::new()),
+                _ => None,
+            };
+            match value {
+                Some(v) => buf.push_str(&v),
+                None => buf.push_str(&rest[..=end]),
+            }
+        } else {
+            let new_style = match name {
+                "/" => Some(base),
+                "bold" => Some(style.add_modifier(Modifier::BOLD)),
+                "red" => Some(style.fg(theme.red)),
+                "green" => Some(style.fg(theme.green)),
+                "yellow" => Some(style.fg(theme.yellow)),
+                "blue" => Some(style.fg(theme.blue)),
+                "magenta" => Some(style.fg(theme.magenta)),
+                "cyan" => Some(style.fg(theme.cyan)),
+                "text" => Some(style.fg(theme.text)),
+                "overlay" => Some(style.fg(theme.overlay)),
+                _ => None,
+            };
+            match new_style {
+                Some(next) => {
+                    if !buf.is_empty() {
+                        spans.push(Span::styled(std::mem::take(&mut buf), style));
+                    }
+                    style = next;
+                }
+                None => buf.push_str(&rest[..=end]),
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    if !buf.is_empty() {
+        spans.push(Span::styled(buf, style));
+    }
+    Line::from(spans)
+}
+
+/// Modal shown when macOS refuses to let us script the player (error -1743)
+/// Small centered "Quit Vyom?" box (`[quit] confirm`)
+fn draw_quit_prompt(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = f.area();
+    let width = 34.min(area.width);
+    let height = 4.min(area.height);
+    let dialog = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Line::from(Span::styled(" Quit Vyom? ", Style::default().fg(theme.base).bg(theme.red).add_modifier(Modifier::BOLD))).centered())
+        .border_style(Style::default().fg(theme.red))
+        .style(Style::default().bg(theme.base));
+    let inner = block.inner(dialog);
+    f.render_widget(Clear, dialog);
+    f.render_widget(block, dialog);
+
+    let mut text = vec![Line::from(vec![
+        Span::styled(" y ", Style::default().fg(theme.red).add_modifier(Modifier::BOLD)),
+        Span::styled("quit   ", Style::default().fg(theme.overlay)),
+        Span::styled(" any key ", Style::default().fg(theme.green).add_modifier(Modifier::BOLD)),
+        Span::styled("stay", Style::default().fg(theme.overlay)),
+    ])];
+    if app.config.quit.pause_playback {
+        text.push(Line::from(Span::styled("playback will pause", Style::default().fg(theme.overlay))));
+    }
+    f.render_widget(Paragraph::new(text).alignment(Alignment::Center), inner);
+}
+
+/// "Resume from 1:12:33?" for a long track that started over
+/// `O`: output devices (then Spotify Connect ones), the one playing marked, the
+/// chosen one highlighted
+fn draw_output_picker(f: &mut Frame, app: &App, picker: &OutputPicker) {
+    let theme = &app.theme;
+    let label = Style::default().fg(theme.overlay);
+    let glyphs = glyphs::get(app.config.ui.glyphs);
+
+    let lines: Vec<Line> = match &picker.devices {
+        None => vec![Line::from(Span::styled("Looking for devices…", label)).centered()],
+        Some(devices) if devices.is_empty() => vec![Line::from(Span::styled("No output devices found", label)).centered()],
+        Some(devices) => devices.iter().enumerate().map(|(i, device)| {
+            let playing = if device.connect { device.active } else { app.output_device.as_ref() == Some(&device.name) };
+            let marker = if playing { glyphs.output } else { " " };
+            // Connect devices take Spotify's playback, not the system's sound
+            let name = if device.connect { format!("{} · Spotify", device.name) } else { device.name.clone() };
+            let style = if i == picker.selected {
+                Style::default().fg(theme.base).bg(theme.blue).add_modifier(Modifier::BOLD)
+            } else if playing {
+                Style::default().fg(theme.blue)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            Line::from(vec![Span::raw(" "), Span::styled(format!(" {} {} ", marker, name), style)])
+        }).collect(),
+    };
+
+    let area = f.area();
+    let width = 44.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let card = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Line::from(Span::styled(" Play through ", Style::default().fg(theme.base).bg(theme.blue).add_modifier(Modifier::BOLD))).centered())
+        .title_bottom(Line::from(Span::styled(" ↑↓ choose · Enter switch · Esc close ", label)).centered())
+        .border_style(Style::default().fg(theme.blue))
+        .style(Style::default().bg(theme.base));
+    f.render_widget(Clear, card);
+    f.render_widget(Paragraph::new(lines).block(block), card);
+}
+
+/// Up next (`Q`), the picked track highlighted while the pane has focus
+fn draw_queue(f: &mut Frame, app: &App, queue: &QueuePane, area: Rect, border: BorderType) {
+    let theme = &app.theme;
+    let label = Style::default().fg(theme.overlay);
+    let focused = app.focused() == Focus::Queue;
+    let inner_height = area.height.saturating_sub(2) as usize;
+
+    let lines: Vec<Line> = match &queue.tracks {
+        None => vec![Line::from(Span::styled("Asking the player…", label)).centered()],
+        Some(Err(e)) => vec![Line::from(Span::styled(e.clone(), label)).centered()],
+        Some(Ok(tracks)) if tracks.is_empty() => vec![Line::from(Span::styled("Nothing queued", label)).centered()],
+        Some(Ok(tracks)) => {
+            // Keep the picked one in view
+            let first = queue.selected.saturating_sub(inner_height.saturating_sub(1));
+            tracks.iter().enumerate().skip(first).take(inner_height).map(|(i, track)| {
+                let style = if focused && i == queue.selected {
+                    Style::default().fg(theme.base).bg(theme.blue).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                Line::from(vec![
+                    Span::styled(format!(" {:>2} ", i + 1), label),
+                    Span::styled(format!("{} ", track.name), style),
+                    Span::styled(format!("· {}  ", track.artist), Style::default().fg(theme.overlay)),
+                    Span::styled(fmt_ms(track.duration_ms), label),
+                ])
+            }).collect()
+        }
+    };
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(border)
+        .title(Line::from(Span::styled(" Up next ", Style::default().fg(theme.base).bg(theme.blue).add_modifier(Modifier::BOLD))).centered())
+        .border_style(Style::default().fg(theme.blue))
+        .style(Style::default().bg(Color::Reset));
+    if focused {
+        block = block.title_bottom(Line::from(Span::styled(" ↑↓ pick · Enter skip to it · Esc close ", label)).centered());
+    }
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Today's listening as a card (`R`, or by itself at `[history] recap_at`)
+fn draw_recap(f: &mut Frame, app: &App, recap: &Summary) {
+    let theme = &app.theme;
+    let label = Style::default().fg(theme.overlay);
+    let big = Style::default().fg(theme.green).add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![
+        Line::default(),
+        Line::from(Span::styled(history::fmt_duration(recap.listened_ms), big)).centered(),
+        Line::from(Span::styled("of music today", label)).centered(),
+        Line::default(),
+        Line::from(vec![
+            Span::styled(recap.listens.to_string(), Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
+            Span::styled(" plays · ", label),
+            Span::styled(recap.distinct_tracks.to_string(), Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
+            Span::styled(" tracks", label),
+        ]).centered(),
+        Line::default(),
+    ];
+    if let Some((artist, plays)) = recap.top_artists.first() {
+        lines.push(Line::from(Span::styled("Top artist", label)).centered());
+        lines.push(Line::from(vec![
+            Span::styled(artist.clone(), Style::default().fg(theme.magenta).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  {} plays", plays), label),
+        ]).centered());
+        lines.push(Line::default());
+    }
+    // Only worth calling "on repeat" if it actually came back
+    if let Some((title, artist, plays)) = recap.top_tracks.first().filter(|t| t.2 > 1) {
+        lines.push(Line::from(Span::styled("On repeat", label)).centered());
+        lines.push(Line::from(Span::styled(title.clone(), Style::default().fg(theme.blue).add_modifier(Modifier::BOLD))).centered());
+        lines.push(Line::from(vec![
+            Span::styled(artist.clone(), Style::default().fg(theme.text)),
+            Span::styled(format!("  ×{}", plays), Style::default().fg(theme.yellow)),
+        ]).centered());
+        lines.push(Line::default());
+    }
+    if recap.listens == 0 {
+        lines = vec![Line::default(), Line::from(Span::styled("Nothing played yet today", label)).centered(), Line::default()];
+    }
+
+    let area = f.area();
+    let width = 46.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let card = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Line::from(Span::styled(" Today in Vyom ", Style::default().fg(theme.base).bg(theme.green).add_modifier(Modifier::BOLD))).centered())
+        .title_bottom(Line::from(Span::styled(" R / Esc close ", label)).centered())
+        .border_style(Style::default().fg(theme.green))
+        .style(Style::default().bg(theme.base));
+    f.render_widget(Clear, card);
+    f.render_widget(Paragraph::new(lines).block(block), card);
+}
+
+/// F12: the app's own resource usage, top right over everything but dialogs
+fn draw_debug_overlay(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let label = Style::default().fg(theme.overlay);
+    let value = Style::default().fg(theme.text);
+    let row = |name: &str, text: String| Line::from(vec![
+        Span::styled(format!("{:<9}", name), label),
+        Span::styled(text, value),
     ]);
-    
-    let left_footer = Paragraph::new(left_footer_text)
-        .alignment(Alignment::Right)
-        .block(Block::default().style(Style::default().bg(Color::Reset)));
-    f.render_widget(left_footer, footer_chunks[0]);
-
-    let right_footer_text = Line::from(vec![
-        Span::styled(" +/- ", Style::default().fg(theme.yellow).add_modifier(Modifier::BOLD)), 
-        Span::styled("Vol ", desc_style),
+    let ms = |d: std::time::Duration| format!("{:.1} ms", d.as_secs_f64() * 1000.0);
+    let mb = |bytes: u64| format!("{:.1} MB", bytes as f64 / 1_048_576.0);
+
+    let mut lines = vec![
+        row("frame", ms(stats::frame_time())),
+        row("poll", ms(stats::poll_latency())),
+        row("loaded", format!("{} lyrics", app.lyrics_cache.len())),
+    ];
+    match &app.debug_sample {
+        Some(sample) => {
+            lines.push(row("events", format!("{}/s, {} dropped", sample.events_per_sec, stats::dropped())));
+            lines.push(row("cpu", sample.cpu_percent.map_or("?".to_string(), |cpu| format!("{:.1}%", cpu))));
+            lines.push(row("memory", sample.rss_kb.map_or("?".to_string(), |kb| mb(kb * 1024))));
+            lines.push(row("tasks", sample.tasks.to_string()));
+            for (name, files, bytes) in &sample.disk_caches {
+                lines.push(row(name, format!("{} files, {}", files, mb(*bytes))));
+            }
+        }
+        None => lines.push(Line::from(Span::styled("sampling...", label))),
+    }
+
+    let area = f.area();
+    let width = 32.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let overlay = Rect::new(area.x + area.width - width, area.y, width, height);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(" Debug (F12) ", Style::default().fg(theme.magenta).add_modifier(Modifier::BOLD)))
+        .border_style(Style::default().fg(theme.magenta))
+        .style(Style::default().bg(theme.base));
+    f.render_widget(Clear, overlay);
+    f.render_widget(Paragraph::new(lines).block(block), overlay);
+}
+
+fn draw_resume_prompt(f: &mut Frame, app: &App, position_ms: u64) {
+    let theme = &app.theme;
+    let area = f.area();
+    let width = 34.min(area.width);
+    let height = 3.min(area.height);
+    let dialog = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let title = format!(" Resume from {}? ", fmt_ms(position_ms));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Line::from(Span::styled(title, Style::default().fg(theme.base).bg(theme.green).add_modifier(Modifier::BOLD))).centered())
+        .border_style(Style::default().fg(theme.green))
+        .style(Style::default().bg(theme.base));
+    let inner = block.inner(dialog);
+    f.render_widget(Clear, dialog);
+    f.render_widget(block, dialog);
+
+    let text = Line::from(vec![
+        Span::styled(" y ", Style::default().fg(theme.green).add_modifier(Modifier::BOLD)),
+        Span::styled("resume   ", Style::default().fg(theme.overlay)),
+        Span::styled(" n ", Style::default().fg(theme.yellow).add_modifier(Modifier::BOLD)),
+        Span::styled("start over", Style::default().fg(theme.overlay)),
     ]);
+    f.render_widget(Paragraph::new(text).alignment(Alignment::Center), inner);
+}
+
+fn draw_permission_dialog(f: &mut Frame, app: &mut App, player: &str) {
+    let theme = &app.theme;
+    let area = f.area();
+    let width = 60.min(area.width);
+    let height = 10.min(area.height);
+    let dialog = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Line::from(Span::styled(" Automation Permission ", Style::default().fg(theme.base).bg(theme.red).add_modifier(Modifier::BOLD))).centered())
+        .border_style(Style::default().fg(theme.red))
+        .style(Style::default().bg(theme.base));
+    let inner = block.inner(dialog);
+    f.render_widget(Clear, dialog);
+    f.render_widget(block, dialog);
+
+    let text = vec![
+        Line::from(Span::styled(format!("Vyom needs permission to control {}.", player), Style::default().fg(theme.text).add_modifier(Modifier::BOLD))),
+        Line::default(),
+        Line::from(Span::styled("macOS only asks once. If it was refused, allow your terminal to control it in", Style::default().fg(theme.overlay))),
+        Line::from(Span::styled(format!("System Settings → Privacy & Security → Automation → {}.", player), Style::default().fg(theme.overlay))),
+    ];
+    f.render_widget(Paragraph::new(text).alignment(Alignment::Center).wrap(Wrap { trim: true }), inner);
+
+    // Button on the last row, Esc hint next to it
+    if inner.height == 0 {
+        return;
+    }
+    let label = " Grant Access ⏎ ";
+    let hint = "  Esc dismiss";
+    let btn_width = (label.chars().count() as u16).min(inner.width);
+    let row_width = (btn_width + hint.chars().count() as u16).min(inner.width);
+    let y = inner.y + inner.height - 1;
+    let x = inner.x + (inner.width - row_width) / 2;
+    app.permission_btn = Rect::new(x, y, btn_width, 1);
+
+    let row = Line::from(vec![
+        Span::styled(label, Style::default().fg(theme.base).bg(theme.green).add_modifier(Modifier::BOLD)),
+        Span::styled(hint, Style::default().fg(theme.overlay)),
+    ]);
+    f.render_widget(Paragraph::new(row), Rect::new(x, y, row_width, 1));
+}
+
+/// Two-row bar visualizer. Bars are synthetic (see `App::tick_visualizer`) and
+/// follow the player state: they collapse to a flat, dimmed line when paused.
+/// Color of column `x` of `width` along the artwork gradient, if there is one
+fn album_gradient(app: &App, x: usize, width: usize) -> Option<Color> {
+    if !app.config.ui.album_colors {
+        return None;
+    }
+    let (from, to) = app.album_colors?;
+    let (r, g, b) = blend(from, to, x as f32 / width.saturating_sub(1).max(1) as f32);
+    Some(Color::Rgb(r, g, b))
+}
+
+fn draw_visualizer(f: &mut Frame, app: &App, area: Rect) {
+    const BLOCKS: [&str; 9] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+    let theme = &app.theme;
+    let width = area.width as usize;
+    let rows = area.height as usize;
+    if width == 0 || rows == 0 || app.visualizer_bars.is_empty() {
+        return;
+    }
+
+    let is_playing = app.track.as_ref().map(|t| t.state == PlayerState::Playing).unwrap_or(false);
+    let style = if is_playing {
+        Style::default().fg(theme.magenta)
+    } else {
+        Style::default().fg(theme.surface).add_modifier(Modifier::DIM)
+    };
+
+    // Eighths of a cell per column, never below 1 so a flat line stays visible
+    let heights: Vec<usize> = (0..width).map(|x| {
+        let bar = app.visualizer_bars[x * app.visualizer_bars.len() / width];
+        ((bar.clamp(0.0, 1.0) * (rows * 8) as f32).round() as usize).max(1)
+    }).collect();
+
+    // Album colors: one span per column along the gradient (paused bars stay grey)
+    let gradient = is_playing && album_gradient(app, 0, width).is_some();
+
+    let lines: Vec<Line> = (0..rows).map(|row| {
+        // Row 0 is the top; each row covers 8 eighths
+        let floor = (rows - 1 - row) * 8;
+        let block = |h: &usize| BLOCKS[h.saturating_sub(floor).min(8)];
+        if gradient {
+            Line::from(heights.iter().enumerate().map(|(x, h)| {
+                Span::styled(block(h), style.fg(album_gradient(app, x, width).unwrap_or(theme.magenta)))
+            }).collect::<Vec<_>>())
+        } else {
+            Line::from(Span::styled(heights.iter().map(block).collect::<String>(), style))
+        }
+    }).collect();
 
-    let right_footer = Paragraph::new(right_footer_text)
-        .alignment(Alignment::Right)
-        .block(Block::default().style(Style::default().bg(Color::Reset)));
-    f.render_widget(right_footer, footer_chunks[1]);
+    f.render_widget(Paragraph::new(lines), area);
 }
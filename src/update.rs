@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::hash;
+
+// Self-update ⬆️
+// `vyom update` asks GitHub for the latest release, downloads the binary built
+// for this platform (`vyom-<arch>-<os>`, e.g. vyom-aarch64-macos), checks it
+// against the `.sha256` file published next to it and swaps it in for the
+// running one. `--check` only says whether there is a newer version.
+// Installs managed by Homebrew are left to `brew upgrade`.
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/MrSyr3x/vyom/releases/latest";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// The release asset built for this machine
+fn asset_name() -> String {
+    let exe = if cfg!(windows) { ".exe" } else { "" };
+    format!("vyom-{}-{}{}", std::env::consts::ARCH, std::env::consts::OS, exe)
+}
+
+/// "v1.2.3" / "1.2.3" as numbers; anything after a `-` (pre-releases) is ignored
+fn parse_version(version: &str) -> Vec<u64> {
+    let version = version.trim_start_matches('v');
+    let version = version.split('-').next().unwrap_or_default();
+    version.split('.').map(|part| part.parse().unwrap_or(0)).collect()
+}
+
+pub async fn run_cli(args: &[String], client: &Client) -> Result<()> {
+    let check_only = args.iter().any(|a| a == "--check");
+    let current = env!("CARGO_PKG_VERSION");
+
+    let release: Release = client.get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send().await
+        .and_then(|resp| resp.error_for_status())
+        .context("Couldn't reach GitHub releases")?
+        .json().await?;
+    let latest = release.tag_name.trim_start_matches('v');
+    if parse_version(latest) <= parse_version(current) {
+        println!("vyom {} is the latest version", current);
+        return Ok(());
+    }
+    if check_only {
+        println!("vyom {} is out (you have {}); `vyom update` installs it", latest, current);
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe()?.canonicalize()?;
+    if exe.components().any(|c| c.as_os_str() == "Cellar") {
+        anyhow::bail!("This vyom was installed with Homebrew: `brew upgrade vyom` updates it");
+    }
+    let name = asset_name();
+    let find = |name: &str| release.assets.iter().find(|asset| asset.name == name);
+    let binary = find(&name)
+        .with_context(|| format!("Release {} has no build for this platform ({})", release.tag_name, name))?;
+    let checksum = find(&format!("{}.sha256", name))
+        .with_context(|| format!("Release {} publishes no checksum for {}", release.tag_name, name))?;
+
+    println!("Downloading vyom {}...", latest);
+    let expected = client.get(&checksum.browser_download_url).send().await?.error_for_status()?.text().await?;
+    // "<hash>  <file>", as sha256sum and shasum write it
+    let expected = expected.split_whitespace().next().unwrap_or_default().to_lowercase();
+    let bytes = client.get(&binary.browser_download_url).send().await?.error_for_status()?.bytes().await?;
+    let actual = hash::hex(&hash::sha256(&bytes));
+    if actual != expected {
+        anyhow::bail!("Checksum mismatch for {} (expected {}, got {}); nothing was changed", name, expected, actual);
+    }
+
+    replace(&exe, &bytes).with_context(|| format!("Couldn't replace {}", exe.display()))?;
+    println!("Updated vyom {} -> {}", current, latest);
+    Ok(())
+}
+
+/// Write the new binary beside the old one, then rename it over it, so a
+/// failure halfway never leaves a broken `vyom`
+fn replace(exe: &Path, bytes: &[u8]) -> Result<()> {
+    let staged = sibling(exe, "new");
+    fs::write(&staged, bytes)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+    // Windows won't overwrite a running .exe, but will rename it out of the way
+    #[cfg(windows)]
+    {
+        let old = sibling(exe, "old");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old)?;
+    }
+    if let Err(e) = fs::rename(&staged, exe) {
+        let _ = fs::remove_file(&staged);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+fn sibling(exe: &Path, extension: &str) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    exe.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tags() {
+        assert_eq!(parse_version("v1.2.3"), [1, 2, 3]);
+        assert_eq!(parse_version("1.2.3"), [1, 2, 3]);
+        assert_eq!(parse_version("v2.0.0-beta.1"), [2, 0, 0]);
+    }
+
+    #[test]
+    fn compares_as_numbers() {
+        assert!(parse_version("v1.10.0") > parse_version("1.9.9"));
+        assert!(parse_version("v1.0.2") > parse_version("1.0.1"));
+        assert!(parse_version("v1.1.0-rc.1") <= parse_version("1.1.0"));
+    }
+}